@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-#[tracy_gizmos::capture]
+#[tracy_gizmos::capture(connection_timeout_ms = 5000)]
 fn main() {
 	work();
 }
@@ -12,6 +12,9 @@ use tracy_gizmos::{
 fn main() {
 	println!("Connecting to Tracy...");
 	let tracy = tracy_gizmos::start_capture();
+	if let Some(info) = tracy_gizmos::capture_network_info() {
+		println!("Tracy is reachable at {info}.");
+	}
 
 	// This could be removed if `no-exit` feature is enabled.
 	while !tracy.is_connected() {
@@ -5,11 +5,14 @@
 //!
 //! ## Usage
 //!
-//! In the `Cargo.toml`:
+//! The attributes are meant to be used via the `tracy-gizmos`
+//! re-exports (`tracy_gizmos::instrument` & `tracy_gizmos::capture`),
+//! which are available with its `attributes` feature. In the
+//! `Cargo.toml`:
 //!
 //! ```toml
 //! [dependencies]
-//! tracy-gizmos-attributes = "0.0.1"
+//! tracy-gizmos = { version = "0.0.9", features = ["attributes"] }
 //! ```
 //!
 //! The [`#[instrument]`][instrument] attribute can now be added to a
@@ -23,6 +26,24 @@
 //! }
 //! ```
 //!
+//! ## Crate path
+//!
+//! The generated code refers to `tracy-gizmos`. If the dependency is
+//! renamed in the `Cargo.toml` (via `package = "tracy-gizmos"`), the
+//! new name is picked up automatically. Otherwise, e.g. when the
+//! rename is inherited from the workspace or `tracy-gizmos` is
+//! re-exported by another crate, the path could be specified
+//! explicitly for every attribute:
+//!
+//! ```no_run
+//! # extern crate tracy_gizmos as my_tracy;
+//! # use tracy_gizmos_attributes::instrument;
+//! #[instrument(crate = my_tracy)]
+//! fn work() {
+//!     // do stuff
+//! }
+//! ```
+//!
 //! [`tracy-gizmos`]: https://crates.io/crates/tracy-gizmos
 //! [zone]: https://docs.rs/tracy-gizmos/latest/tracy_gizmos/struct.Zone.html
 //! [instrument]: macro@self::instrument
@@ -38,6 +59,7 @@ use proc_macro::{
 	Literal,
 	Punct,
 };
+use proc_macro_crate::FoundCrate;
 
 /// Instruments a function to create and start a profiling capture
 /// session.
@@ -60,11 +82,39 @@ use proc_macro::{
 ///    // do stuff
 /// }
 /// ```
+///
+/// ### Connection diagnostics
+///
+/// If no Tracy server connects within the specified amount of
+/// milliseconds, a diagnostic will be printed to stderr and emitted
+/// as a message & the application info:
+///
+/// ```
+/// # use tracy_gizmos_attributes::capture;
+/// #[capture(connection_timeout_ms = 5000)]
+/// fn main() {
+///     // do stuff
+/// }
+/// ```
+///
+/// ### Crate path
+///
+/// The path to `tracy-gizmos` could be specified explicitly, refer
+/// to the [crate-level documentation](crate#crate-path):
+///
+/// ```
+/// # extern crate tracy_gizmos as my_tracy;
+/// # use tracy_gizmos_attributes::capture;
+/// #[capture(connection_timeout_ms = 5000, crate = my_tracy)]
+/// fn main() {
+///     // do stuff
+/// }
+/// ```
 #[proc_macro_attribute]
-pub fn capture(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn capture(attr: TokenStream, item: TokenStream) -> TokenStream {
 	// Cloning a `TokenStream` is cheap since it's reference counted
 	// internally.
-	let with_capture = try_capture(item.clone());
+	let with_capture = try_capture(attr, item.clone());
 	// We chain both error and original item, to prevent the
 	// generation of two compilation errors: one from us and another
 	// one (or multiple, even) caused by original item being skipped.
@@ -74,7 +124,10 @@ pub fn capture(_attr: TokenStream, item: TokenStream) -> TokenStream {
 	}
 }
 
-fn try_capture(item: TokenStream) -> Result<TokenStream, Error> {
+fn try_capture(attr: TokenStream, item: TokenStream) -> Result<TokenStream, Error> {
+	let args = parse_args(attr, Arg::ConnectionTimeout)?;
+	let krate = args.krate.unwrap_or_else(default_crate_path);
+
 	let mut tokens: Vec<TokenTree> = item.into_iter().collect();
 	let mut tokens_it              = tokens.iter();
 
@@ -112,10 +165,10 @@ fn try_capture(item: TokenStream) -> Result<TokenStream, Error> {
 	};
 
 	let augmented_body = vec![
-			make_start_capture(),
+			make_start_capture(&krate, args.connection_timeout),
 			// This should strictly go *after* the capture start,
 			// behaviour is undefined, otherwise.
-			make_zone(name),
+			make_zone(&krate, name),
 			body.stream(),
 		]
 		.into_iter()
@@ -152,6 +205,62 @@ fn try_capture(item: TokenStream) -> Result<TokenStream, Error> {
 /// }
 /// ```
 ///
+/// ### Frames
+///
+/// Callback-driven work, e.g. audio callbacks or I/O completion
+/// handlers, is naturally a frame. The function body could be wrapped
+/// in a discontinuous frame of the given set, in addition to the
+/// zone:
+///
+/// ```
+/// # use tracy_gizmos_attributes::instrument;
+/// #[instrument(frame = "Audio")]
+/// fn mix(buffer: &mut [f32]) {
+///    // will be a frame of the "Audio" set with the "mix" zone inside
+/// }
+/// ```
+///
+/// The zone could be omitted, so only the frame is marked:
+///
+/// ```
+/// # use tracy_gizmos_attributes::instrument;
+/// #[instrument(frame = "IO", zone = false)]
+/// fn on_read_completed(bytes: usize) {
+///    // do stuff
+/// }
+/// ```
+///
+/// ### Migration from `tracing`
+///
+/// With `migration-shims` feature, the arguments of
+/// `tracing::instrument` are accepted as well, so the attribute could
+/// be switched by a find & replace. The span `name` becomes the zone
+/// name, while `skip`, `skip_all`, `fields`, `level`, `target`,
+/// `parent`, `follows_from`, `err` & `ret` are ignored:
+///
+/// ```no_run
+/// # use tracy_gizmos_attributes::instrument;
+/// # #[cfg(feature = "migration-shims")]
+/// #[instrument(name = "load", skip(bytes), fields(size = bytes.len()), level = "debug")]
+/// fn load_texture(bytes: &[u8]) {
+///    // will contain a zone named "load"
+/// }
+/// ```
+///
+/// ### Crate path
+///
+/// The path to `tracy-gizmos` could be specified explicitly, refer
+/// to the [crate-level documentation](crate#crate-path):
+///
+/// ```
+/// # extern crate tracy_gizmos as my_tracy;
+/// # use tracy_gizmos_attributes::instrument;
+/// #[instrument("Heavy", crate = my_tracy)]
+/// fn work() {
+///    // do stuff
+/// }
+/// ```
+///
 /// ### Unsupported cases
 ///
 /// `const fn` cannot be instrumented, and will result in a compilation
@@ -165,6 +274,16 @@ fn try_capture(item: TokenStream) -> Result<TokenStream, Error> {
 /// }
 /// ```
 ///
+/// A function can't opt out of the zone without a frame:
+///
+/// ```compile_fail
+/// # use tracy_gizmos_attributes::instrument;
+/// #[instrument(zone = false)]
+/// fn work() {
+///    // do stuff
+/// }
+/// ```
+///
 /// `async fn` cannot be instrumented, *yet*, and will result in a
 /// compilation failure:
 ///
@@ -195,12 +314,14 @@ fn try_instrument(attr: TokenStream, item: TokenStream) -> Result<TokenStream, E
 	// Put simply, it boils down to:
 	// ... const? async? fn $name:ident ... {}?
 
-	let prefix = if let Some(TokenTree::Literal(s)) = attr.into_iter().next() {
-		Some(s.to_string())
-	} else {
-		None
-	};
-	let prefix = prefix.as_ref().and_then(|p| try_parse_str_literal(p));
+	let args   = parse_args(attr, Arg::Prefix)?;
+	let krate  = args.krate.unwrap_or_else(default_crate_path);
+	let prefix = args.prefix.as_ref().and_then(|p| try_parse_str_literal(p));
+	if let Some((false, span)) = args.zone {
+		if args.frame.is_none() {
+			return Err(Error::new("`zone = false` requires a `frame = \"<name>\"`.", span));
+		}
+	}
 
 	let mut tokens: Vec<TokenTree> = item.into_iter().collect();
 	let mut tokens_it              = tokens.iter();
@@ -230,6 +351,8 @@ fn try_instrument(attr: TokenStream, item: TokenStream) -> Result<TokenStream, E
 	// r# is only important for the rustc, Tracy zone name can be
 	// whatever.
 	let name = name.strip_prefix("r#").unwrap_or(&name);
+	#[cfg(feature = "migration-shims")]
+	let name = args.name.as_deref().unwrap_or(name);
 
 	let prefixed_name = prefix.map(|p| format!("{p}::{name}"));
 	let name = if let Some(ref name) = prefixed_name {
@@ -245,14 +368,166 @@ fn try_instrument(attr: TokenStream, item: TokenStream) -> Result<TokenStream, E
 		_ => unreachable!(),
 	};
 
-	let instrumented_body = vec![make_zone(name), body.stream()]
-		.into_iter()
-		.collect();
+	let mut instrumented_body = Vec::new();
+	if let Some(frame) = args.frame {
+		// The frame should go first, so the zone is inside of it.
+		instrumented_body.push(make_frame(&krate, frame));
+	}
+	if args.zone.is_none_or(|(zone, _)| zone) {
+		instrumented_body.push(make_zone(&krate, name));
+	}
+	instrumented_body.push(body.stream());
+	let instrumented_body = instrumented_body.into_iter().collect();
 	tokens.push(TokenTree::Group(Group::new(Delimiter::Brace, instrumented_body)));
 
 	Ok(TokenStream::from_iter(tokens))
 }
 
+#[derive(PartialEq)]
+enum Arg {
+	Prefix,
+	ConnectionTimeout,
+}
+
+#[derive(Default)]
+struct Args {
+	prefix:             Option<String>,
+	#[cfg(feature = "migration-shims")]
+	name:               Option<String>,
+	frame:              Option<Literal>,
+	zone:               Option<(bool, Span)>,
+	connection_timeout: Option<Literal>,
+	krate:              Option<TokenStream>,
+}
+
+// Parses comma-separated attribute arguments: an optional `"prefix"`,
+// `frame = $name:literal` & `zone = $enabled:ident` or
+// `connection_timeout_ms = $ms:literal` (whichever is `allowed`) and
+// an optional `crate = $path`.
+fn parse_args(attr: TokenStream, allowed: Arg) -> Result<Args, Error> {
+	let expected = match allowed {
+		Arg::Prefix            => "Expected `\"<prefix>\"`, `frame = \"<name>\"`, `zone = <bool>` or `crate = <path>`.",
+		Arg::ConnectionTimeout => "Expected `connection_timeout_ms = <milliseconds>` or `crate = <path>`.",
+	};
+
+	let mut args = Args::default();
+	let mut attr = attr.into_iter().peekable();
+	while let Some(first) = attr.next() {
+		let mut value = Vec::new();
+		while let Some(t) = attr.next_if(|t| !matches!(t, TokenTree::Punct(p) if p.as_char() == ',')) {
+			value.push(t);
+		}
+		// Trailing comma.
+		attr.next();
+
+		match &first {
+			TokenTree::Literal(l) if allowed == Arg::Prefix && value.is_empty() && args.prefix.is_none() => {
+				args.prefix = Some(l.to_string());
+				continue;
+			},
+			TokenTree::Ident(i) => {
+				let key = i.to_string();
+				#[cfg(feature = "migration-shims")]
+				if allowed == Arg::Prefix && parse_tracing_arg(i, &value, &mut args)? {
+					continue;
+				}
+				let mut value = value.into_iter();
+				match value.next() {
+					Some(TokenTree::Punct(p)) if p.as_char() == '=' => {},
+					Some(t) => return Err(Error::new(expected, t.span())),
+					None    => return Err(Error::new(expected, first.span())),
+				}
+				match key.as_str() {
+					"crate" if args.krate.is_none() => {
+						let path = TokenStream::from_iter(value);
+						if path.is_empty() {
+							return Err(Error::new("Expected `crate = <path>`.", first.span()));
+						}
+						args.krate = Some(path);
+					},
+					"connection_timeout_ms" if allowed == Arg::ConnectionTimeout && args.connection_timeout.is_none() => {
+						let ms = match value.next() {
+							Some(TokenTree::Literal(l)) if l.to_string().parse::<u64>().is_ok() => l,
+							Some(t) => return Err(Error::new("Connection timeout should be an integer amount of milliseconds.", t.span())),
+							None    => return Err(Error::new(expected, first.span())),
+						};
+						if let Some(t) = value.next() {
+							return Err(Error::new(expected, t.span()));
+						}
+						args.connection_timeout = Some(ms);
+					},
+					"frame" if allowed == Arg::Prefix && args.frame.is_none() => {
+						let name = match value.next() {
+							Some(TokenTree::Literal(l)) if try_parse_str_literal(&l.to_string()).is_some() => l,
+							Some(t) => return Err(Error::new("Frame name should be a string literal.", t.span())),
+							None    => return Err(Error::new(expected, first.span())),
+						};
+						if let Some(t) = value.next() {
+							return Err(Error::new(expected, t.span()));
+						}
+						args.frame = Some(name);
+					},
+					"zone" if allowed == Arg::Prefix && args.zone.is_none() => {
+						let zone = match value.next() {
+							Some(TokenTree::Ident(i)) if i.to_string() == "true"  => true,
+							Some(TokenTree::Ident(i)) if i.to_string() == "false" => false,
+							Some(t) => return Err(Error::new("Expected `zone = true` or `zone = false`.", t.span())),
+							None    => return Err(Error::new(expected, first.span())),
+						};
+						if let Some(t) = value.next() {
+							return Err(Error::new(expected, t.span()));
+						}
+						args.zone = Some((zone, first.span()));
+					},
+					_ => return Err(Error::new(expected, first.span())),
+				}
+			},
+			t => return Err(Error::new(expected, t.span())),
+		}
+	}
+
+	Ok(args)
+}
+
+// Maps the arguments of `tracing::instrument` onto ours. Only the
+// span name has a meaning for zones, the rest is accepted & ignored,
+// so the code keeps compiling after switching the profilers.
+#[cfg(feature = "migration-shims")]
+fn parse_tracing_arg(key: &Ident, value: &[TokenTree], args: &mut Args) -> Result<bool, Error> {
+	let assigned = matches!(value.first(), Some(TokenTree::Punct(p)) if p.as_char() == '=') && value.len() > 1;
+	let listed   = matches!(value, [TokenTree::Group(g)] if g.delimiter() == Delimiter::Parenthesis);
+	match key.to_string().as_str() {
+		"name" if assigned && args.name.is_none() => {
+			let name = match &value[1..] {
+				[TokenTree::Literal(l)] => try_parse_str_literal(&l.to_string()).map(str::to_owned),
+				_                       => None,
+			};
+			let Some(name) = name else {
+				return Err(Error::new("Zone name should be a string literal.", value[1].span()));
+			};
+			args.name = Some(name);
+		},
+		"skip" | "fields" if listed                                => {},
+		"err" | "ret" if listed || value.is_empty()                => {},
+		"skip_all" if value.is_empty()                             => {},
+		"level" | "target" | "parent" | "follows_from" if assigned => {},
+		_                                                          => return Ok(false),
+	}
+	Ok(true)
+}
+
+// ::tracy_gizmos or the name `tracy-gizmos` is renamed to in the
+// `Cargo.toml` of the crate being compiled.
+fn default_crate_path() -> TokenStream {
+	let name = match proc_macro_crate::crate_name("tracy-gizmos") {
+		Ok(FoundCrate::Name(name)) => name,
+		// Tests, examples & doc tests of `tracy-gizmos` itself refer to
+		// it by name, same as the other crates.
+		Ok(FoundCrate::Itself) | Err(_) => "tracy_gizmos".to_string(),
+	};
+	TokenStream::from_iter(make_path(&[&name]))
+}
+
 fn try_parse_str_literal(s: &str) -> Option<&str> {
 	let s = s.as_bytes();
 	if s.len() >= 2 && s[0] == b'"' {
@@ -263,48 +538,130 @@ fn try_parse_str_literal(s: &str) -> Option<&str> {
 	}
 }
 
-// let _tracy = tracy_gizmos::start_capture();
-fn make_start_capture() -> TokenStream {
+// let _tracy = $krate::start_capture();
+// or
+// let _tracy = $krate::start_capture_with(
+//     $krate::CaptureOptions {
+//         connection_timeout:    Some(Duration::from_millis($ms)),
+//         on_connection_timeout: Some(|text: &str| eprintln!("tracy-gizmos: {}", text)),
+//         ..Default::default()
+//     }
+// );
+fn make_start_capture(krate: &TokenStream, connection_timeout: Option<Literal>) -> TokenStream {
+	let start = match connection_timeout {
+		None     => TokenStream::from_iter(
+			krate.clone().into_iter().chain(make_path(&["start_capture"])).chain([
+				TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new())),
+			])
+		),
+		Some(ms) => {
+			let timeout = TokenStream::from_iter(
+				make_path(&["core", "time", "Duration", "from_millis"]).into_iter().chain([
+					TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::from_iter([
+						TokenTree::Literal(ms),
+					]))),
+				])
+			);
+			let text    = Ident::new("text", Span::mixed_site());
+			let handler = TokenStream::from_iter([
+				TokenTree::Punct(Punct::new('|', Spacing::Alone)),
+				TokenTree::Ident(text.clone()),
+				TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+				TokenTree::Punct(Punct::new('&', Spacing::Alone)),
+				TokenTree::Ident(Ident::new("str", Span::call_site())),
+				TokenTree::Punct(Punct::new('|', Spacing::Alone)),
+			].into_iter().chain(make_path(&["std", "eprintln"])).chain([
+				TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+				TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::from_iter([
+					TokenTree::Literal(Literal::string("tracy-gizmos: {}")),
+					TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+					TokenTree::Ident(text),
+				]))),
+			]));
+			let some = |value| TokenStream::from_iter(
+				make_path(&["core", "option", "Option", "Some"]).into_iter().chain([
+					TokenTree::Group(Group::new(Delimiter::Parenthesis, value)),
+				])
+			);
+			let options = TokenStream::from_iter(
+				krate.clone().into_iter().chain(make_path(&["CaptureOptions"])).chain([
+					TokenTree::Group(Group::new(Delimiter::Brace, TokenStream::from_iter([
+						TokenTree::Ident(Ident::new("connection_timeout", Span::call_site())),
+						TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+					].into_iter().chain(some(timeout)).chain([
+						TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+						TokenTree::Ident(Ident::new("on_connection_timeout", Span::call_site())),
+						TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+					]).chain(some(handler)).chain([
+						TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+						TokenTree::Punct(Punct::new('.', Spacing::Joint)),
+						TokenTree::Punct(Punct::new('.', Spacing::Alone)),
+					]).chain(make_path(&["core", "default", "Default", "default"])).chain([
+						TokenTree::Group(Group::new(Delimiter::Parenthesis, TokenStream::new())),
+					])))),
+				])
+			);
+			TokenStream::from_iter(
+				krate.clone().into_iter().chain(make_path(&["start_capture_with"])).chain([
+					TokenTree::Group(Group::new(Delimiter::Parenthesis, options)),
+				])
+			)
+		},
+	};
+
 	TokenStream::from_iter([
 		TokenTree::Ident(Ident::new("let",    Span::call_site())),
 		TokenTree::Ident(Ident::new("_tracy", Span::mixed_site())),
 		TokenTree::Punct(Punct::new('=', Spacing::Alone)),
-		TokenTree::Punct(Punct::new(':', Spacing::Joint)),
-		TokenTree::Punct(Punct::new(':', Spacing::Alone)),
-		TokenTree::Ident(Ident::new("tracy_gizmos", Span::call_site())),
-		TokenTree::Punct(Punct::new(':', Spacing::Joint)),
-		TokenTree::Punct(Punct::new(':', Spacing::Alone)),
-		TokenTree::Ident(Ident::new("start_capture", Span::call_site())),
+	].into_iter().chain(start).chain([
+		TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+	]))
+}
+
+// ::$segment::$segment...
+fn make_path(segments: &[&str]) -> Vec<TokenTree> {
+	segments
+		.iter()
+		.flat_map(|s| [
+			TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+			TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+			TokenTree::Ident(Ident::new(s, Span::call_site())),
+		])
+		.collect()
+}
+
+// $krate::zone!($text);
+fn make_zone(krate: &TokenStream, name: &str) -> TokenStream {
+	TokenStream::from_iter(krate.clone().into_iter().chain(make_path(&["zone"])).chain([
+		TokenTree::Punct(Punct::new('!', Spacing::Alone)),
 		TokenTree::Group(
 			Group::new(
 				Delimiter::Parenthesis,
-				TokenStream::new(),
+				TokenStream::from_iter([
+					TokenTree::Literal(Literal::string(name)),
+				])
 			)
 		),
 		TokenTree::Punct(Punct::new(';', Spacing::Alone)),
-	])
+	]))
 }
 
-// ::tracy_gizmos::zone!($text);
-fn make_zone(name: &str) -> TokenStream {
-	TokenStream::from_iter([
-		TokenTree::Punct(Punct::new(':', Spacing::Joint)),
-		TokenTree::Punct(Punct::new(':', Spacing::Alone)),
-		TokenTree::Ident(Ident::new("tracy_gizmos", Span::call_site())),
-		TokenTree::Punct(Punct::new(':', Spacing::Joint)),
-		TokenTree::Punct(Punct::new(':', Spacing::Alone)),
-		TokenTree::Ident(Ident::new("zone", Span::call_site())),
+// $krate::frame!(_tracy_frame, $name);
+fn make_frame(krate: &TokenStream, name: Literal) -> TokenStream {
+	TokenStream::from_iter(krate.clone().into_iter().chain(make_path(&["frame"])).chain([
 		TokenTree::Punct(Punct::new('!', Spacing::Alone)),
 		TokenTree::Group(
 			Group::new(
 				Delimiter::Parenthesis,
 				TokenStream::from_iter([
-					TokenTree::Literal(Literal::string(name)),
+					TokenTree::Ident(Ident::new("_tracy_frame", Span::mixed_site())),
+					TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+					TokenTree::Literal(name),
 				])
 			)
 		),
 		TokenTree::Punct(Punct::new(';', Spacing::Alone)),
-	])
+	]))
 }
 
 struct Error {
@@ -1,13 +1,22 @@
 use std::env;
-use std::path::PathBuf;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 fn main() {
 	let mut tracy = PathBuf::from(
 		env::var("CARGO_MANIFEST_DIR").expect("Failed to get the current manifest directory."),
 	);
+	let gizmos = tracy.join("gizmos");
 	tracy.push("tracy");
 
+	// Only the selected vendored sources and our additions are the
+	// build inputs.
+	println!("cargo:rerun-if-changed=build.rs");
+	println!("cargo:rerun-if-changed={}", gizmos.display());
+
 	let defines = defines_from_features();
+	let client  = client_unity(&tracy);
 
 	#[cfg(feature = "bindgen")]
 	{
@@ -22,7 +31,6 @@ fn main() {
 				"-DTRACY_ENABLE",
 				"-DTRACY_MANUAL_LIFETIME",
 				"-DTRACY_DELAYED_INIT",
-				"-DTRACY_NO_FRAME_IMAGE",
 				"-DTRACY_NO_VERIFY",
 			])
 			.clang_args(defines.iter().map(|s| format!("-D{}", s)))
@@ -51,13 +59,12 @@ fn main() {
 	builder
 		.cpp(true)
 		.flag("-std=c++11")
-		.file(tracy.join("TracyClient.cpp"))
+		.include(&tracy)
 		// We always enable it to simplify things. If profiling is not needed,
 		// this crate as a dependency could be optional.
 		.define("TRACY_ENABLE",          None)
 		.define("TRACY_MANUAL_LIFETIME", None)
 		.define("TRACY_DELAYED_INIT",    None)
-		.define("TRACY_NO_FRAME_IMAGE",  None)
 		.define("TRACY_NO_VERIFY",       None)
 		.define("NDEBUG",                None)
 		.opt_level(3); // We always optimize as it is important for dev builds, too.
@@ -66,10 +73,105 @@ fn main() {
 		builder.define(define, None);
 	}
 
+	// Our entry points are `noexcept`, which is only enforced with
+	// exceptions on, so anything thrown by the standard library
+	// (e.g. `std::bad_alloc`) below them terminates the process
+	// instead of unwinding into Rust. It goes first, as it depends on
+	// the client.
+	builder
+		.clone()
+		.file(gizmos.join("GizmosClient.cpp"))
+		.compile("tracy-gizmos-client");
+
+	// Tracy doesn't use C++ exceptions itself, so `-fno-exceptions`
+	// only keeps the client code from having any. It doesn't stop the
+	// standard library from throwing through it, hence it doesn't
+	// make Tracy's own C API entry points safe to unwind from. MSVC
+	// builds always use `/EHsc`, which assumes `extern "C"` functions
+	// never throw, so it is only relevant for GCC-like compilers.
+	if !is_set("CARGO_FEATURE_CPP_EXCEPTIONS") && !builder.get_compiler().is_like_msvc() {
+		builder.flag_if_supported("-fno-exceptions");
+	}
+
 	builder
+		.file(client)
 		.compile("tracy-client")
 }
 
+/// Writes the unity build of the client sources, which are required
+/// for the enabled features, same as `TracyClient.cpp` does for all
+/// of them.
+fn client_unity(tracy: &Path) -> PathBuf {
+	let mut sources = vec![
+		"common/tracy_lz4.cpp",
+		"client/TracyProfiler.cpp",
+		"client/TracyCallstack.cpp",
+		"client/TracySysPower.cpp",
+		"client/TracySysTime.cpp",
+		"client/TracySysTrace.cpp",
+		"common/TracySocket.cpp",
+		"client/tracy_rpmalloc.cpp",
+		"client/TracyAlloc.cpp",
+		"client/TracyOverride.cpp",
+	];
+	if is_set("CARGO_FEATURE_FRAME_IMAGES") {
+		sources.push("client/TracyDxt1.cpp");
+	}
+	// Callstack decoding via libbacktrace, if the platform uses it.
+	let backtrace = if is_set("CARGO_FEATURE_NO_CALLSTACK") {
+		Vec::new()
+	} else {
+		vec![
+			"libbacktrace/alloc.cpp",
+			"libbacktrace/dwarf.cpp",
+			"libbacktrace/fileline.cpp",
+			"libbacktrace/mmapio.cpp",
+			"libbacktrace/posix.cpp",
+			"libbacktrace/sort.cpp",
+			"libbacktrace/state.cpp",
+		]
+	};
+
+	let mut unity = String::new();
+	let include = |unity: &mut String, source: &str| {
+		println!("cargo:rerun-if-changed={}", tracy.join(source).display());
+		_ = writeln!(unity, "#include \"{source}\"");
+	};
+
+	include(&mut unity, "common/TracySystem.cpp");
+	unity.push_str("#ifdef _MSC_VER\n#  pragma warning(push, 0)\n#endif\n");
+	for source in sources {
+		include(&mut unity, source);
+	}
+	if !backtrace.is_empty() {
+		unity.push_str("#if TRACY_HAS_CALLSTACK == 2 || TRACY_HAS_CALLSTACK == 3 || TRACY_HAS_CALLSTACK == 4 || TRACY_HAS_CALLSTACK == 6\n");
+		for source in backtrace {
+			include(&mut unity, source);
+		}
+		unity.push_str("#  if TRACY_HAS_CALLSTACK == 4\n");
+		include(&mut unity, "libbacktrace/macho.cpp");
+		unity.push_str("#  else\n");
+		include(&mut unity, "libbacktrace/elf.cpp");
+		unity.push_str("#  endif\n");
+		include(&mut unity, "common/TracyStackFrames.cpp");
+		unity.push_str("#endif\n");
+	}
+	unity.push_str(concat!(
+		"#ifdef _MSC_VER\n",
+		"#  pragma comment(lib, \"ws2_32.lib\")\n",
+		"#  pragma comment(lib, \"dbghelp.lib\")\n",
+		"#  pragma comment(lib, \"advapi32.lib\")\n",
+		"#  pragma comment(lib, \"user32.lib\")\n",
+		"#  pragma warning(pop)\n",
+		"#endif\n",
+	));
+
+	let path = PathBuf::from(env::var("OUT_DIR").expect("Failed to get the output directory."))
+		.join("TracyClient.cpp");
+	fs::write(&path, unity).expect("Failed to write the client sources.");
+	path
+}
+
 fn defines_from_features() -> Vec<&'static str> {
 	let mut defines = Vec::new();
 	if !is_set("CARGO_FEATURE_CRASH_HANDLER") {
@@ -98,9 +200,21 @@ fn defines_from_features() -> Vec<&'static str> {
 	if !is_set("CARGO_FEATURE_VSYNC") {
 		defines.push("TRACY_NO_VSYNC_CAPTURE");
 	}
+	if !is_set("CARGO_FEATURE_FRAME_IMAGES") {
+		defines.push("TRACY_NO_FRAME_IMAGE");
+	}
 	if is_set("CARGO_FEATURE_NO_EXIT") {
 		defines.push("TRACY_NO_EXIT");
 	}
+	if is_set("CARGO_FEATURE_NO_CALLSTACK") {
+		defines.push("TRACY_NO_CALLSTACK");
+	}
+	if is_set("CARGO_FEATURE_FIBERS") {
+		defines.push("TRACY_FIBERS");
+	}
+	if is_set("CARGO_FEATURE_TIMER_FALLBACK") {
+		defines.push("TRACY_TIMER_FALLBACK");
+	}
 	if !is_set("CARGO_FEATURE_BROADCAST") {
 		defines.push("TRACY_NO_BROADCAST");
 	}
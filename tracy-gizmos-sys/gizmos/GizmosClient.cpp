@@ -0,0 +1,34 @@
+// Small additions on top of Tracy's C API, which are needed by
+// `tracy-gizmos`, but are not exposed by Tracy itself.
+//
+// Everything here is built with the same defines as `TracyClient.cpp`,
+// but always with C++ exceptions, so `noexcept` is enforced: anything
+// thrown below the entry points terminates the process right here,
+// instead of unwinding into Rust.
+
+#include "../tracy/tracy/TracyC.h"
+#include "../tracy/client/TracyProfiler.hpp"
+
+extern "C" {
+
+TRACY_API int64_t ___tracy_gizmos_time() noexcept
+{
+	return tracy::Profiler::GetTime();
+}
+
+TRACY_API void ___tracy_gizmos_emit_zone_begin_at( const struct ___tracy_source_location_data* srcloc, int64_t time ) noexcept
+{
+	TracyQueuePrepareC( tracy::QueueType::ZoneBegin );
+	tracy::MemWrite( &item->zoneBegin.time, time );
+	tracy::MemWrite( &item->zoneBegin.srcloc, (uint64_t)srcloc );
+	TracyQueueCommitC( zoneBeginThread );
+}
+
+TRACY_API void ___tracy_gizmos_emit_zone_end_at( int64_t time ) noexcept
+{
+	TracyQueuePrepareC( tracy::QueueType::ZoneEnd );
+	tracy::MemWrite( &item->zoneEnd.time, time );
+	TracyQueueCommitC( zoneEndThread );
+}
+
+}
@@ -4,3 +4,159 @@
 //! Bindings to Tracy's C API.
 
 include!("bindings.rs");
+
+/// Version of the vendored Tracy client as `(major, minor, patch)`.
+pub const TRACY_VERSION: (u32, u32, u32) = (0, 10, 0);
+/// Network protocol version of the vendored Tracy client. Only the
+/// viewer speaking the same protocol version is able to connect.
+pub const TRACY_PROTOCOL_VERSION: u32 = 64;
+
+// Hand-written bindings to `gizmos/GizmosClient.cpp`.
+extern "C" {
+	/// Current time in Tracy's clock units.
+	pub fn ___tracy_gizmos_time() -> i64;
+	/// Begins a zone, which started at `time` (see `___tracy_gizmos_time`).
+	pub fn ___tracy_gizmos_emit_zone_begin_at(
+		srcloc: *const ___tracy_source_location_data,
+		time: i64,
+	);
+	/// Ends the innermost zone of the current thread at `time`.
+	pub fn ___tracy_gizmos_emit_zone_end_at(time: i64);
+}
+
+// Fiber functions are declared by `TracyC.h` only with `TRACY_FIBERS`,
+// while the bindings are always generated without `fibers` feature.
+#[cfg(feature = "fibers")]
+extern "C" {
+	pub fn ___tracy_fiber_enter(fiber: *const ::std::os::raw::c_char);
+	pub fn ___tracy_fiber_leave();
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeSet;
+	use std::fs;
+	use std::path::{Component, Path, PathBuf};
+
+	const ROOTS: [&str; 2] = ["TracyClient.cpp", "tracy/TracyC.h"];
+	// Files, which are not included, but still must be shipped.
+	// `TracyVersion.hpp` is read by `versions_match_vendored_headers`.
+	const EXTRAS: [&str; 2] = ["libbacktrace/LICENSE", "common/TracyVersion.hpp"];
+
+	fn tracy_dir() -> PathBuf {
+		Path::new(env!("CARGO_MANIFEST_DIR")).join("tracy")
+	}
+
+	// Everything reachable via `#include "..."` from the roots.
+	// Conditional includes are followed, too.
+	fn include_closure() -> BTreeSet<String> {
+		let tracy     = tracy_dir();
+		let mut seen  = BTreeSet::new();
+		let mut queue: Vec<PathBuf> = ROOTS.iter().map(PathBuf::from).collect();
+
+		while let Some(file) = queue.pop() {
+			let Ok(source) = fs::read_to_string(tracy.join(&file)) else {
+				// System headers, like `TargetConditionals.h`.
+				continue;
+			};
+			if !seen.insert(to_key(&file)) {
+				continue;
+			}
+			for line in source.lines() {
+				let line = line.trim_start();
+				let Some(rest) = line.strip_prefix('#') else { continue };
+				let Some(rest) = rest.trim_start().strip_prefix("include") else { continue };
+				let mut parts = rest.split('"');
+				if let (Some(_), Some(include)) = (parts.next(), parts.next()) {
+					queue.push(normalize(&file.parent().unwrap().join(include)));
+				}
+			}
+		}
+
+		seen
+	}
+
+	fn vendored_files(dir: &Path, out: &mut BTreeSet<String>) {
+		for entry in fs::read_dir(dir).unwrap() {
+			let path = entry.unwrap().path();
+			if path.is_dir() {
+				vendored_files(&path, out);
+			} else {
+				out.insert(to_key(path.strip_prefix(tracy_dir()).unwrap()));
+			}
+		}
+	}
+
+	fn excluded_files() -> BTreeSet<String> {
+		let manifest = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml")).unwrap();
+		manifest
+			.lines()
+			.filter_map(|l| l.trim().strip_prefix("\"/tracy/"))
+			.map(|l| l.trim_end_matches(',').trim_end_matches('"').to_string())
+			.collect()
+	}
+
+	fn normalize(path: &Path) -> PathBuf {
+		let mut out = PathBuf::new();
+		for c in path.components() {
+			match c {
+				Component::ParentDir => { out.pop(); },
+				Component::CurDir    => {},
+				c                    => out.push(c),
+			}
+		}
+		out
+	}
+
+	fn to_key(path: &Path) -> String {
+		path.to_string_lossy().replace('\\', "/")
+	}
+
+	fn enum_value(file: &str, name: &str) -> u32 {
+		let source = fs::read_to_string(tracy_dir().join(file)).unwrap();
+		let (_, rest) = source.split_once(&format!("{name} = ")).unwrap();
+		rest[..rest.find(|c: char| !c.is_ascii_digit()).unwrap()].parse().unwrap()
+	}
+
+	#[test]
+	fn versions_match_vendored_headers() {
+		let version = (
+			enum_value("common/TracyVersion.hpp", "Major"),
+			enum_value("common/TracyVersion.hpp", "Minor"),
+			enum_value("common/TracyVersion.hpp", "Patch"),
+		);
+		assert_eq!(super::TRACY_VERSION, version);
+		assert_eq!(super::TRACY_PROTOCOL_VERSION, enum_value("common/TracyProtocol.hpp", "ProtocolVersion"));
+	}
+
+	#[test]
+	fn unity_build_includes_client_sources() {
+		let build  = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("build.rs")).unwrap();
+		let client = fs::read_to_string(tracy_dir().join("TracyClient.cpp")).unwrap();
+		let missing: Vec<_> = client
+			.lines()
+			.filter(|l| l.contains("include"))
+			.filter_map(|l| l.split('"').nth(1))
+			.filter(|source| !build.contains(&format!("\"{source}\"")))
+			.collect();
+		assert!(missing.is_empty(), "Client sources are not selected by build.rs: {missing:?}");
+	}
+
+	#[test]
+	fn vendored_sources_are_pruned() {
+		let mut vendored = BTreeSet::new();
+		vendored_files(&tracy_dir(), &mut vendored);
+		let excluded = excluded_files();
+		let shipped: BTreeSet<_> = vendored.difference(&excluded).cloned().collect();
+
+		let mut needed = include_closure();
+		needed.extend(EXTRAS.iter().map(|s| s.to_string()));
+
+		let missing: Vec<_> = needed.difference(&shipped).collect();
+		assert!(missing.is_empty(), "Needed sources are not shipped: {missing:?}");
+		let unused: Vec<_> = shipped.difference(&needed).collect();
+		assert!(unused.is_empty(), "Unused sources are shipped: {unused:?}");
+		let stale: Vec<_> = excluded.difference(&vendored).collect();
+		assert!(stale.is_empty(), "Excluded sources do not exist: {stale:?}");
+	}
+}
@@ -0,0 +1,195 @@
+use std::marker::PhantomData;
+
+#[cfg(feature = "enabled")]
+use std::cell::RefCell;
+#[cfg(feature = "enabled")]
+use std::ffi::CStr;
+#[cfg(feature = "enabled")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "enabled")]
+use crate::{Plot, PlotEmit};
+
+/// How often aggregated statistics are emitted per thread.
+#[cfg(feature = "enabled")]
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// A zone, which is not emitted, but aggregated instead.
+///
+/// Refer to [`zone!`] for the usage how-to.
+///
+/// Zones lasting less than a microsecond are hard to profile, as
+/// emitting every one of them costs more than the zone itself and
+/// floods the capture. An aggregated zone only accumulates its
+/// count, total, min & max duration per thread, which are emitted
+/// once a second into `<name>: count`, `<name>: total, ns`, `<name>:
+/// min, ns` & `<name>: max, ns` plots. Unless `no-dynamic-strings`
+/// feature is enabled, the same summary is also emitted as a message
+/// into the thread timeline.
+///
+/// Statistics of the last incomplete second are emitted on the next
+/// aggregated zone after it, or by [`flush_aggregates`].
+///
+/// [`zone!`]: crate::zone!
+pub struct AggregatedZone {
+	#[cfg(feature = "enabled")]
+	site:    &'static AggregateSite,
+	#[cfg(feature = "enabled")]
+	started: Instant,
+	_unsend: PhantomData<*mut ()>,
+}
+
+#[cfg(feature = "enabled")]
+impl AggregatedZone {
+	#[inline(always)]
+	pub(crate) fn begin(site: &'static AggregateSite) -> Self {
+		Self { site, started: Instant::now(), _unsend: PhantomData }
+	}
+}
+
+#[cfg(any(doc, feature = "enabled"))]
+impl Drop for AggregatedZone {
+	#[inline(always)]
+	fn drop(&mut self) {
+		#[cfg(feature = "enabled")]
+		record(self.site, self.started.elapsed());
+	}
+}
+
+/// Static data of the aggregated zone.
+#[doc(hidden)]
+#[cfg(feature = "enabled")]
+pub struct AggregateSite {
+	#[cfg_attr(feature = "no-dynamic-strings", allow(dead_code))]
+	name:  &'static str,
+	count: Plot,
+	total: Plot,
+	min:   Plot,
+	max:   Plot,
+}
+
+#[cfg(feature = "enabled")]
+impl AggregateSite {
+	#[doc(hidden)]
+	pub const fn new(
+		name:  &'static str,
+		count: &'static CStr,
+		total: &'static CStr,
+		min:   &'static CStr,
+		max:   &'static CStr,
+	) -> Self {
+		Self {
+			name,
+			count: Plot::new(count),
+			total: Plot::new(total),
+			min:   Plot::new(min),
+			max:   Plot::new(max),
+		}
+	}
+}
+
+#[cfg(feature = "enabled")]
+struct Stats {
+	site:  &'static AggregateSite,
+	since: Instant,
+	count: u64,
+	total: Duration,
+	min:   Duration,
+	max:   Duration,
+}
+
+#[cfg(feature = "enabled")]
+impl Stats {
+	fn new(site: &'static AggregateSite) -> Self {
+		Self {
+			site,
+			since: Instant::now(),
+			count: 0,
+			total: Duration::ZERO,
+			min:   Duration::MAX,
+			max:   Duration::ZERO,
+		}
+	}
+
+	fn emit(&mut self) {
+		if self.count != 0 {
+			let site = self.site;
+			site.count.emit(self.count as i64);
+			site.total.emit(self.total.as_nanos() as i64);
+			site.min.emit(self.min.as_nanos() as i64);
+			site.max.emit(self.max.as_nanos() as i64);
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			crate::details::message_size(&format!(
+				"{}: {} calls, {:?} total, {:?} min, {:?} max",
+				site.name,
+				self.count,
+				self.total,
+				self.min,
+				self.max,
+			));
+		}
+		*self = Self::new(self.site);
+	}
+}
+
+#[cfg(feature = "enabled")]
+thread_local! {
+	// Only a handful of aggregated zones are expected per thread, so
+	// a linear lookup is fine.
+	static STATS: RefCell<Vec<Stats>> = const { RefCell::new(Vec::new()) };
+}
+
+#[cfg(feature = "enabled")]
+fn record(site: &'static AggregateSite, elapsed: Duration) {
+	STATS.with_borrow_mut(|all| {
+		let i = match all.iter().position(|stats| std::ptr::eq(stats.site, site)) {
+			Some(i) => i,
+			None    => {
+				all.push(Stats::new(site));
+				all.len() - 1
+			},
+		};
+		let stats = &mut all[i];
+		stats.count += 1;
+		stats.total += elapsed;
+		stats.min    = stats.min.min(elapsed);
+		stats.max    = stats.max.max(elapsed);
+		if stats.since.elapsed() >= WINDOW {
+			stats.emit();
+		}
+	});
+}
+
+/// Emits statistics of all aggregated zones of the current thread
+/// right away, refer to [`AggregatedZone`].
+///
+/// It is useful before the thread or the capture ends, so the last
+/// incomplete second is not lost.
+pub fn flush_aggregates() {
+	#[cfg(feature = "enabled")]
+	STATS.with_borrow_mut(|all| all.iter_mut().for_each(Stats::emit));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::zone;
+
+	#[test]
+	fn aggregated_zones() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		for _ in 0..3 {
+			zone!("tiny", aggregate);
+		}
+		#[cfg(feature = "enabled")]
+		STATS.with_borrow(|all| {
+			let stats = all.iter().find(|stats| stats.site.name == "tiny").unwrap();
+			assert_eq!(stats.count, 3);
+			assert!(stats.min <= stats.max);
+		});
+		flush_aggregates();
+		#[cfg(feature = "enabled")]
+		STATS.with_borrow(|all| assert!(all.iter().all(|stats| stats.count == 0)));
+	}
+}
@@ -0,0 +1,143 @@
+#[cfg(feature = "enabled")]
+use std::collections::BTreeMap;
+#[cfg(feature = "enabled")]
+use std::sync::Mutex;
+#[cfg(feature = "enabled")]
+use std::sync::atomic::Ordering;
+
+/// Batch, which is flushed when the capture starts, refer to
+/// [`AppInfoBatch::global`].
+static GLOBAL: AppInfoBatch = AppInfoBatch::new();
+
+/// Ordered batch of the [`app_info`] entries.
+///
+/// [`app_info`] called from multiple threads interleaves differently
+/// on every run, so the trace descriptions can't be compared. The
+/// batch collects `key: info` entries instead and emits them, ordered
+/// by the key, as a single [`app_info`] on [`AppInfoBatch::flush`].
+/// Adding an entry with the same key replaces the previous one.
+///
+/// The [`AppInfoBatch::global`] batch is flushed automatically, when
+/// the capture starts, so subsystems could describe themselves even
+/// before that.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// let gpu = std::thread::spawn(|| AppInfoBatch::global().add("gpu", "RTX 4090"));
+/// let cpu = std::thread::spawn(|| AppInfoBatch::global().add("cpu", "16 cores"));
+/// _ = (gpu.join(), cpu.join());
+///
+/// // Emits "cpu: 16 cores\ngpu: RTX 4090".
+/// let _tracy = start_capture();
+/// ```
+///
+/// [`app_info`]: crate::app_info
+pub struct AppInfoBatch {
+	#[cfg(feature = "enabled")]
+	entries: Mutex<BTreeMap<String, String>>,
+}
+
+impl AppInfoBatch {
+	/// Creates an empty batch.
+	pub const fn new() -> Self {
+		Self {
+			#[cfg(feature = "enabled")]
+			entries: Mutex::new(BTreeMap::new()),
+		}
+	}
+
+	/// Returns the batch, which is flushed when the capture starts.
+	pub fn global() -> &'static Self {
+		&GLOBAL
+	}
+
+	/// Adds the entry, which is emitted as `key: info`.
+	pub fn add(&self, key: &str, info: &str) {
+		#[cfg(feature = "enabled")]
+		self.entries.lock().unwrap_or_else(|e| e.into_inner()).insert(key.to_owned(), info.to_owned());
+		#[cfg(not(feature = "enabled"))]
+		{
+			// Silences unused variable warning.
+			_ = (key, info);
+		}
+	}
+
+	/// Emits the entries ordered by the key and clears the batch.
+	///
+	/// Entries are kept until the capture starts, as there is nowhere
+	/// to emit them before that.
+	pub fn flush(&self) {
+		#[cfg(feature = "enabled")]
+		if crate::STARTED.load(Ordering::Acquire) {
+			let entries = std::mem::take(&mut *self.entries.lock().unwrap_or_else(|e| e.into_inner()));
+			for text in texts(&entries) {
+				crate::app_info(&text);
+			}
+		}
+	}
+}
+
+impl Default for AppInfoBatch {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Returns the entries as lines of texts, which are as few as Tracy
+/// allows.
+#[cfg(feature = "enabled")]
+fn texts(entries: &BTreeMap<String, String>) -> Vec<String> {
+	const MAX_LEN: usize = u16::MAX as usize - 1;
+
+	let mut texts = Vec::new();
+	let mut text  = String::new();
+	for (key, info) in entries {
+		let line = format!("{key}: {info}");
+		if !text.is_empty() && text.len() + 1 + line.len() > MAX_LEN {
+			texts.push(std::mem::take(&mut text));
+		}
+		if !text.is_empty() {
+			text.push('\n');
+		}
+		text.push_str(&line);
+	}
+	if !text.is_empty() {
+		texts.push(text);
+	}
+	texts
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ordered_app_info() {
+		let batch = &AppInfoBatch::new();
+		std::thread::scope(|s| {
+			for (key, info) in [("gpu", "old"), ("cpu", "16 cores"), ("gpu", "RTX")] {
+				s.spawn(move || batch.add(key, info)).join().unwrap();
+			}
+		});
+
+		#[cfg(feature = "enabled")]
+		{
+			let entries = batch.entries.lock().unwrap().clone();
+			assert_eq!(texts(&entries), ["cpu: 16 cores\ngpu: RTX"]);
+
+			let long: BTreeMap<_, _> = (0..3).map(|i| (i.to_string(), "x".repeat(30_000))).collect();
+			assert_eq!(texts(&long).len(), 2);
+
+			// Nowhere to emit yet.
+			batch.flush();
+			assert!(!batch.entries.lock().unwrap().is_empty());
+			AppInfoBatch::global().add("test", "global");
+			let _tracy = crate::start_capture();
+			assert!(AppInfoBatch::global().entries.lock().unwrap().is_empty());
+			batch.flush();
+			assert!(batch.entries.lock().unwrap().is_empty());
+		}
+	}
+}
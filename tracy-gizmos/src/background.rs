@@ -0,0 +1,61 @@
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// A background thread, which lives alongside the capture or the
+/// debug HTTP endpoint.
+///
+/// Capture threads are stopped and joined before the shutdown, so
+/// they can safely use Tracy until asked to stop.
+pub(crate) struct Background {
+	stop:   Arc<Stop>,
+	thread: JoinHandle<()>,
+}
+
+/// A stop signal for the [`Background`] thread.
+pub(crate) struct Stop {
+	stopped: Mutex<bool>,
+	signal:  Condvar,
+}
+
+impl Background {
+	pub(crate) fn spawn<F>(name: &str, f: F) -> Self
+	where
+		F: FnOnce(&Stop) + Send + 'static,
+	{
+		let stop = Arc::new(Stop {
+			stopped: Mutex::new(false),
+			signal:  Condvar::new(),
+		});
+		let thread = std::thread::Builder::new()
+			.name(name.to_string())
+			.spawn({
+				let stop = Arc::clone(&stop);
+				move || f(&stop)
+			})
+			.expect("Failed to spawn a background thread.");
+		Self { stop, thread }
+	}
+
+	pub(crate) fn stop(self) {
+		*self.stop.stopped.lock().unwrap_or_else(|e| e.into_inner()) = true;
+		self.stop.signal.notify_one();
+		// Background threads should not panic, but if they did,
+		// there is nothing to do about it anyway.
+		_ = self.thread.join();
+	}
+}
+
+impl Stop {
+	/// Blocks for the given duration or until stopped, whatever
+	/// happens first.
+	///
+	/// Returns `true` if the thread was asked to stop.
+	pub(crate) fn wait(&self, timeout: Duration) -> bool {
+		let stopped = self.stopped.lock().unwrap_or_else(|e| e.into_inner());
+		let (stopped, _) = self.signal
+			.wait_timeout_while(stopped, timeout, |stopped| !*stopped)
+			.unwrap_or_else(|e| e.into_inner());
+		*stopped
+	}
+}
@@ -0,0 +1,84 @@
+//! Profiling of build scripts and procedural macros.
+//!
+//! Slow build steps can be profiled with Tracy too: add
+//! `tracy-gizmos` with `enabled` & `build-profiling` features as a
+//! build-dependency (or a regular dependency of a proc-macro crate),
+//! start the capture via one of the functions here and use [`zone!`]
+//! around the interesting phases as usual.
+//!
+//! Build steps are usually short-lived, so the profiling data has
+//! barely any chance to reach the profiler, unless `no-exit` feature
+//! is also enabled and `TRACY_NO_EXIT=1` is set.
+//!
+//! [`zone!`]: crate::zone!
+
+use std::sync::Once;
+
+use crate::{app_info, start_capture, TracyCapture};
+
+/// Starts the capture of a build script.
+///
+/// The package name is added to the application info.
+///
+/// # Examples
+///
+/// In the `build.rs`:
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn generate_bindings() {}
+/// fn main() {
+///     let _tracy = build::capture_build_script();
+///     zone!("bindings");
+///     generate_bindings();
+/// }
+/// ```
+pub fn capture_build_script() -> TracyCapture {
+	let tracy = start_capture();
+	crate::set_thread_name!("build script");
+	app_info(&describe("Build script"));
+	tracy
+}
+
+/// Starts the capture of procedural macros, if it hasn't been
+/// started yet.
+///
+/// All procedural macros are executed by the compiler process, so
+/// the capture is shared between them and lasts until the process
+/// exits. Hence, it is fine (and expected) to call this at the
+/// beginning of every instrumented macro.
+///
+/// # Examples
+///
+/// ```ignore
+/// # // this doctest is ignored, because it is impossible to build a
+/// # // proc-macro crate here.
+/// #[proc_macro_derive(Heavy)]
+/// pub fn derive_heavy(input: TokenStream) -> TokenStream {
+///     tracy_gizmos::build::capture_proc_macros();
+///     tracy_gizmos::zone!("derive(Heavy)");
+///     expand(input)
+/// }
+/// ```
+pub fn capture_proc_macros() {
+	static STARTED: Once = Once::new();
+
+	STARTED.call_once(|| {
+		// Compiler process never tells us it is finishing, so the
+		// capture is never shut down.
+		_ = std::mem::ManuallyDrop::new(start_capture());
+		app_info(&describe("Procedural macros"));
+	});
+}
+
+fn describe(what: &str) -> String {
+	// Cargo provides these both to build scripts and to the compiler
+	// running the procedural macros.
+	let package = std::env::var("CARGO_PKG_NAME").unwrap_or_default();
+	let crate_name = std::env::var("CARGO_CRATE_NAME").unwrap_or_default();
+	match (package.is_empty(), crate_name.is_empty()) {
+		(true,  true)  => what.to_string(),
+		(false, true)  => format!("{what} of {package}"),
+		(_,     false) => format!("{what} of {package} ({crate_name})"),
+	}
+}
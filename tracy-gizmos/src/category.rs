@@ -0,0 +1,204 @@
+//! Zone categories, which allow to compile out whole groups of zones.
+//!
+//! Every category is a bit of the `u64` mask. A zone gets a category
+//! via [`zone!`], e.g. `zone!("draw", category: RENDER)`, and it is
+//! statically removed, unless the category is enabled. A zone could
+//! belong to several categories at once, e.g. `IO | NET`, and it is
+//! kept if any of them is enabled.
+//!
+//! All categories are enabled by default. A build with only some of
+//! them is selected via `TRACY_GIZMOS_CATEGORIES` environment
+//! variable during the build, which is a comma-separated list of
+//! the built-in category names or custom category bits, e.g.
+//! `TRACY_GIZMOS_CATEGORIES=IO,NET,16`. Zones without a category are
+//! never affected.
+//!
+//! Built-in categories occupy the lower 16 bits, while the rest are
+//! available for application-specific ones via [`custom`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use tracy_gizmos::*;
+//! const GAMEPLAY: u64 = category::custom(16);
+//!
+//! zone!("draw", category: RENDER);
+//! zone!(sync, "sync saves", Color::ORANGE, category: IO | NET);
+//! zone!("tick", category: GAMEPLAY);
+//! ```
+//!
+//! [`zone!`]: crate::zone!
+
+/// Rendering & GPU submission.
+pub const RENDER:    u64 = 1 << 0;
+/// File system I/O.
+pub const IO:        u64 = 1 << 1;
+/// Networking.
+pub const NET:       u64 = 1 << 2;
+/// Audio mixing & playback.
+pub const AUDIO:     u64 = 1 << 3;
+/// Physics simulation.
+pub const PHYSICS:   u64 = 1 << 4;
+/// AI & navigation.
+pub const AI:        u64 = 1 << 5;
+/// Animation.
+pub const ANIMATION: u64 = 1 << 6;
+/// Scripting.
+pub const SCRIPTING: u64 = 1 << 7;
+/// User interface.
+pub const UI:        u64 = 1 << 8;
+/// Asset loading & processing.
+pub const ASSETS:    u64 = 1 << 9;
+/// Job system & task scheduling.
+pub const JOBS:      u64 = 1 << 10;
+/// Memory management.
+pub const MEMORY:    u64 = 1 << 11;
+
+/// Built-in category names, as accepted by `TRACY_GIZMOS_CATEGORIES`.
+const BUILT_IN: [(&str, u64); 12] = [
+	("RENDER",    RENDER),
+	("IO",        IO),
+	("NET",       NET),
+	("AUDIO",     AUDIO),
+	("PHYSICS",   PHYSICS),
+	("AI",        AI),
+	("ANIMATION", ANIMATION),
+	("SCRIPTING", SCRIPTING),
+	("UI",        UI),
+	("ASSETS",    ASSETS),
+	("JOBS",      JOBS),
+	("MEMORY",    MEMORY),
+];
+
+/// The first bit available for custom categories.
+pub const FIRST_CUSTOM_BIT: u32 = 16;
+
+/// Returns the custom category of the given bit, which should be
+/// within `16..64`.
+pub const fn custom(bit: u32) -> u64 {
+	if bit < FIRST_CUSTOM_BIT || bit >= u64::BITS {
+		panic!("Custom category bit should be within 16..64.");
+	}
+	1 << bit
+}
+
+/// Mask of the enabled categories.
+pub const ENABLED: u64 = match option_env!("TRACY_GIZMOS_CATEGORIES") {
+	Some(categories) => parse_mask(categories),
+	None             => u64::MAX,
+};
+
+/// Returns `true`, if any of the categories is enabled.
+#[doc(hidden)]
+#[inline(always)]
+pub const fn is_enabled(categories: u64) -> bool {
+	categories & ENABLED != 0
+}
+
+const fn parse_mask(s: &str) -> u64 {
+	let bytes    = s.as_bytes();
+	let mut mask = 0;
+	let mut from = 0;
+	while from < bytes.len() {
+		let mut to = from;
+		while to < bytes.len() && bytes[to] != b',' {
+			to += 1;
+		}
+		let name = trim(bytes.split_at(to).0.split_at(from).1);
+		if !name.is_empty() {
+			mask |= parse_category(name);
+		}
+		from = to + 1;
+	}
+	mask
+}
+
+const fn parse_category(name: &[u8]) -> u64 {
+	if !name.is_empty() && name[0].is_ascii_digit() {
+		let mut bit = 0;
+		let mut i   = 0;
+		while i < name.len() {
+			if !name[i].is_ascii_digit() {
+				panic!("TRACY_GIZMOS_CATEGORIES should list category names or custom bits.");
+			}
+			bit = bit * 10 + (name[i] - b'0') as u32;
+			i += 1;
+		}
+		return custom(bit);
+	}
+
+	let mut i = 0;
+	while i < BUILT_IN.len() {
+		if eq(BUILT_IN[i].0.as_bytes(), name) {
+			return BUILT_IN[i].1;
+		}
+		i += 1;
+	}
+	panic!("TRACY_GIZMOS_CATEGORIES should list category names or custom bits.");
+}
+
+const fn trim(mut s: &[u8]) -> &[u8] {
+	while let [b' ', rest @ ..] = s {
+		s = rest;
+	}
+	while let [rest @ .., b' '] = s {
+		s = rest;
+	}
+	s
+}
+
+const fn eq(a: &[u8], b: &[u8]) -> bool {
+	if a.len() != b.len() {
+		return false;
+	}
+	let mut i = 0;
+	while i < a.len() {
+		if a[i] != b[i] {
+			return false;
+		}
+		i += 1;
+	}
+	true
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{zone, zone_l3};
+
+	#[test]
+	fn parses_masks() {
+		assert_eq!(parse_mask(""),            0);
+		assert_eq!(parse_mask("IO"),          IO);
+		assert_eq!(parse_mask("IO,NET,16"),   IO | NET | custom(16));
+		assert_eq!(parse_mask("MEMORY,,63"),  MEMORY | custom(63));
+		assert_eq!(parse_mask(" AI , UI "),   AI | UI);
+	}
+
+	#[test]
+	#[should_panic]
+	fn rejects_unknown_categories() {
+		parse_mask("IO,GPU");
+	}
+
+	#[test]
+	#[should_panic]
+	fn rejects_built_in_bits() {
+		custom(3);
+	}
+
+	#[test]
+	fn category_zones() {
+		const GAMEPLAY: u64 = custom(16);
+
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		zone!(draw, "draw", category: RENDER);
+		#[cfg(feature = "enabled")]
+		assert_eq!(draw.ctx.active != 0, is_enabled(RENDER));
+		zone!(sync, "sync", crate::Color::ORANGE, category: IO | NET);
+		sync.text("saves");
+		zone!("tick", category: GAMEPLAY);
+		zone_l3!(_nested, "nested", category: AI);
+	}
+}
@@ -0,0 +1,287 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Minimal distance between calibration anchors. The conversion
+/// blocks for the rest of it when used right after the capture start.
+#[cfg(feature = "enabled")]
+const CALIBRATION: Duration = Duration::from_millis(10);
+
+/// The first anchor, which is taken at the capture start.
+static BASE: OnceLock<Anchor> = OnceLock::new();
+
+/// A pair of `Instant` & Tracy time sampled at the same moment.
+#[derive(Clone, Copy)]
+struct Anchor {
+	instant: Instant,
+	#[cfg(feature = "enabled")]
+	time:    i64,
+}
+
+impl Anchor {
+	#[cfg(feature = "enabled")]
+	fn now() -> Self {
+		// SAFETY: The Tracy clock is usable regardless of the
+		// profiler state.
+		let before  = unsafe { sys::___tracy_gizmos_time() };
+		let instant = Instant::now();
+		let after   = unsafe { sys::___tracy_gizmos_time() };
+		Self { instant, time: before + (after - before) / 2 }
+	}
+
+	#[cfg(not(feature = "enabled"))]
+	fn now() -> Self {
+		Self { instant: Instant::now() }
+	}
+}
+
+/// Takes the base calibration anchor, if it is not taken yet.
+#[cfg(feature = "enabled")]
+pub(crate) fn calibrate() {
+	BASE.get_or_init(Anchor::now);
+}
+
+/// Returns the fresh anchor & the amount of nanoseconds per Tracy
+/// tick measured against the base one.
+#[cfg(feature = "enabled")]
+fn calibrated() -> (Anchor, f64) {
+	let base = *BASE.get_or_init(Anchor::now);
+	let mut now = Anchor::now();
+	let elapsed = now.instant - base.instant;
+	if elapsed < CALIBRATION {
+		std::thread::sleep(CALIBRATION - elapsed);
+		now = Anchor::now();
+	}
+	(now, nanos_per_tick(base, now))
+}
+
+#[cfg(feature = "enabled")]
+fn nanos_per_tick(base: Anchor, now: Anchor) -> f64 {
+	let ticks = (now.time - base.time).max(1);
+	(now.instant - base.instant).as_nanos() as f64 / ticks as f64
+}
+
+/// Returns the time elapsed since `time` in Tracy's clock units.
+///
+/// Unlike the conversions, it never blocks. Until the calibration
+/// data is gathered, the rate is measured against the base anchor on
+/// every call, so it is less precise right after the capture start.
+#[cfg(feature = "enabled")]
+pub(crate) fn elapsed_since(time: i64) -> Duration {
+	static SETTLED: OnceLock<f64> = OnceLock::new();
+
+	// SAFETY: The Tracy clock is usable regardless of the profiler
+	// state.
+	let ticks = unsafe { sys::___tracy_gizmos_time() } - time;
+	let rate  = match SETTLED.get() {
+		Some(rate) => *rate,
+		None => {
+			let base = *BASE.get_or_init(Anchor::now);
+			let now  = Anchor::now();
+			let rate = nanos_per_tick(base, now);
+			if now.instant - base.instant >= CALIBRATION {
+				*SETTLED.get_or_init(|| rate)
+			} else if now.time > base.time {
+				rate
+			} else {
+				// Nothing to measure against yet, assume nanoseconds.
+				1.0
+			}
+		},
+	};
+	Duration::from_nanos((ticks.max(0) as f64 * rate) as u64)
+}
+
+/// Nanoseconds from `from` to `to`, negative if `to` is earlier.
+fn signed_nanos(from: Instant, to: Instant) -> i64 {
+	if to >= from {
+		(to - from).as_nanos() as i64
+	} else {
+		-((from - to).as_nanos() as i64)
+	}
+}
+
+/// Converts the `Instant` into Tracy's clock units.
+///
+/// It allows placing externally timestamped events, e.g. coming from
+/// a hardware device synchronized with the host clock, accurately on
+/// the Tracy timeline. Tracy's clock is usually the raw CPU
+/// timestamp counter, so the conversion is calibrated against
+/// `Instant` since the capture start. The calibration is anchored at
+/// the moment of conversion, so the closer the `instant` is to now,
+/// the more precise is the result.
+///
+/// The first conversion right after the capture start could block
+/// for a few milliseconds to gather enough calibration data.
+///
+/// When the instrumentation is disabled, nanoseconds since the first
+/// conversion are returned, so the round-trip through
+/// [`from_tracy_time`] still holds.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::time::{Duration, Instant};
+/// # fn device_timestamp() -> Instant { Instant::now() - Duration::from_micros(250) }
+/// let _tracy = start_capture();
+/// // Host time of the event reported by the device.
+/// let happened = device_timestamp();
+/// let time = to_tracy_time(happened);
+/// # _ = time;
+/// ```
+pub fn to_tracy_time(instant: Instant) -> i64 {
+	#[cfg(feature = "enabled")]
+	{
+		let (now, ns_per_tick) = calibrated();
+		now.time + (signed_nanos(now.instant, instant) as f64 / ns_per_tick).round() as i64
+	}
+	#[cfg(not(feature = "enabled"))]
+	{
+		signed_nanos(BASE.get_or_init(Anchor::now).instant, instant)
+	}
+}
+
+/// Converts the time in Tracy's clock units back into the `Instant`.
+///
+/// It is the inverse of [`to_tracy_time`], refer to it for the
+/// calibration details.
+///
+/// # Panics
+///
+/// Panics if the resulting `Instant` is not representable on the
+/// current platform.
+pub fn from_tracy_time(time: i64) -> Instant {
+	#[cfg(feature = "enabled")]
+	let (anchor, nanos) = {
+		let (now, ns_per_tick) = calibrated();
+		(now.instant, ((time - now.time) as f64 * ns_per_tick).round() as i64)
+	};
+	#[cfg(not(feature = "enabled"))]
+	let (anchor, nanos) = (BASE.get_or_init(Anchor::now).instant, time);
+
+	let offset = Duration::from_nanos(nanos.unsigned_abs());
+	if nanos >= 0 {
+		anchor.checked_add(offset)
+	} else {
+		anchor.checked_sub(offset)
+	}
+	.expect("Tracy time should be representable as Instant.")
+}
+
+/// Source of the time stamps used by Tracy, refer to
+/// [`timer_source`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TimerSource {
+	/// The CPU time stamp counter, which is invariant, i.e. ticks at
+	/// the constant rate regardless of the power states.
+	InvariantTsc,
+	/// The CPU time stamp counter, which is not invariant, but used
+	/// anyway because of `TRACY_NO_INVARIANT_CHECK=1` environment
+	/// variable. Zones could end up being distorted or zero-length.
+	UncheckedTsc,
+	/// The hardware counter, which Tracy uses without any checks:
+	/// `mach_absolute_time` on iOS or the time stamp counter on
+	/// Windows on non-x86 CPUs.
+	HardwareCounter,
+	/// The monotonic clock of the OS. It is a bit more expensive to
+	/// query, but works on any hardware.
+	OsClock,
+	/// The CPU has no invariant time stamp counter, so the capture
+	/// can't start & [`start_capture`] panics. Enable
+	/// `timer-fallback` feature to use the OS clock instead.
+	///
+	/// [`start_capture`]: crate::start_capture
+	Unsupported,
+}
+
+impl TimerSource {
+	/// Returns the human-readable description of the timer source.
+	pub const fn describe(self) -> &'static str {
+		match self {
+			Self::InvariantTsc    => "Timer: invariant TSC",
+			Self::UncheckedTsc    => "Timer: TSC, which is not invariant, TRACY_NO_INVARIANT_CHECK is set",
+			Self::HardwareCounter => "Timer: hardware counter",
+			Self::OsClock         => "Timer: OS monotonic clock",
+			Self::Unsupported     => "Timer: none, the CPU has no invariant TSC, enable `timer-fallback` feature",
+		}
+	}
+}
+
+/// Returns the source of the time stamps Tracy uses on this machine.
+///
+/// On x86 Tracy relies on the invariant time stamp counter of the CPU
+/// and refuses to start without one, unless `timer-fallback` feature
+/// is enabled. On Windows on other CPUs and on iOS Tracy uses the
+/// hardware counter unconditionally. Other platforms always use the
+/// OS clock. The source is also reported via [`app_info`], when the
+/// capture starts, so distorted or zero-length zones on exotic
+/// hardware could be explained from the trace alone.
+///
+/// [`app_info`]: crate::app_info
+pub fn timer_source() -> TimerSource {
+	#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+	{
+		if has_invariant_tsc() {
+			TimerSource::InvariantTsc
+		} else if std::env::var_os("TRACY_NO_INVARIANT_CHECK").is_some_and(|v| v.as_encoded_bytes().starts_with(b"1")) {
+			TimerSource::UncheckedTsc
+		} else if cfg!(feature = "timer-fallback") {
+			TimerSource::OsClock
+		} else {
+			TimerSource::Unsupported
+		}
+	}
+	#[cfg(all(not(any(target_arch = "x86", target_arch = "x86_64")), any(windows, target_os = "ios")))]
+	{
+		TimerSource::HardwareCounter
+	}
+	#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", windows, target_os = "ios")))]
+	{
+		TimerSource::OsClock
+	}
+}
+
+/// Mirrors the check Tracy does at the start.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn has_invariant_tsc() -> bool {
+	#[cfg(target_arch = "x86")]
+	use std::arch::x86::__cpuid;
+	#[cfg(target_arch = "x86_64")]
+	use std::arch::x86_64::__cpuid;
+
+	const RDTSC:         u32 = 1 << 4;
+	const INVARIANT_TSC: u32 = 1 << 8;
+
+	let has_rdtsc = __cpuid(1).edx & RDTSC != 0;
+	let max_leaf  = __cpuid(0x8000_0000).eax;
+	has_rdtsc && max_leaf >= 0x8000_0007 && __cpuid(0x8000_0007).edx & INVARIANT_TSC != 0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn round_trip() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let now = Instant::now();
+		for offset in [Duration::ZERO, Duration::from_micros(250), Duration::from_millis(40)] {
+			for instant in [now - offset, now + offset] {
+				let back = from_tracy_time(to_tracy_time(instant));
+				assert!(signed_nanos(instant, back).abs() < 50_000, "{instant:?} became {back:?}");
+			}
+		}
+		assert!(to_tracy_time(now) < to_tracy_time(now + Duration::from_millis(1)));
+	}
+
+	#[test]
+	fn timer_sources() {
+		let source = timer_source();
+		assert_eq!(source, timer_source());
+		assert!(source.describe().starts_with("Timer: "));
+		#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", windows, target_os = "ios")))]
+		assert_eq!(source, TimerSource::OsClock);
+	}
+}
@@ -0,0 +1,201 @@
+use std::marker::PhantomData;
+#[cfg(feature = "enabled")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "enabled")]
+use crate::Zone;
+use crate::ZoneLocation;
+
+/// Creates a [`CoalescedZone`], which emits a single zone per the
+/// given amount of loop iterations.
+///
+/// Optionally, a custom [`Color`] could be assigned to the zone.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn decode(block: &u8) {}
+/// # let blocks = vec![0u8; 4096];
+/// let mut decoding = coalesced_zone!("decode block", flush_every: 1024);
+/// for block in &blocks {
+///     let _iteration = decoding.iteration();
+///     decode(block);
+/// }
+/// ```
+///
+/// [`Color`]: crate::Color
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! coalesced_zone {
+	($name:literal, flush_every:$n:expr) => {
+		$crate::coalesced_zone!($name, $crate::Color::UNSPECIFIED, flush_every:$n)
+	};
+	($name:literal, $color:expr, flush_every:$n:expr) => {
+		$crate::CoalescedZone::new($crate::zone!(@loc $name, $color), $n)
+	};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! coalesced_zone {
+	($name:literal, flush_every:$n:expr) => {
+		$crate::CoalescedZone::compiled_out($n)
+	};
+	($name:literal, $color:expr, flush_every:$n:expr) => {{
+		// Silences unused `Color` import warning.
+		_ = $color;
+		$crate::CoalescedZone::compiled_out($n)
+	}};
+}
+
+/// A zone coalescing iterations of a tight loop.
+///
+/// Emitting a zone per iteration of a tight loop floods the capture
+/// and adds noticeable overhead, while a single zone around the loop
+/// hides how it progresses. A coalesced zone accumulates iterations
+/// instead: a single representative zone spans every `flush_every`
+/// iterations and gets their count & total duration attached as a
+/// text. With `no-dynamic-strings` feature, both are attached as
+/// numbers, the total is in nanoseconds.
+///
+/// The representative zone begins with the first accumulated
+/// iteration and ends with the last one, or when the coalesced zone
+/// is flushed or dropped. Hence, it must not outlive the enclosing
+/// zones, same as [`Zone`].
+///
+/// Refer to [`coalesced_zone!`] for the usage how-to.
+///
+/// [`Zone`]: crate::Zone
+pub struct CoalescedZone {
+	#[cfg(feature = "enabled")]
+	location:    &'static ZoneLocation,
+	#[cfg(feature = "enabled")]
+	flush_every: u32,
+	#[cfg(feature = "enabled")]
+	count:       u32,
+	#[cfg(feature = "enabled")]
+	total:       Duration,
+	#[cfg(feature = "enabled")]
+	zone:        Option<Zone>,
+	_unsend:     PhantomData<*mut ()>,
+}
+
+impl CoalescedZone {
+	/// Creates a coalesced zone at the given location, which emits a
+	/// zone per `flush_every` iterations.
+	pub const fn new(location: &'static ZoneLocation, flush_every: u32) -> Self {
+		#[cfg(not(feature = "enabled"))]
+		{
+			// Silences unused variable warning.
+			_ = (location, flush_every);
+		}
+		Self {
+			#[cfg(feature = "enabled")]
+			location,
+			#[cfg(feature = "enabled")]
+			flush_every,
+			#[cfg(feature = "enabled")]
+			count:   0,
+			#[cfg(feature = "enabled")]
+			total:   Duration::ZERO,
+			#[cfg(feature = "enabled")]
+			zone:    None,
+			_unsend: PhantomData,
+		}
+	}
+
+	#[doc(hidden)]
+	#[cfg(not(feature = "enabled"))]
+	pub const fn compiled_out(flush_every: u32) -> Self {
+		Self { _unsend: PhantomData }
+	}
+
+	/// Starts an iteration, which ends when the returned guard is
+	/// dropped.
+	#[inline(always)]
+	pub fn iteration(&mut self) -> CoalescedIteration<'_> {
+		#[cfg(feature = "enabled")]
+		if self.zone.is_none() {
+			self.zone = Some(Zone::begin(self.location));
+		}
+		CoalescedIteration {
+			#[cfg(feature = "enabled")]
+			started: Instant::now(),
+			zone:    self,
+		}
+	}
+
+	/// Ends the representative zone right away, even if fewer than
+	/// `flush_every` iterations were accumulated.
+	pub fn flush(&mut self) {
+		#[cfg(feature = "enabled")]
+		if let Some(zone) = self.zone.take() {
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			zone.text_fmt(format_args!("{} iterations, {:?} total", self.count, self.total));
+			#[cfg(feature = "no-dynamic-strings")]
+			{
+				zone.number(self.count as u64);
+				zone.number(self.total.as_nanos() as u64);
+			}
+			self.count = 0;
+			self.total = Duration::ZERO;
+		}
+	}
+}
+
+impl Drop for CoalescedZone {
+	fn drop(&mut self) {
+		self.flush();
+	}
+}
+
+/// An iteration of the [`CoalescedZone`], which ends when dropped.
+pub struct CoalescedIteration<'a> {
+	#[cfg(feature = "enabled")]
+	started: Instant,
+	#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+	zone:    &'a mut CoalescedZone,
+}
+
+impl Drop for CoalescedIteration<'_> {
+	#[inline(always)]
+	fn drop(&mut self) {
+		#[cfg(feature = "enabled")]
+		{
+			let zone = &mut *self.zone;
+			zone.total += self.started.elapsed();
+			zone.count += 1;
+			if zone.count >= zone.flush_every {
+				zone.flush();
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn iterations_are_coalesced() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let mut coalesced = coalesced_zone!("coalesced", flush_every: 3);
+		for _ in 0..4 {
+			let _iteration = coalesced.iteration();
+		}
+		#[cfg(feature = "enabled")]
+		assert_eq!((coalesced.count, coalesced.zone.is_some()), (1, true));
+		coalesced.flush();
+		#[cfg(feature = "enabled")]
+		assert_eq!((coalesced.count, coalesced.zone.is_some()), (0, false));
+
+		let mut colored = coalesced_zone!("coalesced", crate::Color::RED, flush_every: 0);
+		drop(colored.iteration());
+
+		static LOCATION: crate::ZoneLocation = crate::ZoneLocation::new(
+			c"located", c"iterations_are_coalesced", c"coalesce.rs", 1, crate::Color::UNSPECIFIED,
+		);
+		let mut located = crate::CoalescedZone::new(&LOCATION, 1);
+		drop(located.iteration());
+	}
+}
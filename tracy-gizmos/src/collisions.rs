@@ -0,0 +1,186 @@
+//! Detection of the conflicting frame & plot names in debug builds.
+//!
+//! Tracy silently mixes up the data, when the same name is used
+//! both for a continuous and a discontinuous frame set, or when a
+//! plot is configured differently in several places. Such mistakes
+//! are reported once per name via a yellow message.
+//!
+//! Names are static, so every thread checks a name & its usage only
+//! once by pointer, without locking the registry on every frame mark.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::sync::Mutex;
+
+use crate::{Color, PlotConfig, PlotFormat, PlotStyle};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum FrameKind {
+	Continuous,
+	Discontinuous,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Check {
+	Frame(FrameKind),
+	Plot { format: PlotFormat, style: PlotStyle, filled: bool, color: u32 },
+}
+
+impl From<PlotConfig> for Check {
+	fn from(config: PlotConfig) -> Self {
+		Self::Plot {
+			format: config.format,
+			style:  config.style,
+			filled: config.filled,
+			color:  config.color.as_u32(),
+		}
+	}
+}
+
+thread_local! {
+	static CHECKED: RefCell<HashSet<(usize, Check)>> = RefCell::new(HashSet::new());
+}
+
+/// Returns `true`, if the name is used this way for the first time
+/// on the current thread.
+#[inline(always)]
+fn first_check(name: &'static CStr, check: Check) -> bool {
+	CHECKED.with_borrow_mut(|checked| checked.insert((name.as_ptr() as usize, check)))
+}
+
+struct Usage<T> {
+	first:    T,
+	reported: bool,
+}
+
+#[derive(Default)]
+struct Registry {
+	frames: HashMap<&'static CStr, Usage<FrameKind>>,
+	plots:  HashMap<&'static CStr, Usage<PlotConfig>>,
+}
+
+static REGISTRY: Mutex<Option<Registry>> = Mutex::new(None);
+
+fn with_registry<R>(f: impl FnOnce(&mut Registry) -> R) -> R {
+	let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+	f(registry.get_or_insert_with(Registry::default))
+}
+
+/// Checks a continuous frame mark, null `name` stands for the main
+/// frame.
+///
+/// # Safety
+///
+/// Non-null `name` must be null-terminated and live until the end of
+/// the program.
+#[inline(always)]
+pub(crate) unsafe fn frame(name: *const c_char) {
+	if !name.is_null() {
+		check_frame_once(CStr::from_ptr(name), FrameKind::Continuous);
+	}
+}
+
+/// Checks a discontinuous frame start.
+///
+/// # Safety
+///
+/// `name` must be null-terminated and live until the end of the
+/// program.
+#[inline(always)]
+pub(crate) unsafe fn discontinuous_frame(name: *const c_char) {
+	check_frame_once(CStr::from_ptr(name), FrameKind::Discontinuous);
+}
+
+/// Checks a plot configuration.
+#[inline(always)]
+pub(crate) fn plot(name: &'static CStr, config: PlotConfig) {
+	if first_check(name, config.into()) {
+		warn(check_plot(name, config));
+	}
+}
+
+#[inline(always)]
+fn check_frame_once(name: &'static CStr, kind: FrameKind) {
+	if first_check(name, Check::Frame(kind)) {
+		warn(check_frame(name, kind));
+	}
+}
+
+fn warn(warning: Option<String>) {
+	if let Some(warning) = warning {
+		crate::details::message_size_color(&warning, Color::YELLOW);
+	}
+}
+
+fn check_frame(name: &'static CStr, kind: FrameKind) -> Option<String> {
+	with_registry(|registry| {
+		let usage = registry.frames
+			.entry(name)
+			.or_insert(Usage { first: kind, reported: false });
+		if usage.first == kind || usage.reported {
+			return None;
+		}
+		usage.reported = true;
+		Some(format!(
+			"Name collision: `{}` is used both as a continuous and a discontinuous frame",
+			name.to_string_lossy(),
+		))
+	})
+}
+
+fn check_plot(name: &'static CStr, config: PlotConfig) -> Option<String> {
+	with_registry(|registry| {
+		let usage = registry.plots
+			.entry(name)
+			.or_insert(Usage { first: config, reported: false });
+		if same_config(&usage.first, &config) || usage.reported {
+			return None;
+		}
+		usage.reported = true;
+		Some(format!(
+			"Name collision: plot `{}` is configured as both {:?} and {config:?}",
+			name.to_string_lossy(),
+			usage.first,
+		))
+	})
+}
+
+fn same_config(a: &PlotConfig, b: &PlotConfig) -> bool {
+	Check::from(*a) == Check::from(*b)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn frames_collide_once() {
+		let name = c"collisions: frames";
+		assert_eq!(check_frame(name, FrameKind::Discontinuous), None);
+		assert_eq!(check_frame(name, FrameKind::Discontinuous), None);
+		assert!(check_frame(name, FrameKind::Continuous).is_some());
+		assert_eq!(check_frame(name, FrameKind::Continuous), None);
+	}
+
+	#[test]
+	fn plots_collide_once() {
+		let name   = c"collisions: plots";
+		let filled = PlotConfig { filled: true, ..Default::default() };
+		assert_eq!(check_plot(name, PlotConfig::default()), None);
+		assert_eq!(check_plot(name, PlotConfig::default()), None);
+		assert!(check_plot(name, filled).is_some());
+		assert_eq!(check_plot(name, filled), None);
+	}
+
+	#[test]
+	fn checked_once_per_pointer() {
+		let name = c"collisions: pointers";
+		assert!(first_check(name, Check::Frame(FrameKind::Continuous)));
+		assert!(!first_check(name, Check::Frame(FrameKind::Continuous)));
+		assert!(first_check(name, Check::Frame(FrameKind::Discontinuous)));
+		assert!(first_check(name, PlotConfig::default().into()));
+		assert!(!first_check(name, PlotConfig::default().into()));
+	}
+}
@@ -0,0 +1,180 @@
+//! Commands to control the profiler from debug consoles.
+
+use crate::Level;
+
+/// A command controlling the profiler, refer to
+/// [`profiler_commands`].
+#[derive(Debug, Clone, Copy)]
+pub struct ProfilerCommand {
+	/// Name of the command, e.g. `tracy.pause`.
+	pub name:  &'static str,
+	/// Arguments of the command, if any, e.g. `<patterns...>`.
+	pub usage: &'static str,
+	/// Short description of the command.
+	pub help:  &'static str,
+	run:       fn(&str) -> Result<String, String>,
+}
+
+impl ProfilerCommand {
+	/// Runs the command with the whitespace-separated arguments.
+	///
+	/// Returns the text to show back to the user on success, or the
+	/// error description otherwise.
+	pub fn run(&self, args: &str) -> Result<String, String> {
+		(self.run)(args.trim())
+	}
+}
+
+/// Returns the commands, which allow in-game consoles or debug HTTP
+/// endpoints to control the profiler without any custom glue.
+///
+/// | Command              | Action                                 |
+/// |----------------------|----------------------------------------|
+/// | `tracy.pause`        | [`set_paused`]`(true)`                 |
+/// | `tracy.resume`       | [`set_paused`]`(false)`                |
+/// | `tracy.level`        | [`set_zone_level`]                     |
+/// | `tracy.threads`      | [`set_thread_filter`]                  |
+/// | `tracy.interner-cap` | [`set_interner_cap`]                   |
+/// | `tracy.stats`        | Reports the state of the profiler      |
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn console_input() -> String { String::new() }
+/// let _tracy = start_capture();
+/// let input = console_input();
+/// let (name, args) = input.split_once(' ').unwrap_or((&input, ""));
+/// match profiler_commands().iter().find(|command| command.name == name) {
+///     Some(command) => match command.run(args) {
+///         Ok(output) => println!("{output}"),
+///         Err(error) => println!("{name}: {error}, usage: {name} {}", command.usage),
+///     },
+///     None => {
+///         for command in profiler_commands() {
+///             println!("{} {} - {}", command.name, command.usage, command.help);
+///         }
+///     },
+/// }
+/// ```
+///
+/// [`set_paused`]: crate::set_paused
+/// [`set_zone_level`]: crate::set_zone_level
+/// [`set_thread_filter`]: crate::set_thread_filter
+/// [`set_interner_cap`]: crate::set_interner_cap
+pub fn profiler_commands() -> &'static [ProfilerCommand] {
+	&COMMANDS
+}
+
+static COMMANDS: [ProfilerCommand; 6] = [
+	ProfilerCommand {
+		name:  "tracy.pause",
+		usage: "",
+		help:  "pauses the instrumentation",
+		run:   |args| {
+			no_args(args)?;
+			crate::set_paused(true);
+			Ok("paused".into())
+		},
+	},
+	ProfilerCommand {
+		name:  "tracy.resume",
+		usage: "",
+		help:  "resumes the instrumentation",
+		run:   |args| {
+			no_args(args)?;
+			crate::set_paused(false);
+			Ok("resumed".into())
+		},
+	},
+	ProfilerCommand {
+		name:  "tracy.level",
+		usage: "<essential|normal|detailed|verbose>",
+		help:  "sets the most verbose level of emitted zones",
+		run:   |args| {
+			let level = parse_level(args)?;
+			crate::set_zone_level(level);
+			Ok(format!("zone level: {level:?}"))
+		},
+	},
+	ProfilerCommand {
+		name:  "tracy.threads",
+		usage: "[patterns...]",
+		help:  "emits only from threads matching the patterns, all threads if none",
+		run:   |args| {
+			crate::set_thread_filter(args.split_whitespace());
+			if args.is_empty() {
+				Ok("thread filter removed".into())
+			} else {
+				Ok(format!("thread filter: {args}"))
+			}
+		},
+	},
+	ProfilerCommand {
+		name:  "tracy.interner-cap",
+		usage: "<names>",
+		help:  "sets the maximum amount of interned run-time names",
+		run:   |args| {
+			let cap = args.parse().map_err(|_| format!("invalid amount of names `{args}`"))?;
+			crate::set_interner_cap(cap);
+			Ok(format!("interner cap: {cap}"))
+		},
+	},
+	ProfilerCommand {
+		name:  "tracy.stats",
+		usage: "",
+		help:  "reports the state of the profiler",
+		run:   |args| {
+			no_args(args)?;
+			let interner = crate::interner_stats();
+			Ok(format!(
+				"paused: {}, interned names: {} ({} bytes, {} overflows)",
+				crate::is_paused(),
+				interner.names,
+				interner.bytes,
+				interner.overflows,
+			))
+		},
+	},
+];
+
+fn no_args(args: &str) -> Result<(), String> {
+	if args.is_empty() {
+		Ok(())
+	} else {
+		Err(format!("unexpected arguments `{args}`"))
+	}
+}
+
+fn parse_level(args: &str) -> Result<Level, String> {
+	match args {
+		"essential" => Ok(Level::Essential),
+		"normal"    => Ok(Level::Normal),
+		"detailed"  => Ok(Level::Detailed),
+		"verbose"   => Ok(Level::Verbose),
+		_           => Err(format!("unknown level `{args}`")),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn run(name: &str, args: &str) -> Result<String, String> {
+		let command = profiler_commands().iter().find(|command| command.name == name).unwrap();
+		command.run(args)
+	}
+
+	#[test]
+	fn console_commands() {
+		let names: std::collections::HashSet<_> = profiler_commands().iter().map(|command| command.name).collect();
+		assert_eq!(names.len(), profiler_commands().len());
+
+		assert_eq!(parse_level("detailed"), Ok(Level::Detailed));
+		assert!(parse_level("loud").is_err());
+		assert!(run("tracy.level", "").is_err());
+		assert!(run("tracy.pause", "now").is_err());
+		assert!(run("tracy.interner-cap", "many").is_err());
+		assert!(run("tracy.stats", "").unwrap().starts_with("paused: false"));
+	}
+}
@@ -0,0 +1,151 @@
+#[cfg(feature = "enabled")]
+use std::cell::RefCell;
+use std::fmt::Display;
+
+#[cfg(feature = "enabled")]
+use crate::Zone;
+
+#[cfg(feature = "enabled")]
+thread_local! {
+	static CONTEXT: RefCell<Vec<(&'static str, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Sets the context field of the current thread, which is attached
+/// to every [`zone_ctx!`] zone as the `key=value` text.
+///
+/// It lets the data, like a server request ID, flow onto every zone
+/// of a handler without passing it around. Setting the same key again
+/// replaces its value, while fields are attached in the order they
+/// were first set.
+///
+/// The context is per-thread, so it should be set on the thread
+/// handling the request and cleared via [`clear_context`] once it is
+/// done, as threads of a pool are reused.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// fn handle(request_id: u64) {
+///     set_context("request_id", request_id);
+///     parse();
+///     clear_context("request_id");
+/// }
+///
+/// fn parse() {
+///     // Gets `request_id=<id>` text.
+///     zone_ctx!("parse");
+/// }
+/// ```
+///
+/// [`zone_ctx!`]: crate::zone_ctx!
+pub fn set_context(key: &'static str, value: impl Display) {
+	#[cfg(feature = "enabled")]
+	CONTEXT.with_borrow_mut(|context| {
+		let value = value.to_string();
+		match context.iter_mut().find(|(k, _)| *k == key) {
+			Some((_, v)) => *v = value,
+			None         => context.push((key, value)),
+		}
+	});
+	#[cfg(not(feature = "enabled"))]
+	{
+		// Silences unused variable warning.
+		_ = (key, value);
+	}
+}
+
+/// Removes the context field of the current thread, refer to
+/// [`set_context`].
+pub fn clear_context(key: &'static str) {
+	#[cfg(feature = "enabled")]
+	CONTEXT.with_borrow_mut(|context| context.retain(|(k, _)| *k != key));
+	#[cfg(not(feature = "enabled"))]
+	{
+		// Silences unused variable warning.
+		_ = key;
+	}
+}
+
+/// Attaches the context fields of the current thread to the zone.
+#[cfg(feature = "enabled")]
+pub fn attach(zone: &Zone) {
+	CONTEXT.with_borrow(|context| {
+		for (key, value) in context {
+			zone.field(key, value);
+		}
+	});
+}
+
+/// Instruments the current scope with a profiling zone, which gets
+/// the context fields of the current thread as its text.
+///
+/// It accepts the name, the optional variable & color, same as
+/// [`zone!`]. The fields are set via [`set_context`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # let path = "level.bin";
+/// set_context("request_id", 42);
+/// zone_ctx!("load");
+/// zone_ctx!(loading, "load", Color::TEAL);
+/// loading.text(path);
+/// ```
+///
+/// [`zone!`]: crate::zone!
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! zone_ctx {
+	($name:literal $(, $color:expr)?) => {
+		$crate::zone_ctx!(_z, $name $(, $color)?);
+	};
+	($var:ident, $name:literal $(, $color:expr)?) => {
+		$crate::zone!($var, $name $(, $color)?);
+		$crate::dynamic_string! {
+			$crate::details::attach_context(&$var);
+		}
+	};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! zone_ctx {
+	($($var:ident,)? $name:literal $(, $color:expr)?) => {
+		$crate::zone!($($var,)? $name $(, $color)?);
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn handle() {
+		zone_ctx!("handle");
+		zone_ctx!(named, "handle", crate::Color::RED);
+		named.text("named");
+	}
+
+	#[test]
+	fn context_fields() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		set_context("request_id", 1);
+		set_context("user", "admin");
+		set_context("request_id", 2);
+		handle();
+
+		#[cfg(feature = "enabled")]
+		CONTEXT.with_borrow(|context| {
+			assert_eq!(context, &[("request_id", "2".to_string()), ("user", "admin".to_string())]);
+		});
+
+		clear_context("request_id");
+		clear_context("user");
+		handle();
+
+		#[cfg(feature = "enabled")]
+		CONTEXT.with_borrow(|context| assert!(context.is_empty()));
+	}
+}
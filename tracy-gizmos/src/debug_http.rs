@@ -0,0 +1,333 @@
+//! Tiny HTTP endpoint to inspect & control the profiler of headless
+//! services.
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use crate::background::Background;
+
+/// Running debug HTTP endpoint, refer to [`serve_debug_http`].
+///
+/// The server is stopped, when dropped.
+#[must_use = "the server is stopped, when dropped"]
+pub struct DebugHttp {
+	addr:       SocketAddr,
+	background: Option<Background>,
+}
+
+impl DebugHttp {
+	/// Returns the address the server is listening on, e.g. to find
+	/// out the port chosen by the system.
+	pub fn local_addr(&self) -> SocketAddr {
+		self.addr
+	}
+}
+
+impl Drop for DebugHttp {
+	fn drop(&mut self) {
+		if let Some(background) = self.background.take() {
+			background.stop();
+		}
+	}
+}
+
+/// Starts a tiny HTTP server on the localhost, which exposes the
+/// profiler state and accepts [`profiler_commands`].
+///
+/// It is meant for headless services, where there is no in-app UI to
+/// control the profiler. Pass `0` to let the system choose the port.
+/// Requests are served one by one on a background thread:
+///
+/// | Request                | Response                                  |
+/// |------------------------|-------------------------------------------|
+/// | `GET /state`           | JSON with the capture state               |
+/// | `GET /commands`        | JSON with the available commands          |
+/// | `POST /command/<name>` | Runs the command with the body as args    |
+///
+/// The state includes whether the capture is connected to the
+/// server, whether it is paused, emitted events per second (only with
+/// `self-profile` feature, `null` otherwise), the zone level, the
+/// thread filter & the interner statistics.
+///
+/// Any local process could still reach the endpoint, but browsers
+/// are kept out: requests with an `Origin` header or with a `Host`
+/// other than `localhost` or `127.0.0.1` are rejected with `403`, so
+/// web pages can't drive the profiler, even via DNS rebinding.
+/// Request lines & headers are limited in size & count.
+///
+/// # Errors
+///
+/// Returns an error if the port can't be bound.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn serve() {}
+/// let _tracy = start_capture();
+/// let _http  = serve_debug_http(9090).unwrap();
+/// // $ curl localhost:9090/state
+/// // $ curl -d verbose localhost:9090/command/tracy.level
+/// serve();
+/// ```
+///
+/// [`profiler_commands`]: crate::profiler_commands
+pub fn serve_debug_http(port: u16) -> io::Result<DebugHttp> {
+	const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+	let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+	listener.set_nonblocking(true)?;
+	let addr = listener.local_addr()?;
+
+	let background = Background::spawn("tracy-gizmos debug http", move |stop| loop {
+		match listener.accept() {
+			// A broken request just drops the connection.
+			Ok((stream, _)) => _ = serve(stream),
+			// Either nobody is connecting or the connection failed.
+			Err(_) => {
+				if stop.wait(POLL_INTERVAL) {
+					return;
+				}
+			},
+		}
+	});
+
+	Ok(DebugHttp { addr, background: Some(background) })
+}
+
+fn serve(stream: TcpStream) -> io::Result<()> {
+	const TIMEOUT:     Duration = Duration::from_secs(1);
+	const MAX_BODY:    usize    = 4096;
+	const MAX_HEADERS: usize    = 32;
+	const FORBIDDEN:   &str     = "403 Forbidden";
+
+	stream.set_nonblocking(false)?;
+	stream.set_read_timeout(Some(TIMEOUT))?;
+	stream.set_write_timeout(Some(TIMEOUT))?;
+
+	let mut reader = BufReader::new(&stream);
+	let mut line   = String::new();
+	read_line(&mut reader, &mut line)?;
+	let mut parts  = line.split_whitespace();
+	let method     = parts.next().unwrap_or_default().to_owned();
+	let path       = parts.next().unwrap_or_default().to_owned();
+
+	let mut length  = 0;
+	let mut allowed = true;
+	for _ in 0..=MAX_HEADERS {
+		line.clear();
+		if read_line(&mut reader, &mut line)? == 0 || line.trim().is_empty() {
+			break;
+		}
+		let Some((name, value)) = line.split_once(':') else {
+			continue;
+		};
+		let (name, value) = (name.trim(), value.trim());
+		if name.eq_ignore_ascii_case("content-length") {
+			length = value.parse().unwrap_or(0);
+		} else if name.eq_ignore_ascii_case("origin") {
+			allowed = false;
+		} else if name.eq_ignore_ascii_case("host") {
+			allowed &= is_local_host(value);
+		}
+	}
+	if !line.trim().is_empty() {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "too many headers"));
+	}
+
+	let (status, json) = if allowed {
+		let mut body = vec![0; length.min(MAX_BODY)];
+		reader.read_exact(&mut body)?;
+		respond(&method, &path, &String::from_utf8_lossy(&body))
+	} else {
+		(FORBIDDEN, r#"{"error":"forbidden"}"#.into())
+	};
+	let mut stream = &stream;
+	write!(
+		stream,
+		"HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{json}",
+		json.len(),
+	)?;
+	stream.flush()
+}
+
+/// Reads the line, failing if it is too long.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<usize> {
+	const MAX_LINE: u64 = 1024;
+
+	let read = reader.take(MAX_LINE).read_line(line)?;
+	if read as u64 == MAX_LINE && !line.ends_with('\n') {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "line is too long"));
+	}
+	Ok(read)
+}
+
+/// Returns `true`, if the `Host` header value names the loopback.
+fn is_local_host(host: &str) -> bool {
+	let name = match host.rsplit_once(':') {
+		Some((name, port)) if port.bytes().all(|b| b.is_ascii_digit()) => name,
+		_                                                               => host,
+	};
+	name.eq_ignore_ascii_case("localhost") || name == "127.0.0.1"
+}
+
+fn respond(method: &str, path: &str, body: &str) -> (&'static str, String) {
+	const OK:          &str = "200 OK";
+	const BAD_REQUEST: &str = "400 Bad Request";
+	const NOT_FOUND:   &str = "404 Not Found";
+
+	match (method, path) {
+		("GET", "/state")    => (OK, state()),
+		("GET", "/commands") => {
+			let mut json = String::from("[");
+			for (i, command) in crate::profiler_commands().iter().enumerate() {
+				if i > 0 {
+					json.push(',');
+				}
+				_ = write!(
+					json,
+					r#"{{"name":{},"usage":{},"help":{}}}"#,
+					quoted(command.name),
+					quoted(command.usage),
+					quoted(command.help),
+				);
+			}
+			json.push(']');
+			(OK, json)
+		},
+		("POST", path) => {
+			let command = path
+				.strip_prefix("/command/")
+				.and_then(|name| crate::profiler_commands().iter().find(|command| command.name == name));
+			match command.map(|command| command.run(body)) {
+				Some(Ok(output)) => (OK,          format!(r#"{{"ok":true,"output":{}}}"#,  quoted(&output))),
+				Some(Err(error)) => (BAD_REQUEST, format!(r#"{{"ok":false,"error":{}}}"#, quoted(&error))),
+				None             => (NOT_FOUND,   r#"{"ok":false,"error":"unknown command"}"#.into()),
+			}
+		},
+		_ => (NOT_FOUND, r#"{"error":"not found"}"#.into()),
+	}
+}
+
+fn state() -> String {
+	#[cfg(feature = "enabled")]
+	let connected = crate::connected();
+	#[cfg(not(feature = "enabled"))]
+	let connected = false;
+
+	#[cfg(feature = "enabled")]
+	let events_per_sec = crate::self_profile::events_per_sec();
+	#[cfg(not(feature = "enabled"))]
+	let events_per_sec: Option<f64> = None;
+
+	let threads  = crate::thread_filter::patterns();
+	let interner = crate::interner_stats();
+
+	let mut json = String::new();
+	_ = write!(
+		json,
+		r#"{{"enabled":{},"connected":{connected},"paused":{},"events_per_sec":"#,
+		cfg!(feature = "enabled"),
+		crate::is_paused(),
+	);
+	match events_per_sec {
+		Some(rate) => _ = write!(json, "{rate:.1}"),
+		None       => json.push_str("null"),
+	}
+	_ = write!(
+		json,
+		r#","zone_level":"{:?}","thread_filter":["#,
+		crate::level::zone_level(),
+	);
+	for (i, pattern) in threads.iter().enumerate() {
+		if i > 0 {
+			json.push(',');
+		}
+		json.push_str(&quoted(pattern));
+	}
+	_ = write!(
+		json,
+		r#"],"interner":{{"names":{},"bytes":{},"overflows":{}}}}}"#,
+		interner.names,
+		interner.bytes,
+		interner.overflows,
+	);
+	json
+}
+
+/// Returns the string as a JSON string literal.
+fn quoted(s: &str) -> String {
+	let mut json = String::with_capacity(s.len() + 2);
+	json.push('"');
+	for c in s.chars() {
+		match c {
+			'"'  => json.push_str("\\\""),
+			'\\' => json.push_str("\\\\"),
+			'\n' => json.push_str("\\n"),
+			'\r' => json.push_str("\\r"),
+			'\t' => json.push_str("\\t"),
+			c if c.is_control() => _ = write!(json, "\\u{:04x}", c as u32),
+			c    => json.push(c),
+		}
+	}
+	json.push('"');
+	json
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn request(addr: SocketAddr, request: &str) -> String {
+		let mut stream = TcpStream::connect(addr).unwrap();
+		stream.write_all(request.as_bytes()).unwrap();
+		let mut response = String::new();
+		// Dropped connections could be reset, when the request is
+		// not read fully.
+		_ = stream.read_to_string(&mut response);
+		response
+	}
+
+	#[test]
+	fn debug_http_endpoint() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let http   = serve_debug_http(0).unwrap();
+		let addr   = http.local_addr();
+
+		let state = request(addr, "GET /state HTTP/1.1\r\nHost: localhost\r\n\r\n");
+		assert!(state.starts_with("HTTP/1.1 200 OK\r\n"));
+		assert!(state.contains(r#""paused":false"#));
+		assert!(state.contains(r#""thread_filter":[]"#));
+
+		let commands = request(addr, "GET /commands HTTP/1.1\r\n\r\n");
+		assert!(commands.contains(r#""name":"tracy.stats""#));
+
+		let stats = request(addr, "POST /command/tracy.stats HTTP/1.1\r\nContent-Length: 0\r\n\r\n");
+		assert!(stats.contains(r#"{"ok":true,"output":"paused: false"#));
+
+		let wrong = request(addr, "POST /command/tracy.level HTTP/1.1\r\nContent-Length: 4\r\n\r\nloud");
+		assert!(wrong.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+		assert!(wrong.contains("unknown level `loud`"));
+
+		let missing = request(addr, "GET /nothing HTTP/1.1\r\n\r\n");
+		assert!(missing.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+		let origin = "POST /command/tracy.pause HTTP/1.1\r\nHost: localhost\r\nOrigin: http://example.com\r\n\r\n";
+		assert!(request(addr, origin).starts_with("HTTP/1.1 403 Forbidden\r\n"));
+		let rebound = request(addr, "GET /state HTTP/1.1\r\nHost: example.com:9090\r\n\r\n");
+		assert!(rebound.starts_with("HTTP/1.1 403 Forbidden\r\n"));
+		assert!(request(addr, "GET /state HTTP/1.1\r\nHost: 127.0.0.1:9090\r\n\r\n").starts_with("HTTP/1.1 200 OK\r\n"));
+
+		let long = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(2048));
+		assert!(request(addr, &long).is_empty());
+		let many = format!("GET /state HTTP/1.1\r\n{}\r\n", "X-Header: 1\r\n".repeat(64));
+		assert!(request(addr, &many).is_empty());
+
+		assert_eq!(quoted("a\"b\\\n\u{1}"), r#""a\"b\\\n\u0001""#);
+		drop(http);
+		assert!(TcpStream::connect(addr).is_err());
+	}
+}
@@ -0,0 +1,246 @@
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+use std::cell::RefCell;
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+use std::collections::HashMap;
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+use std::ffi::CStr;
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+use std::sync::Mutex;
+
+/// Instruments the current scope with a profiling zone, which name
+/// includes the concrete type behind a trait object.
+///
+/// Hot dynamic dispatch sites are hard to read in the timeline, as
+/// every implementation ends up in the same zone. This zone is named
+/// `<Type>::<method>` instead, where `<Type>` is the concrete type
+/// name without module paths.
+///
+/// The trait must have [`AnyNamed`] as a supertrait, which is
+/// implemented via [`any_named!`] for every implementation. The
+/// object must dereference right into the trait object, e.g. `&dyn
+/// Trait` or `Box<dyn Trait>`, while `&Box<dyn Trait>` doesn't
+/// compile.
+///
+/// The zone name is known only at run-time, so it is a bit more
+/// expensive than the regular [`zone!`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// trait Handler: AnyNamed {
+///     fn handle(&self, event: u32);
+/// }
+///
+/// struct Logger;
+/// any_named!(Logger);
+///
+/// impl Handler for Logger {
+///     fn handle(&self, event: u32) {}
+/// }
+///
+/// fn dispatch(handlers: &[Box<dyn Handler>], event: u32) {
+///     for handler in handlers.iter().map(Box::as_ref) {
+///         // Will be shown as e.g. `Logger::handle`.
+///         # #[cfg(not(feature = "no-dynamic-strings"))]
+///         dyn_zone!(handler, "handle");
+///         handler.handle(event);
+///     }
+/// }
+/// ```
+///
+/// Same as with [`zone!`], the zone could be named to attach dynamic
+/// data:
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # trait Handler: AnyNamed { fn handle(&self, event: u32); }
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
+/// # fn dispatch(handler: &dyn Handler, event: u32) {
+/// dyn_zone!(zone, handler, "handle");
+/// zone.number(event as u64);
+/// # }
+/// ```
+///
+/// [`any_named!`]: crate::any_named!
+/// [`zone!`]: crate::zone!
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! dyn_zone {
+	($var:ident, $obj:expr, $method:literal) => {$crate::dynamic_string! {
+		let type_name = $crate::AnyNamed::type_name(&*$obj);
+		#[allow(unused_variables)]
+		let $var = $crate::details::dyn_zone(type_name, $method);
+	}};
+	($obj:expr, $method:literal) => {
+		$crate::dyn_zone!(_z, $obj, $method);
+	};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! dyn_zone {
+	($var:ident, $obj:expr, $method:literal) => {
+		// Silences unused expression warning.
+		_ = &$obj;
+		#[allow(unused_variables)]
+		let $var = $crate::Zone::new();
+	};
+	($obj:expr, $method:literal) => {
+		// Silences unused expression warning.
+		_ = &$obj;
+	};
+}
+
+/// Provides the concrete type name of a value, even if it is behind
+/// a trait object.
+///
+/// Add it as a supertrait to make the type name of the trait objects
+/// available, refer to [`dyn_zone!`].
+///
+/// The trait is sealed, it is implemented only via [`any_named!`] for
+/// the types behind the trait objects. So references & smart pointers
+/// to the objects are rejected instead of being named after
+/// themselves.
+///
+/// [`any_named!`]: crate::any_named!
+pub trait AnyNamed: seal::Sealed {
+	/// Returns the name of the concrete type.
+	///
+	/// Same as [`std::any::type_name`], the exact contents are not
+	/// guaranteed.
+	fn type_name(&self) -> &'static str;
+}
+
+/// Implementation details, do not relay on anything from this module!
+///
+/// It is public only due to the usage in `any_named!` body.
+#[doc(hidden)]
+pub mod seal {
+	pub trait Sealed {}
+}
+
+/// Implements [`AnyNamed`] for the types, refer to [`dyn_zone!`].
+///
+/// # Examples
+///
+/// ```
+/// # use tracy_gizmos::*;
+/// struct Logger;
+/// struct Cache<K, V>(Vec<(K, V)>);
+///
+/// any_named!(Logger);
+/// any_named!(impl<K, V> Cache<K, V>);
+///
+/// assert!(AnyNamed::type_name(&Logger).ends_with("Logger"));
+/// ```
+#[macro_export]
+macro_rules! any_named {
+	(impl<$($generic:ident),+ $(,)?> $ty:ty) => {
+		impl<$($generic),+> $crate::seal::Sealed for $ty {}
+		impl<$($generic),+> $crate::AnyNamed for $ty {
+			#[inline(always)]
+			fn type_name(&self) -> &'static str {
+				::core::any::type_name::<Self>()
+			}
+		}
+	};
+	($($ty:ty),+ $(,)?) => {$(
+		impl $crate::seal::Sealed for $ty {}
+		impl $crate::AnyNamed for $ty {
+			#[inline(always)]
+			fn type_name(&self) -> &'static str {
+				::core::any::type_name::<Self>()
+			}
+		}
+	)+};
+}
+
+/// Zone names interned by the type name & method.
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+static NAMES: Mutex<Option<HashMap<(&str, &str), &CStr>>> = Mutex::new(None);
+
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+thread_local! {
+	// Avoids locking on the hot path, `type_name` results are
+	// static, so their pointers work as keys.
+	static CACHE: RefCell<HashMap<(usize, usize), &'static CStr>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the interned `<Type>::<method>` zone name.
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+pub(crate) fn zone_name(type_name: &'static str, method: &'static str) -> &'static CStr {
+	let key = (type_name.as_ptr() as usize, method.as_ptr() as usize);
+	if let Some(name) = CACHE.with_borrow(|cache| cache.get(&key).copied()) {
+		return name;
+	}
+
+	let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+	let name = *names
+		.get_or_insert_with(HashMap::new)
+		.entry((type_name, method))
+		.or_insert_with(|| {
+			crate::intern::intern(&format!("{}::{method}", short_type_name(type_name)))
+		});
+	drop(names);
+	CACHE.with_borrow_mut(|cache| cache.insert(key, name));
+	name
+}
+
+/// Strips module paths from the type name, including generic
+/// arguments, e.g. `app::Cache<std::string::String>` becomes
+/// `Cache<String>`.
+#[cfg(any(test, all(feature = "enabled", not(feature = "no-dynamic-strings"))))]
+fn short_type_name(full: &str) -> String {
+	let mut short = String::with_capacity(full.len());
+	let mut rest  = full;
+	while let Some(i) = rest.find("::") {
+		let head = &rest[..i];
+		let keep = head
+			.rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+			.map_or(0, |j| j + 1);
+		short.push_str(&head[..keep]);
+		rest = &rest[i + 2..];
+	}
+	short.push_str(rest);
+	short
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	trait Handler: AnyNamed {}
+
+	struct Logger;
+	any_named!(Logger);
+	impl Handler for Logger {}
+
+	#[test]
+	fn short_names() {
+		assert_eq!(short_type_name("Logger"), "Logger");
+		assert_eq!(short_type_name("app::io::Logger"), "Logger");
+		assert_eq!(
+			short_type_name("app::Cache<alloc::string::String, (u32, core::option::Option<app::Key>)>"),
+			"Cache<String, (u32, Option<Key>)>",
+		);
+		assert_eq!(short_type_name("&[app::Key]"), "&[Key]");
+	}
+
+	#[test]
+	fn concrete_type_behind_trait_object() {
+		let handler: Box<dyn Handler> = Box::new(Logger);
+		assert!(AnyNamed::type_name(&*handler).ends_with("::Logger"));
+	}
+
+	#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+	#[test]
+	fn names_are_interned() {
+		let _tracy = crate::start_capture();
+		let handler: &dyn Handler = &Logger;
+		let name = zone_name(AnyNamed::type_name(handler), "handle");
+		assert_eq!(name, c"Logger::handle");
+		assert!(std::ptr::eq(name, zone_name(AnyNamed::type_name(handler), "handle")));
+		dyn_zone!(handler, "handle");
+	}
+}
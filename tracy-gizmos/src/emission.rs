@@ -0,0 +1,88 @@
+//! Emissions into Tracy in progress, which the capture shutdown
+//! waits for.
+//!
+//! Every emission is counted while it checks the capture is running
+//! and calls into Tracy. The shutdown mutes the instrumentation
+//! first and then waits for the counters to drain, so no emission
+//! could reach the stopped profiler. Counters are striped by thread,
+//! so the emitting threads don't contend over a single cache line.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const STRIPES: usize = 16;
+
+#[repr(align(128))]
+struct Stripe(AtomicUsize);
+
+static IN_FLIGHT: [Stripe; STRIPES] = [const { Stripe(AtomicUsize::new(0)) }; STRIPES];
+static NEXT_STRIPE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+	// It has no destructor, so it is usable even while the thread
+	// exits.
+	static STRIPE: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+/// An emission in progress, which holds the shutdown off until
+/// dropped, see [`crate::emission`].
+pub(crate) struct Emission(&'static Stripe);
+
+impl Emission {
+	#[inline(always)]
+	pub(crate) fn enter() -> Self {
+		let stripe = &IN_FLIGHT[stripe()];
+		// Pairs with the muted state check, refer to `crate::emission`.
+		stripe.0.fetch_add(1, Ordering::SeqCst);
+		Self(stripe)
+	}
+}
+
+impl Drop for Emission {
+	#[inline(always)]
+	fn drop(&mut self) {
+		self.0.0.fetch_sub(1, Ordering::Release);
+	}
+}
+
+#[inline(always)]
+fn stripe() -> usize {
+	STRIPE.with(|stripe| {
+		if stripe.get() == usize::MAX {
+			stripe.set(NEXT_STRIPE.fetch_add(1, Ordering::Relaxed) % STRIPES);
+		}
+		stripe.get()
+	})
+}
+
+/// Waits for the emissions in progress to finish.
+///
+/// The instrumentation must be muted already, so no new emissions
+/// could begin.
+pub(crate) fn wait_drained() {
+	for stripe in &IN_FLIGHT {
+		while stripe.0.load(Ordering::SeqCst) != 0 {
+			std::thread::yield_now();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::{Duration, Instant};
+
+	use super::*;
+
+	#[test]
+	fn waits_for_emissions() {
+		let started  = Instant::now();
+		let emission = Emission::enter();
+		let emitter  = std::thread::spawn(move || {
+			std::thread::sleep(Duration::from_millis(10));
+			drop(emission);
+		});
+		wait_drained();
+		assert!(started.elapsed() >= Duration::from_millis(10));
+		emitter.join().unwrap();
+	}
+}
@@ -0,0 +1,106 @@
+//! Mirroring of frames and messages as ETW events.
+//!
+//! When `etw` feature is enabled on Windows, frame marks and messages
+//! are also written as TraceLogging events of the `TracyGizmos`
+//! provider, so captures taken with WPA or xperf can be correlated
+//! with Tracy captures. TraceLogging checks whether any session
+//! listens to the provider first, so it is cheap otherwise.
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+use tracelogging as tlg;
+
+tlg::define_provider!(PROVIDER, "TracyGizmos");
+
+/// Registers the provider, must be paired with [`unregister`].
+pub(crate) fn register() {
+	// SAFETY: The provider is unregistered when the capture is
+	// dropped, while static provider outlives everything.
+	unsafe {
+		PROVIDER.register();
+	}
+}
+
+pub(crate) fn unregister() {
+	PROVIDER.unregister();
+}
+
+/// Mirrors a frame mark. Null `name` stands for the main frame.
+///
+/// # Safety
+///
+/// Non-null `name` must be null-terminated.
+#[inline(always)]
+pub(crate) unsafe fn frame_mark(name: *const c_char) {
+	tlg::write_event!(
+		PROVIDER,
+		"FrameMark",
+		level(Informational),
+		str8("Name", frame_name(name)),
+	);
+}
+
+/// Mirrors a discontinuous frame start.
+///
+/// # Safety
+///
+/// `name` must be null-terminated.
+#[inline(always)]
+pub(crate) unsafe fn frame_start(name: *const c_char) {
+	tlg::write_event!(
+		PROVIDER,
+		"FrameStart",
+		level(Informational),
+		str8("Name", frame_name(name)),
+	);
+}
+
+/// Mirrors a discontinuous frame end.
+///
+/// # Safety
+///
+/// `name` must be null-terminated.
+#[inline(always)]
+pub(crate) unsafe fn frame_end(name: *const c_char) {
+	tlg::write_event!(
+		PROVIDER,
+		"FrameEnd",
+		level(Informational),
+		str8("Name", frame_name(name)),
+	);
+}
+
+/// Mirrors a message.
+///
+/// # Safety
+///
+/// `text` must be null-terminated.
+#[inline(always)]
+pub(crate) unsafe fn message_cstr(text: *const c_char) {
+	tlg::write_event!(
+		PROVIDER,
+		"Message",
+		level(Informational),
+		str8("Text", CStr::from_ptr(text).to_bytes()),
+	);
+}
+
+/// Mirrors a message.
+#[inline(always)]
+pub(crate) fn message(text: &[u8]) {
+	tlg::write_event!(
+		PROVIDER,
+		"Message",
+		level(Informational),
+		str8("Text", text),
+	);
+}
+
+unsafe fn frame_name<'a>(name: *const c_char) -> &'a [u8] {
+	if name.is_null() {
+		b"main"
+	} else {
+		CStr::from_ptr(name).to_bytes()
+	}
+}
@@ -0,0 +1,49 @@
+/// Client features, which change what is collected & how, so they
+/// are reported when the capture starts.
+const FEATURES: &[(&str, bool)] = &[
+	("callstacks",        cfg!(not(feature = "no-callstack"))),
+	("callstack-inlines", cfg!(feature = "callstack-inlines")),
+	("sampling",          cfg!(feature = "sampling")),
+	("context-switch",    cfg!(feature = "context-switch")),
+	("system-tracing",    cfg!(feature = "system-tracing")),
+	("hw-counters",       cfg!(feature = "hw-counters")),
+	("vsync",             cfg!(feature = "vsync")),
+	("code-transfer",     cfg!(feature = "code-transfer")),
+	("frame-images",      cfg!(feature = "frame-images")),
+	("fibers",            cfg!(feature = "fibers")),
+	("broadcast",         cfg!(feature = "broadcast")),
+	("only-localhost",    cfg!(feature = "only-localhost")),
+	("only-ipv4",         cfg!(feature = "only-ipv4")),
+	("crash-handler",     cfg!(feature = "crash-handler")),
+	("timer-fallback",    cfg!(feature = "timer-fallback")),
+	("no-exit",           cfg!(feature = "no-exit")),
+	("dynamic-strings",   cfg!(not(feature = "no-dynamic-strings"))),
+];
+
+/// Returns the features the client was built with, e.g.
+/// `Features: +callstacks -sampling ...`, so captures tell why some
+/// data is missing.
+pub(crate) fn describe() -> String {
+	let mut text = String::from("Features:");
+	for (name, enabled) in FEATURES {
+		text.push(' ');
+		text.push(if *enabled { '+' } else { '-' });
+		text.push_str(name);
+	}
+	text
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn feature_matrix() {
+		let text = describe();
+		assert!(text.starts_with("Features: "));
+		assert_eq!(text.split(' ').count(), FEATURES.len() + 1);
+		assert_eq!(text.contains("+callstacks"), cfg!(not(feature = "no-callstack")));
+		assert_eq!(text.contains("+sampling"), cfg!(feature = "sampling"));
+		assert!(text.contains("+only-localhost"));
+	}
+}
@@ -0,0 +1,238 @@
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+#[cfg(feature = "enabled")]
+use std::ffi::CStr;
+
+/// A pool of stable fiber names for job systems reusing task slots.
+///
+/// Tracy identifies fibers by their names, so a job system creating
+/// a name per job floods the capture with short-lived fibers, while
+/// sharing a name between concurrent jobs interleaves them into a
+/// mess. The pool hands out slots instead: every slot is named
+/// `<prefix>-<index>`, is owned by a single job at a time and is
+/// reused once the job is done.
+///
+/// A job enters its fiber on whatever worker thread runs it and
+/// leaves it when it is suspended or done, so it could migrate
+/// between worker threads. [`FiberSlot::enter`] returns a guard,
+/// which leaves the fiber on the very same thread, so enter & leave
+/// are always paired correctly.
+///
+/// Zones started inside a fiber must end before leaving it, as
+/// [`Zone`] is bound to the thread.
+///
+/// Requires `fibers` feature, slots are still handed out when the
+/// instrumentation is disabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn poll_job(job: u32) -> bool { true }
+/// static FIBERS: FiberPool = FiberPool::new("fiber");
+///
+/// # let job = 42;
+/// let mut slot = FIBERS.acquire();
+/// // On any worker thread, every time the job is polled:
+/// let done = {
+///     let _fiber = slot.enter();
+///     zone!("poll job");
+///     poll_job(job)
+/// };
+/// if done {
+///     // Slot becomes available for the next job.
+///     drop(slot);
+/// }
+/// ```
+///
+/// [`Zone`]: crate::Zone
+pub struct FiberPool {
+	#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+	prefix: &'static str,
+	slots:  Mutex<Slots>,
+}
+
+struct Slots {
+	free:  Vec<usize>,
+	count: usize,
+	#[cfg(feature = "enabled")]
+	names: Vec<&'static CStr>,
+}
+
+impl FiberPool {
+	/// Creates an empty pool, which names slots as `<prefix>-<index>`.
+	///
+	/// Names are allocated once per slot and never freed, as Tracy
+	/// requires them to live until the end of the program. So,
+	/// prefer long-living pools.
+	pub const fn new(prefix: &'static str) -> Self {
+		Self {
+			prefix,
+			slots: Mutex::new(Slots {
+				free:  Vec::new(),
+				count: 0,
+				#[cfg(feature = "enabled")]
+				names: Vec::new(),
+			}),
+		}
+	}
+
+	/// Acquires a free slot, the one with the lowest index is
+	/// preferred. The slot is returned into the pool on drop.
+	///
+	/// # Panics
+	///
+	/// Panics if the prefix contains a null byte.
+	pub fn acquire(&self) -> FiberSlot<'_> {
+		let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+		let index = match slots.free.iter().enumerate().min_by_key(|(_, &i)| i) {
+			Some((at, _)) => slots.free.swap_remove(at),
+			None          => {
+				#[cfg(feature = "enabled")]
+				{
+					let name = fiber_name(self.prefix, slots.count);
+					slots.names.push(name);
+				}
+				slots.count += 1;
+				slots.count - 1
+			},
+		};
+		FiberSlot {
+			pool:  self,
+			index,
+			#[cfg(feature = "enabled")]
+			name:  slots.names[index],
+		}
+	}
+}
+
+/// A fiber slot acquired from the [`FiberPool`].
+///
+/// Refer to [`FiberPool`] for the usage how-to.
+pub struct FiberSlot<'a> {
+	pool:  &'a FiberPool,
+	index: usize,
+	#[cfg(feature = "enabled")]
+	name:  &'static CStr,
+}
+
+impl FiberSlot<'_> {
+	/// Returns the slot index, which is a part of its name.
+	pub fn index(&self) -> usize {
+		self.index
+	}
+
+	/// Enters the slot fiber on the current thread, until the
+	/// returned guard is dropped.
+	pub fn enter(&mut self) -> FiberGuard<'_> {
+		#[cfg(feature = "enabled")]
+		if let Some(_emission) = crate::emission() {
+			// SAFETY: Slot names are static & null-terminated.
+			unsafe {
+				sys::___tracy_fiber_enter(self.name.as_ptr());
+			}
+			crate::self_profile::count_event();
+		}
+		#[cfg(all(debug_assertions, feature = "enabled"))]
+		crate::lifo::enter_fiber(self.name);
+		FiberGuard {
+			#[cfg(all(debug_assertions, feature = "enabled"))]
+			name:    self.name,
+			_slot:   PhantomData,
+			_unsend: PhantomData,
+		}
+	}
+}
+
+impl Drop for FiberSlot<'_> {
+	fn drop(&mut self) {
+		let mut slots = self.pool.slots.lock().unwrap_or_else(|e| e.into_inner());
+		slots.free.push(self.index);
+	}
+}
+
+/// Active fiber, which is left when dropped.
+///
+/// Refer to [`FiberPool`] for the usage how-to.
+pub struct FiberGuard<'a> {
+	#[cfg(all(debug_assertions, feature = "enabled"))]
+	name:    &'static CStr,
+	_slot:   PhantomData<&'a mut ()>,
+	// Fiber must be left on the same thread it was entered on.
+	_unsend: PhantomData<*mut ()>,
+}
+
+impl Drop for FiberGuard<'_> {
+	fn drop(&mut self) {
+		#[cfg(feature = "enabled")]
+		if let Some(_emission) = crate::emission() {
+			// SAFETY: The fiber was entered by this thread.
+			unsafe {
+				sys::___tracy_fiber_leave();
+			}
+			crate::self_profile::count_event();
+		}
+		#[cfg(all(debug_assertions, feature = "enabled"))]
+		crate::lifo::leave_fiber(self.name);
+	}
+}
+
+/// Allocates `<prefix>-<index>\0`, without run-time formatting.
+#[cfg(feature = "enabled")]
+fn fiber_name(prefix: &str, index: usize) -> &'static CStr {
+	let mut name = Vec::with_capacity(prefix.len() + 22);
+	name.extend_from_slice(prefix.as_bytes());
+	name.push(b'-');
+	let start = name.len();
+	let mut rest = index;
+	loop {
+		name.push(b'0' + (rest % 10) as u8);
+		rest /= 10;
+		if rest == 0 {
+			break;
+		}
+	}
+	name[start..].reverse();
+	let name = std::ffi::CString::new(name).expect("Fiber prefix should not contain null bytes.");
+	Box::leak(name.into_boxed_c_str())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn slots_are_reused() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let pool = FiberPool::new("test fiber");
+		let a = pool.acquire();
+		let mut b = pool.acquire();
+		let c = pool.acquire();
+		assert_eq!((a.index(), b.index(), c.index()), (0, 1, 2));
+		drop(a);
+		drop(c);
+		assert_eq!(pool.acquire().index(), 0);
+
+		#[cfg(feature = "enabled")]
+		{
+			assert_eq!(b.name, c"test fiber-1");
+			assert_eq!(fiber_name("f", 120), c"f-120");
+		}
+		// The job migrates to another thread and back.
+		std::thread::scope(|s| {
+			s.spawn(|| {
+				let _fiber = b.enter();
+			});
+		});
+		crate::zone!(outer, "outer");
+		let fiber = b.enter();
+		// The fiber zone is suspended along with the fiber.
+		crate::zone!(suspended, "suspended");
+		drop(fiber);
+		drop(outer);
+		let _fiber = b.enter();
+		drop(suspended);
+	}
+}
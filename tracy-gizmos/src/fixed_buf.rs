@@ -0,0 +1,95 @@
+use std::fmt;
+
+/// Fixed-capacity text buffer, which truncates everything not
+/// fitting into it.
+///
+/// Truncated text ends with `...`, so it is visible in the capture.
+pub struct FixedBuf<const N: usize> {
+	buf:       [u8; N],
+	len:       usize,
+	truncated: bool,
+}
+
+impl<const N: usize> FixedBuf<N> {
+	/// Creates an empty buffer.
+	#[inline(always)]
+	pub const fn new() -> Self {
+		Self { buf: [0; N], len: 0, truncated: false }
+	}
+
+	/// Returns the written text.
+	pub fn as_str(&self) -> &str {
+		// SAFETY: Only whole chars or ASCII dots are written.
+		unsafe { std::str::from_utf8_unchecked(&self.buf[..self.len]) }
+	}
+
+	fn mark_truncated(&mut self) {
+		self.truncated = true;
+		let mut end = self.len.min(N.saturating_sub(3));
+		while !self.as_str().is_char_boundary(end) {
+			end -= 1;
+		}
+		let dots = (N - end).min(3);
+		self.buf[end..end + dots].fill(b'.');
+		self.len = end + dots;
+	}
+}
+
+impl<const N: usize> Default for FixedBuf<N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<const N: usize> fmt::Write for FixedBuf<N> {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		if self.truncated {
+			return Ok(());
+		}
+		let free = N - self.len;
+		if s.len() <= free {
+			self.buf[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+			self.len += s.len();
+			return Ok(());
+		}
+
+		let mut fits = free;
+		while !s.is_char_boundary(fits) {
+			fits -= 1;
+		}
+		self.buf[self.len..self.len + fits].copy_from_slice(&s.as_bytes()[..fits]);
+		self.len += fits;
+		self.mark_truncated();
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fmt::Write;
+
+	use super::*;
+
+	fn format<const N: usize>(args: fmt::Arguments) -> FixedBuf<N> {
+		let mut buf = FixedBuf::<N>::new();
+		buf.write_fmt(args).unwrap();
+		buf
+	}
+
+	#[test]
+	fn fits() {
+		assert_eq!(format::<8>(format_args!("x={}", 42)).as_str(), "x=42");
+		assert_eq!(format::<4>(format_args!("x={}", 42)).as_str(), "x=42");
+		assert_eq!(format::<0>(format_args!("")).as_str(), "");
+	}
+
+	#[test]
+	fn truncates() {
+		assert_eq!(format::<8>(format_args!("value={}", 12345)).as_str(), "value...");
+		assert_eq!(format::<2>(format_args!("abc")).as_str(), "..");
+		assert_eq!(format::<0>(format_args!("abc")).as_str(), "");
+		// Multi-byte chars are never split.
+		assert_eq!(format::<7>(format_args!("ab{}", "ééé")).as_str(), "abé...");
+		assert_eq!(format::<6>(format_args!("ab{}", "ééé")).as_str(), "ab...");
+	}
+}
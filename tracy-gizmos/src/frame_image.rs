@@ -0,0 +1,91 @@
+/// Attaches the screenshot to the frame, so it is shown in the frame
+/// timeline.
+///
+/// The image is tightly packed RGBA, 4 bytes per pixel, and it is
+/// copied, so the buffer could be reused right away. Tracy compresses
+/// it in the background, but doesn't scale it, so prefer downscaled
+/// images, e.g. 320x180, to keep the overhead and the capture size
+/// low. Both dimensions must be divisible by 4.
+///
+/// Screenshots are usually captured with a delay, as the GPU is a
+/// few frames behind. `offset` is the amount of frames the image
+/// lags behind the current one. `flip` tells, that the image rows go
+/// from the bottom up, e.g. for OpenGL framebuffers.
+///
+/// # Panics
+///
+/// Panics if the dimensions are not divisible by 4 or the `rgba`
+/// size doesn't match them.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn render() {}
+/// # fn read_downscaled_backbuffer(_: &mut [u8]) -> bool { true }
+/// let _tracy = start_capture();
+/// let mut screenshot = vec![0; 320 * 180 * 4];
+/// loop {
+///     render();
+///     // The readback finishes 2 frames later.
+///     if read_downscaled_backbuffer(&mut screenshot) {
+///         emit_frame_image(&screenshot, 320, 180, 2, false);
+///     }
+///     frame!();
+/// }
+/// ```
+pub fn emit_frame_image(rgba: &[u8], width: u16, height: u16, offset: u8, flip: bool) {
+	assert!(
+		width.is_multiple_of(4) && height.is_multiple_of(4),
+		"Frame image dimensions should be divisible by 4.",
+	);
+	assert_eq!(
+		rgba.len(),
+		width as usize * height as usize * 4,
+		"Frame image should be RGBA of the given dimensions.",
+	);
+
+	#[cfg(feature = "enabled")]
+	{
+		let Some(_emission) = crate::emission() else {
+			return;
+		};
+		// SAFETY: The image size is checked above, Tracy copies it.
+		unsafe {
+			sys::___tracy_emit_frame_image(rgba.as_ptr().cast(), width, height, offset, flip as i32);
+		}
+		crate::self_profile::count_event();
+	}
+	#[cfg(not(feature = "enabled"))]
+	{
+		// Silences unused variable warnings.
+		_ = (offset, flip);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn frame_images() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let image = vec![0x7f; 16 * 8 * 4];
+		emit_frame_image(&image, 16, 8, 0, false);
+		crate::frame!();
+		emit_frame_image(&image, 8, 16, 1, true);
+	}
+
+	#[test]
+	#[should_panic(expected = "divisible by 4")]
+	fn unaligned_frame_images() {
+		emit_frame_image(&[0; 6 * 4 * 4], 6, 4, 0, false);
+	}
+
+	#[test]
+	#[should_panic(expected = "RGBA")]
+	fn truncated_frame_images() {
+		emit_frame_image(&[0; 4 * 4 * 3], 4, 4, 0, false);
+	}
+}
@@ -0,0 +1,64 @@
+//! Frame time plot, which is updated by every main frame mark.
+//!
+//! When [`CaptureOptions::frame_time_plot`] is set, the time between
+//! consecutive main frame marks is emitted into the `Frame time, ms`
+//! plot, so there is no need to measure it by hand.
+//!
+//! [`CaptureOptions::frame_time_plot`]: crate::CaptureOptions::frame_time_plot
+
+use std::ffi::CStr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use crate::{Plot, PlotConfig, PlotEmit};
+
+const NAME: &CStr = c"Frame time, ms";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// The latest main frame mark.
+static LAST: Mutex<Option<Instant>> = Mutex::new(None);
+
+pub(crate) fn set_config(config: Option<PlotConfig>) {
+	*LAST.lock().unwrap_or_else(|e| e.into_inner()) = None;
+	if let Some(config) = config {
+		Plot::with_config(NAME, config);
+	}
+	ENABLED.store(config.is_some(), Ordering::Relaxed);
+}
+
+/// Records the main frame mark.
+#[inline(always)]
+pub(crate) fn mark() {
+	if ENABLED.load(Ordering::Relaxed) {
+		mark_slow();
+	}
+}
+
+#[inline(never)]
+fn mark_slow() {
+	let now  = Instant::now();
+	let last = LAST.lock().unwrap_or_else(|e| e.into_inner()).replace(now);
+	if let Some(last) = last {
+		Plot::new(NAME).emit((now - last).as_secs_f64() * 1000.0);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn frame_time_plot() {
+		let tracy = crate::start_capture_with(crate::CaptureOptions {
+			frame_time_plot: Some(PlotConfig::default()),
+			..Default::default()
+		});
+		assert!(ENABLED.load(Ordering::Relaxed));
+		crate::frame!();
+		assert!(LAST.lock().unwrap().is_some());
+		crate::frame!();
+		drop(tracy);
+		assert!(!ENABLED.load(Ordering::Relaxed));
+	}
+}
@@ -0,0 +1,228 @@
+#[cfg(feature = "enabled")]
+use std::ffi::CStr;
+
+use crate::{Frame, Zone};
+
+/// Secondary frame set, which name is known only at run-time.
+///
+/// It works in the same way as [`frame!`] with a name, but allows to
+/// create frame sets from configuration or plugin names. The name is
+/// interned once, so marking frames costs the same as with the macro.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn plugin_names() -> Vec<String> { vec![] }
+/// # fn run_plugin(_: &str) {}
+/// let plugins: Vec<_> = plugin_names()
+///     .into_iter()
+///     .map(|name| (FrameSet::new(&name), name))
+///     .collect();
+///
+/// loop {
+///     for (frames, name) in &plugins {
+///         // Marks the discontinuous frame for the scope.
+///         let _frame = frames.scope();
+///         run_plugin(name);
+///     }
+/// }
+/// ```
+///
+/// [`frame!`]: crate::frame!
+#[derive(Debug, Clone, Copy)]
+pub struct FrameSet {
+	#[cfg(feature = "enabled")]
+	name: &'static CStr,
+}
+
+impl FrameSet {
+	/// Creates a frame set with the given name.
+	///
+	/// The name is interned and never freed, as Tracy requires it to
+	/// live until the end of the program, refer to
+	/// [`set_interner_cap`]. Frame sets with the same name are the
+	/// same frame set.
+	///
+	/// # Panics
+	///
+	/// Panics if the `name` contains a null byte.
+	///
+	/// [`set_interner_cap`]: crate::set_interner_cap
+	pub fn new(name: &str) -> Self {
+		assert!(!name.contains('\0'), "Frame set name should not contain null bytes.");
+		Self {
+			#[cfg(feature = "enabled")]
+			name: crate::intern::intern(name),
+		}
+	}
+
+	/// Marks the end of the continuous frame, refer to [`frame!`].
+	///
+	/// [`frame!`]: crate::frame!
+	#[inline(always)]
+	pub fn mark(&self) {
+		#[cfg(feature = "enabled")]
+		// SAFETY: Interned names are static & null-terminated.
+		unsafe {
+			crate::details::mark_frame_end(self.name.as_ptr().cast());
+		}
+	}
+
+	/// Begins the discontinuous frame, which ends when the returned
+	/// [`Frame`] is dropped, refer to [`frame!`].
+	///
+	/// [`frame!`]: crate::frame!
+	#[inline(always)]
+	pub fn scope(&self) -> Frame {
+		#[cfg(feature = "enabled")]
+		// SAFETY: Interned names are static & null-terminated.
+		return unsafe { crate::details::discontinuous_frame(self.name.as_ptr().cast()) };
+
+		#[cfg(not(feature = "enabled"))]
+		Frame()
+	}
+}
+
+impl Frame {
+	/// Marks the end of the continuous frame of the given set, like
+	/// [`frame!`] with a name does.
+	///
+	/// It is a function-based alternative to the macro for generic
+	/// code & other macros, which can't pass a literal. The name is
+	/// looked up in the interner on every call, so prefer a
+	/// [`FrameSet`] for the frequently marked sets.
+	///
+	/// # Panics
+	///
+	/// Panics if the `name` contains a null byte.
+	///
+	/// [`frame!`]: crate::frame!
+	pub fn continuous(name: &str) {
+		FrameSet::new(name).mark();
+	}
+
+	/// Begins the discontinuous frame of the given set, which ends
+	/// when the returned [`Frame`] is dropped, like [`frame!`] with a
+	/// variable does.
+	///
+	/// Refer to [`Frame::continuous`] for the details.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// fn with_frame<R>(name: &str, f: impl FnOnce() -> R) -> R {
+	///     let _frame = Frame::discontinuous(name);
+	///     f()
+	/// }
+	///
+	/// with_frame("IO", || std::fs::read("save.dat"));
+	/// ```
+	///
+	/// [`frame!`]: crate::frame!
+	pub fn discontinuous(name: &str) -> Frame {
+		FrameSet::new(name).scope()
+	}
+}
+
+/// Runs the closure as a single main frame, marking the frame end
+/// right after it, and returns its result.
+///
+/// It standardizes the game loop instrumentation, so the
+/// [`frame!`] call can't be forgotten or skipped by an early
+/// `continue`. Refer to [`run_named_frame`] for secondary frame sets.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn update() -> bool { true }
+/// # fn render() {}
+/// let _tracy = start_capture();
+/// while run_frame(|| {
+///     let running = update();
+///     render();
+///     running
+/// }) {}
+/// ```
+///
+/// [`frame!`]: crate::frame!
+#[inline]
+pub fn run_frame<R>(f: impl FnOnce() -> R) -> R {
+	let result = f();
+	#[cfg(feature = "enabled")]
+	// SAFETY: Null pointer means main frame.
+	unsafe {
+		crate::details::mark_frame_end(std::ptr::null());
+	}
+	result
+}
+
+/// Runs the closure as a single continuous frame of the given set,
+/// marking the frame end right after it, and returns its result.
+///
+/// The closure is also wrapped in a top-level zone named after the
+/// frame set, so the frame contents are visible in the timeline. The
+/// name is looked up in the interner on every call, refer to
+/// [`Frame::continuous`].
+///
+/// # Panics
+///
+/// Panics if the `name` contains a null byte.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn step_physics() {}
+/// let sim = std::thread::spawn(|| loop {
+///     run_named_frame("sim", step_physics);
+/// });
+/// ```
+#[track_caller]
+pub fn run_named_frame<R>(name: &str, f: impl FnOnce() -> R) -> R {
+	let frames = FrameSet::new(name);
+	let result = {
+		#[cfg(feature = "enabled")]
+		let _zone = Zone::begin_from_cstr(frames.name);
+		#[cfg(not(feature = "enabled"))]
+		let _zone = Zone::new();
+		f()
+	};
+	frames.mark();
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn runtime_frame_sets() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let name   = format!("plugin {}", 1);
+		let frames = FrameSet::new(&name);
+		#[cfg(feature = "enabled")]
+		assert_eq!(frames.name.as_ptr(), FrameSet::new("plugin 1").name.as_ptr());
+		frames.mark();
+		frames.mark();
+		let io = FrameSet::new("plugin IO");
+		let _frame = io.scope();
+		Frame::continuous("plugin 1");
+		let _io = Frame::discontinuous("plugin IO 2");
+	}
+
+	#[test]
+	fn closure_frames() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		assert_eq!(run_frame(|| 1 + 1), 2);
+		let mut steps = 0;
+		for _ in 0..3 {
+			run_named_frame("sim", || steps += 1);
+		}
+		assert_eq!(steps, 3);
+	}
+}
@@ -0,0 +1,207 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "enabled")]
+use crate::{Plot, PlotEmit};
+
+/// Rolling statistics of the frame times, e.g. for in-app HUDs or
+/// automated performance tests.
+///
+/// It keeps the latest `window` frame times, which are measured
+/// between consecutive [`FrameStats::frame`] calls, so it is meant to
+/// be called right next to [`frame!`]. Statistics are available in
+/// every build, even without the `enabled` feature. Optionally, they
+/// are emitted into `Frame time, mean ms`, `Frame time, p95 ms` &
+/// `Frame time, p99 ms` plots, so the same numbers are seen in
+/// Tracy.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn update() {}
+/// # fn render() {}
+/// let mut stats = FrameStats::new(FrameStats::DEFAULT_WINDOW).with_plots();
+/// loop {
+///     update();
+///     render();
+///     frame!();
+///     stats.frame();
+///     if let Some(p99) = stats.p99() {
+///         println!("p99: {p99:.1?}");
+///     }
+/// }
+/// ```
+///
+/// [`frame!`]: crate::frame!
+pub struct FrameStats {
+	times:  VecDeque<Duration>,
+	window: usize,
+	last:   Option<Instant>,
+	#[cfg(feature = "enabled")]
+	plots:  bool,
+}
+
+impl FrameStats {
+	/// Default amount of the latest frames, which are accounted for,
+	/// e.g. 2 seconds at 60 FPS.
+	pub const DEFAULT_WINDOW: usize = 120;
+
+	/// Creates the statistics of the latest `window` frames.
+	///
+	/// # Panics
+	///
+	/// Panics if the `window` is zero.
+	pub fn new(window: usize) -> Self {
+		assert!(window > 0, "Frame stats window should be non-zero.");
+		Self {
+			times: VecDeque::with_capacity(window),
+			window,
+			last:  None,
+			#[cfg(feature = "enabled")]
+			plots: false,
+		}
+	}
+
+	/// Emits the mean, p95 & p99 frame times into plots on every
+	/// frame.
+	#[cfg_attr(not(feature = "enabled"), allow(unused_mut))]
+	pub fn with_plots(mut self) -> Self {
+		#[cfg(feature = "enabled")]
+		{
+			self.plots = true;
+		}
+		self
+	}
+
+	/// Records the time passed since the previous call as the frame
+	/// time.
+	pub fn frame(&mut self) {
+		let now  = Instant::now();
+		let last = self.last.replace(now);
+		if let Some(last) = last {
+			self.record(now - last);
+		}
+	}
+
+	/// Records the frame time, which is measured elsewhere.
+	pub fn record(&mut self, time: Duration) {
+		if self.times.len() == self.window {
+			self.times.pop_front();
+		}
+		self.times.push_back(time);
+
+		#[cfg(feature = "enabled")]
+		if self.plots {
+			const MEAN: Plot = Plot::new(c"Frame time, mean ms");
+			const P95:  Plot = Plot::new(c"Frame time, p95 ms");
+			const P99:  Plot = Plot::new(c"Frame time, p99 ms");
+
+			let sorted = self.sorted();
+			let ms     = |time: Duration| time.as_secs_f64() * 1000.0;
+			if let Some(mean) = self.mean() {
+				MEAN.emit(ms(mean));
+			}
+			P95.emit(ms(percentile(&sorted, 95.0)));
+			P99.emit(ms(percentile(&sorted, 99.0)));
+		}
+	}
+
+	/// Returns the amount of frames accounted for.
+	pub fn len(&self) -> usize {
+		self.times.len()
+	}
+
+	/// Returns `true` if no frames are recorded yet.
+	pub fn is_empty(&self) -> bool {
+		self.times.is_empty()
+	}
+
+	/// Forgets the recorded frames, e.g. after a loading screen.
+	pub fn reset(&mut self) {
+		self.times.clear();
+		self.last = None;
+	}
+
+	/// Returns the mean frame time.
+	pub fn mean(&self) -> Option<Duration> {
+		if self.times.is_empty() {
+			return None;
+		}
+		Some(self.times.iter().sum::<Duration>() / self.times.len() as u32)
+	}
+
+	/// Returns the `p`-th percentile of the frame times, e.g. `50.0`
+	/// is the median.
+	///
+	/// # Panics
+	///
+	/// Panics if `p` is not within `0.0..=100.0`.
+	pub fn percentile(&self, p: f64) -> Option<Duration> {
+		assert!((0.0..=100.0).contains(&p), "Percentile should be within 0..=100.");
+		if self.times.is_empty() {
+			return None;
+		}
+		Some(percentile(&self.sorted(), p))
+	}
+
+	/// Returns the 95th percentile of the frame times.
+	pub fn p95(&self) -> Option<Duration> {
+		self.percentile(95.0)
+	}
+
+	/// Returns the 99th percentile of the frame times.
+	pub fn p99(&self) -> Option<Duration> {
+		self.percentile(99.0)
+	}
+
+	fn sorted(&self) -> Vec<Duration> {
+		let mut sorted: Vec<_> = self.times.iter().copied().collect();
+		sorted.sort_unstable();
+		sorted
+	}
+}
+
+impl Default for FrameStats {
+	fn default() -> Self {
+		Self::new(Self::DEFAULT_WINDOW)
+	}
+}
+
+/// Returns the nearest-rank percentile of non-empty sorted times.
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+	let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+	sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn frame_stats() {
+		#[cfg(feature = "enabled")]
+		let _tracy    = crate::start_capture();
+		let mut stats = FrameStats::new(100).with_plots();
+		assert!(stats.is_empty());
+		assert_eq!(stats.mean(), None);
+		assert_eq!(stats.p99(), None);
+
+		for ms in (1..=200).rev() {
+			stats.record(Duration::from_millis(ms));
+		}
+		assert_eq!(stats.len(), 100);
+		assert_eq!(stats.mean(), Some(Duration::from_micros(50_500)));
+		assert_eq!(stats.percentile(0.0), Some(Duration::from_millis(1)));
+		assert_eq!(stats.percentile(50.0), Some(Duration::from_millis(50)));
+		assert_eq!(stats.p95(), Some(Duration::from_millis(95)));
+		assert_eq!(stats.p99(), Some(Duration::from_millis(99)));
+		assert_eq!(stats.percentile(100.0), Some(Duration::from_millis(100)));
+
+		stats.reset();
+		stats.frame();
+		assert!(stats.is_empty());
+		stats.frame();
+		assert_eq!(stats.len(), 1);
+	}
+}
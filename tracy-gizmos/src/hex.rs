@@ -0,0 +1,73 @@
+use std::fmt::Write;
+
+const ROW: usize = 16;
+
+/// Formats a bounded hex dump of `data`, at most `max_len` bytes are
+/// dumped.
+///
+/// ```text
+/// 20 bytes
+/// 0000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 01  |Hello, world!...|
+/// 0010  02 03 04 05                                       |....|
+/// ```
+pub(crate) fn format_hex(label: Option<&str>, data: &[u8], max_len: usize) -> String {
+	let shown = &data[..data.len().min(max_len)];
+	let rows  = shown.len().div_ceil(ROW);
+	let mut text = String::with_capacity(32 + rows * 80);
+
+	// Writing into a `String` never fails.
+	if let Some(label) = label {
+		_ = write!(text, "{label}: ");
+	}
+	_ = write!(text, "{} bytes", data.len());
+
+	for (i, row) in shown.chunks(ROW).enumerate() {
+		_ = write!(text, "\n{:04x} ", i * ROW);
+		for j in 0..ROW {
+			if j == ROW / 2 {
+				text.push(' ');
+			}
+			match row.get(j) {
+				Some(b) => _ = write!(text, " {b:02x}"),
+				None    => text.push_str("   "),
+			}
+		}
+		text.push_str("  |");
+		text.extend(row.iter().map(|&b| {
+			if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }
+		}));
+		text.push('|');
+	}
+
+	if shown.len() < data.len() {
+		_ = write!(text, "\n... {} more bytes", data.len() - shown.len());
+	}
+	text
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dumps_rows() {
+		let data = b"Hello, world!\n\x00\x01\x02\x03\x04\x05";
+		assert_eq!(
+			format_hex(Some("rx"), data, 64),
+			"rx: 20 bytes\n\
+			 0000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 01  |Hello, world!...|\n\
+			 0010  02 03 04 05                                       |....|",
+		);
+	}
+
+	#[test]
+	fn is_bounded() {
+		assert_eq!(
+			format_hex(None, &[0xff; 40], 2),
+			"40 bytes\n\
+			 0000  ff ff                                             |..|\n\
+			 ... 38 more bytes",
+		);
+		assert_eq!(format_hex(None, &[], 16), "0 bytes");
+	}
+}
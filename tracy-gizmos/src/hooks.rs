@@ -0,0 +1,167 @@
+//! User hooks called when zones are entered & exited.
+//!
+//! When `zone-hooks` feature is enabled, locations of active zones
+//! are recorded in a thread-local stack, so the hooks registered via
+//! [`on_zone_enter`] & [`on_zone_exit`] receive the zone location on
+//! both ends. Otherwise, the tracking is compiled out completely.
+
+#[cfg(feature = "enabled")]
+use std::cell::RefCell;
+#[cfg(feature = "enabled")]
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::ZoneLocation;
+
+/// Zone hook, which is a plain function, so it is cheap to check and
+/// call.
+pub type ZoneHook = fn(&ZoneLocation);
+
+/// Currently registered `ZoneHook`s, null if there is none.
+#[cfg(feature = "enabled")]
+static ENTER: AtomicPtr<()> = AtomicPtr::new(std::ptr::null_mut());
+#[cfg(feature = "enabled")]
+static EXIT:  AtomicPtr<()> = AtomicPtr::new(std::ptr::null_mut());
+
+#[cfg(feature = "enabled")]
+thread_local! {
+	/// Locations of active zones, `None` for zones, which location is
+	/// known only at run-time.
+	static ENTERED: RefCell<Vec<Option<&'static ZoneLocation>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Registers the hook, which is called on the current thread right
+/// after a zone is entered, or removes the current one with `None`.
+///
+/// Hooks allow to mirror zones into custom telemetry, e.g. HUD timers
+/// or an in-game profiler overlay, without instrumenting the code
+/// twice. They are called synchronously, so they must be fast and
+/// must not emit any instrumentation themselves. Only zones with a
+/// static location are reported, i.e. not [`zone_dynamic!`] ones, and
+/// only while they are emitted to Tracy.
+///
+/// Lazy zones are reported when they are actually emitted, i.e. when
+/// something happens inside of them or when they end.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::cell::RefCell;
+/// # use std::time::Instant;
+/// thread_local! {
+///     static TIMERS: RefCell<Vec<Instant>> = const { RefCell::new(Vec::new()) };
+/// }
+///
+/// fn enter(_: &ZoneLocation) {
+///     TIMERS.with_borrow_mut(|timers| timers.push(Instant::now()));
+/// }
+///
+/// fn exit(location: &ZoneLocation) {
+///     let started = TIMERS.with_borrow_mut(|timers| timers.pop()).unwrap();
+///     eprintln!("{}: {:?}", location.name(), started.elapsed());
+/// }
+///
+/// let _tracy = start_capture();
+/// on_zone_enter(Some(enter));
+/// on_zone_exit(Some(exit));
+/// zone!("timed in the HUD");
+/// ```
+///
+/// [`zone_dynamic!`]: crate::zone_dynamic!
+pub fn on_zone_enter(hook: Option<ZoneHook>) {
+	#[cfg(feature = "enabled")]
+	ENTER.store(hook.map_or(std::ptr::null_mut(), |hook| hook as *mut ()), Ordering::Release);
+}
+
+/// Registers the hook, which is called on the current thread right
+/// before a zone is exited, or removes the current one with `None`.
+///
+/// Refer to [`on_zone_enter`] for the details.
+pub fn on_zone_exit(hook: Option<ZoneHook>) {
+	#[cfg(feature = "enabled")]
+	EXIT.store(hook.map_or(std::ptr::null_mut(), |hook| hook as *mut ()), Ordering::Release);
+}
+
+#[cfg(feature = "enabled")]
+#[inline(always)]
+fn call(hook: &AtomicPtr<()>, location: &ZoneLocation) {
+	let hook = hook.load(Ordering::Acquire);
+	if hook.is_null() {
+		return;
+	}
+	// SAFETY: Only `ZoneHook`s are stored, see `on_zone_enter`.
+	let hook = unsafe { std::mem::transmute::<*mut (), ZoneHook>(hook) };
+	hook(location);
+}
+
+/// Records that the zone is entered, `location` is `None` for zones,
+/// which location is known only at run-time.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn enter(location: Option<&'static ZoneLocation>, ctx: sys::TracyCZoneCtx) {
+	if ctx.active == 0 {
+		return;
+	}
+	ENTERED.with_borrow_mut(|entered| entered.push(location));
+	if let Some(location) = location {
+		call(&ENTER, location);
+	}
+}
+
+/// Records that the zone is exited.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn leave(ctx: sys::TracyCZoneCtx) {
+	if ctx.active == 0 {
+		return;
+	}
+	if let Some(Some(location)) = ENTERED.with_borrow_mut(Vec::pop) {
+		call(&EXIT, location);
+	}
+}
+
+#[cfg(all(test, feature = "enabled"))]
+mod tests {
+	use std::cell::RefCell;
+
+	use super::*;
+	use crate::*;
+
+	thread_local! {
+		static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+	}
+
+	fn enter(location: &ZoneLocation) {
+		EVENTS.with_borrow_mut(|events| events.push(format!("> {}", location.name())));
+	}
+
+	fn exit(location: &ZoneLocation) {
+		EVENTS.with_borrow_mut(|events| events.push(format!("< {}", location.name())));
+	}
+
+	#[test]
+	fn hooked_zones() {
+		let _tracy = start_capture();
+		on_zone_enter(Some(enter));
+		on_zone_exit(Some(exit));
+		{
+			zone!("outer");
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			zone_dynamic!(format!("{}", "dynamic"));
+			zone!("skipped", enabled: false);
+			zone!(lazy "lazy");
+			zone!("inner");
+		}
+		on_zone_enter(None);
+		on_zone_exit(None);
+		zone!("unhooked");
+		EVENTS.with_borrow(|events| assert_eq!(*events, [
+			"> outer",
+			"> lazy",
+			"> inner",
+			"< inner",
+			"< lazy",
+			"< outer",
+		]));
+	}
+}
@@ -0,0 +1,318 @@
+//! Hooks for third-party integrations into the capture lifecycle.
+//!
+//! Crates, which want to report their own data (engine plugins
+//! sampling their subsystems, database drivers plotting pool usage,
+//! etc.) can implement [`TracyIntegration`] and [`register`] it. This
+//! crate doesn't need to know anything about them, while they don't
+//! need to track when the capture starts or ends.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use tracy_gizmos::integration::{self, TracyIntegration};
+//!
+//! struct Pool;
+//!
+//! impl TracyIntegration for Pool {
+//!     fn init(&self) {
+//!         tracy_gizmos::app_info("pool: 16 connections");
+//!     }
+//!
+//!     fn tick_interval(&self) -> Option<Duration> {
+//!         Some(Duration::from_millis(100))
+//!     }
+//!
+//!     fn tick(&self) {
+//!         tracy_gizmos::plot!("pool: busy connections", 3);
+//!     }
+//! }
+//!
+//! integration::register(Pool);
+//! let _tracy = tracy_gizmos::start_capture();
+//! ```
+
+#[cfg(feature = "enabled")]
+use std::sync::{Arc, Mutex, MutexGuard};
+#[cfg(feature = "enabled")]
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "enabled")]
+use crate::background::{Background, Stop};
+
+/// A custom integration, which follows the capture lifecycle.
+///
+/// All methods are called only while the capture is running, so it
+/// is fine to use any of the instrumentation there. Every method has
+/// an empty default implementation.
+pub trait TracyIntegration: Send + Sync + 'static {
+	/// Called when the capture starts, or right away when registered
+	/// during an already running capture.
+	fn init(&self) {}
+
+	/// Called before the capture shuts down.
+	fn shutdown(&self) {}
+
+	/// How often [`TracyIntegration::tick`] should be called. `None`
+	/// (the default) disables the ticking.
+	fn tick_interval(&self) -> Option<Duration> {
+		None
+	}
+
+	/// Called periodically from a background thread, see
+	/// [`TracyIntegration::tick_interval`].
+	fn tick(&self) {}
+
+	/// Called right after the main frame is marked as finished via
+	/// [`frame!()`](crate::frame), on the same thread. Allows to
+	/// flush batched data exactly at the frame boundaries.
+	///
+	/// Keep it cheap, as it directly contributes to the frame time.
+	#[allow(unused_variables)]
+	fn frame(&self, mark: &FrameMark) {}
+}
+
+/// The main frame boundary, refer to [`TracyIntegration::frame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameMark {
+	/// Number of main frames finished since the capture start,
+	/// including this one.
+	pub index: u64,
+	/// When the frame was marked.
+	pub time:  Instant,
+}
+
+/// Registers the integration.
+///
+/// It is initialized when the capture starts (or right away, if it
+/// is running already) and shut down when the capture ends. The
+/// integration stays registered for the following captures.
+///
+/// Does nothing, if the instrumentation is disabled.
+pub fn register(integration: impl TracyIntegration) {
+	#[cfg(feature = "enabled")]
+	{
+		let integration: Arc<dyn TracyIntegration> = Arc::new(integration);
+		let running = {
+			let mut registry = registry();
+			registry.integrations.push(Entry::new(Arc::clone(&integration)));
+			if registry.running && registry.ticker.is_none() {
+				registry.ticker = Some(spawn_ticker());
+			}
+			HOOKED.store(registry.running, Ordering::Relaxed);
+			registry.running
+		};
+		if running {
+			integration.init();
+		}
+	}
+}
+
+// Intervals of the ticker thread wake ups are capped, so it notices
+// integrations registered in the meantime.
+#[cfg(feature = "enabled")]
+const MAX_TICK_WAIT: Duration = Duration::from_millis(100);
+
+// Main frames finished in the current capture.
+#[cfg(feature = "enabled")]
+static FRAMES: AtomicU64 = AtomicU64::new(0);
+
+// Whether there are integrations to notify, so frame marks don't
+// need to lock the registry otherwise.
+#[cfg(feature = "enabled")]
+static HOOKED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "enabled")]
+struct Entry {
+	integration: Arc<dyn TracyIntegration>,
+	next_tick:   Option<Instant>,
+}
+
+#[cfg(feature = "enabled")]
+impl Entry {
+	fn new(integration: Arc<dyn TracyIntegration>) -> Self {
+		let next_tick = integration.tick_interval().map(|interval| Instant::now() + interval);
+		Self { integration, next_tick }
+	}
+}
+
+#[cfg(feature = "enabled")]
+struct Registry {
+	integrations: Vec<Entry>,
+	running:      bool,
+	ticker:       Option<Background>,
+}
+
+#[cfg(feature = "enabled")]
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+	integrations: Vec::new(),
+	running:      false,
+	ticker:       None,
+});
+
+#[cfg(feature = "enabled")]
+fn registry() -> MutexGuard<'static, Registry> {
+	// Integrations are called outside of the lock, so it can't be
+	// poisoned by them.
+	REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Initializes all registered integrations and starts ticking them.
+#[cfg(feature = "enabled")]
+pub(crate) fn start() {
+	let integrations = {
+		let mut registry = registry();
+		registry.running = true;
+		FRAMES.store(0, Ordering::Relaxed);
+		if registry.integrations.is_empty() {
+			return;
+		}
+		HOOKED.store(true, Ordering::Relaxed);
+		registry.ticker = Some(spawn_ticker());
+		for entry in &mut registry.integrations {
+			*entry = Entry::new(Arc::clone(&entry.integration));
+		}
+		snapshot(&registry)
+	};
+	for integration in integrations {
+		integration.init();
+	}
+}
+
+/// Stops ticking and shuts all registered integrations down in the
+/// reverse order.
+#[cfg(feature = "enabled")]
+pub(crate) fn stop() {
+	let (ticker, integrations) = {
+		let mut registry = registry();
+		registry.running = false;
+		HOOKED.store(false, Ordering::Relaxed);
+		(registry.ticker.take(), snapshot(&registry))
+	};
+	if let Some(ticker) = ticker {
+		ticker.stop();
+	}
+	for integration in integrations.iter().rev() {
+		integration.shutdown();
+	}
+}
+
+/// Counts the main frame and notifies the integrations about it.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn mark_frame() {
+	let index = FRAMES.fetch_add(1, Ordering::Relaxed) + 1;
+	if HOOKED.load(Ordering::Relaxed) {
+		notify_frame(FrameMark { index, time: Instant::now() });
+	}
+}
+
+#[cold]
+#[cfg(feature = "enabled")]
+fn notify_frame(mark: FrameMark) {
+	let integrations = snapshot(&registry());
+	for integration in integrations {
+		integration.frame(&mark);
+	}
+}
+
+/// Forgets the ticker without joining it and doesn't shut the
+/// integrations down, as it is used in a forked child process, where
+/// the ticker thread doesn't exist.
+#[cfg(feature = "enabled")]
+pub(crate) fn abandon() {
+	let mut registry = registry();
+	registry.running = false;
+	HOOKED.store(false, Ordering::Relaxed);
+	std::mem::forget(registry.ticker.take());
+}
+
+#[cfg(feature = "enabled")]
+fn snapshot(registry: &Registry) -> Vec<Arc<dyn TracyIntegration>> {
+	registry.integrations.iter().map(|entry| Arc::clone(&entry.integration)).collect()
+}
+
+#[cfg(feature = "enabled")]
+fn spawn_ticker() -> Background {
+	Background::spawn("tracy-gizmos integrations", tick)
+}
+
+#[cfg(feature = "enabled")]
+fn tick(stop: &Stop) {
+	let mut due = Vec::new();
+	loop {
+		let now      = Instant::now();
+		let mut wait = MAX_TICK_WAIT;
+		for entry in &mut registry().integrations {
+			let (Some(next), Some(interval)) = (entry.next_tick, entry.integration.tick_interval()) else {
+				continue;
+			};
+			let next = if next <= now {
+				due.push(Arc::clone(&entry.integration));
+				// Skips the missed ticks, if we are late.
+				if next + interval <= now { now + interval } else { next + interval }
+			} else {
+				next
+			};
+			entry.next_tick = Some(next);
+			wait = wait.min(next - now);
+		}
+
+		for integration in due.drain(..) {
+			integration.tick();
+		}
+
+		if stop.wait(wait) {
+			return;
+		}
+	}
+}
+
+#[cfg(all(test, feature = "enabled"))]
+mod tests {
+	use std::sync::atomic::AtomicUsize;
+
+	use super::*;
+
+	static INITS:     AtomicUsize = AtomicUsize::new(0);
+	static TICKS:     AtomicUsize = AtomicUsize::new(0);
+	static SHUTDOWNS: AtomicUsize = AtomicUsize::new(0);
+	static LAST_FRAME: AtomicU64   = AtomicU64::new(0);
+
+	struct Counter;
+
+	impl TracyIntegration for Counter {
+		fn init(&self)     { INITS.fetch_add(1, Ordering::Relaxed); }
+		fn shutdown(&self) { SHUTDOWNS.fetch_add(1, Ordering::Relaxed); }
+		fn tick(&self)     { TICKS.fetch_add(1, Ordering::Relaxed); }
+
+		fn frame(&self, mark: &FrameMark) {
+			LAST_FRAME.store(mark.index, Ordering::Relaxed);
+		}
+
+		fn tick_interval(&self) -> Option<Duration> {
+			Some(Duration::from_millis(1))
+		}
+	}
+
+	#[test]
+	fn follows_capture_lifecycle() {
+		register(Counter);
+		assert_eq!(INITS.load(Ordering::Relaxed), 0);
+
+		let tracy = crate::start_capture();
+		assert_eq!(INITS.load(Ordering::Relaxed), 1);
+		std::thread::sleep(Duration::from_millis(50));
+		assert!(TICKS.load(Ordering::Relaxed) > 0);
+		crate::frame!();
+		crate::frame!();
+		assert_eq!(LAST_FRAME.load(Ordering::Relaxed), 2);
+		drop(tracy);
+		assert_eq!(SHUTDOWNS.load(Ordering::Relaxed), 1);
+
+		let ticks = TICKS.load(Ordering::Relaxed);
+		std::thread::sleep(Duration::from_millis(10));
+		assert_eq!(TICKS.load(Ordering::Relaxed), ticks);
+	}
+}
@@ -0,0 +1,176 @@
+//! Interner of the names, which are known only at run-time.
+//!
+//! Tracy requires zone, plot & memory pool names to live until the
+//! end of the program, so names built at run-time are leaked. They
+//! are interned, so every distinct name is leaked only once, and the
+//! interner is capped, so feeding it unbounded names, e.g. ids, can't
+//! exhaust the memory. Once the cap is reached, new names are
+//! reported as `overflow` or copied, which is announced by a single
+//! warning message.
+
+// Most of the run-time names are formatted, so only a few users are
+// left without dynamic strings.
+#![cfg_attr(feature = "no-dynamic-strings", allow(dead_code))]
+
+#[cfg(feature = "enabled")]
+use std::collections::HashMap;
+#[cfg(feature = "enabled")]
+use std::ffi::{CStr, CString};
+#[cfg(feature = "enabled")]
+use std::sync::Mutex;
+#[cfg(feature = "enabled")]
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Default amount of names, which could be interned.
+pub const DEFAULT_INTERNER_CAP: usize = 4096;
+
+/// Name reported instead of the names, which didn't fit the interner.
+#[cfg(feature = "enabled")]
+const OVERFLOW: &CStr = c"overflow";
+
+#[cfg(feature = "enabled")]
+static CAP: AtomicUsize = AtomicUsize::new(DEFAULT_INTERNER_CAP);
+
+#[cfg(feature = "enabled")]
+static INTERNER: Mutex<Option<Interner>> = Mutex::new(None);
+
+/// The overflow was reported since the cap was set.
+#[cfg(feature = "enabled")]
+static WARNED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "enabled")]
+#[derive(Default)]
+struct Interner {
+	names: HashMap<&'static str, &'static CStr>,
+	stats: InternerStats,
+}
+
+/// Statistics of the name interner, refer to [`interner_stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InternerStats {
+	/// Amount of interned names.
+	pub names:     usize,
+	/// Size of interned names, including the null terminators.
+	pub bytes:     usize,
+	/// Amount of names, which were reported as `overflow`, as the
+	/// interner was full.
+	pub overflows: u64,
+}
+
+/// Sets the maximum amount of names, which could be interned,
+/// [`DEFAULT_INTERNER_CAP`] by default.
+///
+/// Names built at run-time, e.g. by [`dyn_zone!`] or
+/// [`RepeatingTimer`], are leaked once per distinct name, as Tracy
+/// requires. Once the cap is reached, new names are reported as
+/// `overflow` instead, so unbounded names can't exhaust the memory.
+/// The first overflow after the cap is set is also reported via a
+/// warning message.
+///
+/// Already interned names are kept, even if the cap is lowered below
+/// their amount.
+///
+/// [`dyn_zone!`]: crate::dyn_zone!
+/// [`RepeatingTimer`]: crate::RepeatingTimer
+pub fn set_interner_cap(cap: usize) {
+	#[cfg(feature = "enabled")]
+	{
+		CAP.store(cap, Ordering::Relaxed);
+		WARNED.store(false, Ordering::Relaxed);
+	}
+}
+
+/// Returns the current statistics of the name interner, refer to
+/// [`set_interner_cap`].
+///
+/// They are always zero, unless `enabled` feature is on.
+pub fn interner_stats() -> InternerStats {
+	#[cfg(feature = "enabled")]
+	{
+		let interner = INTERNER.lock().unwrap_or_else(|e| e.into_inner());
+		interner.as_ref().map(|interner| interner.stats).unwrap_or_default()
+	}
+
+	#[cfg(not(feature = "enabled"))]
+	InternerStats::default()
+}
+
+/// Returns the interned name, or `None` if the interner is full.
+///
+/// Names with inner nulls are cut, they can't be shown anyway.
+#[cfg(feature = "enabled")]
+pub(crate) fn try_intern(name: &str) -> Option<&'static CStr> {
+	let name         = name.split('\0').next().unwrap_or_default();
+	let mut locked = INTERNER.lock().unwrap_or_else(|e| e.into_inner());
+	let interner   = locked.get_or_insert_with(Interner::default);
+	if let Some(interned) = interner.names.get(name) {
+		return Some(interned);
+	}
+	if interner.names.len() >= CAP.load(Ordering::Relaxed) {
+		interner.stats.overflows += 1;
+		drop(locked);
+		if !WARNED.swap(true, Ordering::Relaxed) {
+			warn_overflow();
+		}
+		return None;
+	}
+
+	let interned: &'static CStr = Box::leak(CString::new(name).unwrap_or_default().into_boxed_c_str());
+	// The name is valid UTF-8, as it is a copy of `&str`.
+	let key = interned.to_str().unwrap_or_default();
+	interner.names.insert(key, interned);
+	interner.stats.names += 1;
+	interner.stats.bytes += key.len() + 1;
+	Some(interned)
+}
+
+/// Returns the interned name, or `overflow` if the interner is full.
+#[cfg(feature = "enabled")]
+pub(crate) fn intern(name: &str) -> &'static CStr {
+	try_intern(name).unwrap_or(OVERFLOW)
+}
+
+#[cfg(feature = "enabled")]
+#[cold]
+fn warn_overflow() {
+	// SAFETY: The text is static & null-terminated.
+	unsafe {
+		crate::details::message_color(
+			c"Name interner is full, further run-time names are reported as `overflow` or copied. \
+			  Refer to `tracy_gizmos::set_interner_cap`.".as_ptr().cast(),
+			crate::Color::YELLOW,
+		);
+	}
+}
+
+#[cfg(all(test, feature = "enabled"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn names_overflow() {
+		let _tracy = crate::start_capture();
+		let before = interner_stats();
+		let name   = intern("interned name");
+		assert_eq!(name, c"interned name");
+		assert!(std::ptr::eq(name, intern("interned name")));
+		assert_eq!(intern("cut\0name"), c"cut");
+
+		set_interner_cap(before.names);
+		let events = crate::record::record(|| {
+			assert_eq!(intern("interned name"), c"interned name");
+			assert_eq!(try_intern("yet another name"), None);
+			assert_eq!(intern("yet another name"), OVERFLOW);
+		});
+		assert!(WARNED.load(Ordering::Relaxed));
+		// Unless another thread has overflowed first, the first
+		// overflow warns, even if it is not `intern`.
+		let warnings = events.iter().filter(|event| matches!(event, crate::record::Event::Message(_)));
+		assert!(warnings.count() <= 1, "{events:?}");
+		set_interner_cap(DEFAULT_INTERNER_CAP);
+
+		let after = interner_stats();
+		assert_eq!(after.overflows - before.overflows, 2);
+		assert!(after.names <= before.names + 2);
+	}
+}
@@ -0,0 +1,63 @@
+//! Naming of dynamically generated code.
+//!
+//! Tracy client has no API to name code, which is not described by
+//! the debug information of the loaded images, nor to retrieve it.
+//! Hence, symbols are only announced via messages.
+
+/// Registers the symbol of dynamically generated code, e.g. a
+/// function compiled by wasmtime or LuaJIT.
+///
+/// Tracy can't resolve frames of sampled callstacks going into such
+/// code, so the symbol is announced in the capture via a `JIT symbol
+/// <name>: <start>..<end>, <len> bytes` message, which allows to
+/// attribute them by hand. The code itself is neither read nor
+/// transferred.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn compile(source: &str) -> Vec<u8> { vec![0xc3] }
+/// let _tracy = start_capture();
+/// let code = compile("function answer() return 42 end");
+/// register_jit_symbol(code.as_ptr(), code.len(), "lua: answer");
+/// // Run the code.
+/// unregister_jit_symbol(code.as_ptr());
+/// ```
+pub fn register_jit_symbol(address: *const u8, len: usize, name: &str) {
+	#[cfg(feature = "enabled")]
+	{
+		let address = address as usize;
+		crate::details::message_size(&format!(
+			"JIT symbol {name}: {address:#x}..{:#x}, {len} bytes",
+			address.wrapping_add(len),
+		));
+	}
+}
+
+/// Unregisters the symbol of dynamically generated code, which was
+/// registered via [`register_jit_symbol`], e.g. before its code is
+/// freed.
+///
+/// The symbol release is announced in the capture via a message.
+pub fn unregister_jit_symbol(address: *const u8) {
+	#[cfg(feature = "enabled")]
+	{
+		let address = address as usize;
+		crate::details::message_size(&format!("JIT symbol at {address:#x} released"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn registered_symbols() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let code = [0xc3u8; 16];
+		register_jit_symbol(code.as_ptr(), code.len(), "wasm: add");
+		unregister_jit_symbol(code.as_ptr());
+	}
+}
@@ -0,0 +1,137 @@
+#[cfg(any(test, all(feature = "enabled", not(feature = "no-dynamic-strings"))))]
+use std::fmt::{self, Write};
+
+/// Parses a zone text entry attached via [`Zone::kv`].
+///
+/// Returns the unescaped key and value, or `None` if the text doesn't
+/// follow the key/value convention.
+///
+/// The convention is `key=value`, where both parts are escaped:
+/// - `\` becomes `\\`
+/// - line feed becomes `\n`
+/// - carriage return becomes `\r`
+/// - `=` becomes `\=` (only needed in the key, but accepted anywhere)
+///
+/// Hence, every entry always fits a single line, and the first
+/// unescaped `=` always separates the key from the value.
+///
+/// # Examples
+///
+/// ```
+/// # use tracy_gizmos::*;
+/// assert_eq!(
+///     parse_kv(r"path=C:\\assets\\a=b.png"),
+///     Some(("path".to_string(), r"C:\assets\a=b.png".to_string())),
+/// );
+/// assert_eq!(parse_kv("just text"), None);
+/// ```
+///
+/// [`Zone::kv`]: crate::Zone::kv
+pub fn parse_kv(text: &str) -> Option<(String, String)> {
+	let mut key   = String::new();
+	let mut value = String::new();
+	let mut out   = &mut key;
+	let mut in_key = true;
+	let mut chars  = text.chars();
+
+	while let Some(c) = chars.next() {
+		match c {
+			'\\' => out.push(match chars.next()? {
+				'\\' => '\\',
+				'n'  => '\n',
+				'r'  => '\r',
+				'='  => '=',
+				_    => return None,
+			}),
+			'=' if in_key => {
+				in_key = false;
+				out    = &mut value;
+			},
+			c => out.push(c),
+		}
+	}
+
+	if in_key {
+		None
+	} else {
+		Some((key, value))
+	}
+}
+
+/// Displays the `key=value` entry, escaping both parts.
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+pub(crate) struct Field<'a>(pub(crate) &'a str, pub(crate) &'a dyn fmt::Display);
+
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+impl fmt::Display for Field<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		Escaper(f).write_str(self.0)?;
+		f.write_char('=')?;
+		write!(Escaper(f), "{}", self.1)
+	}
+}
+
+/// Escapes everything written through it according to the key/value
+/// convention, refer to [`parse_kv`].
+#[cfg(any(test, all(feature = "enabled", not(feature = "no-dynamic-strings"))))]
+pub(crate) struct Escaper<'a, W: Write>(pub(crate) &'a mut W);
+
+#[cfg(any(test, all(feature = "enabled", not(feature = "no-dynamic-strings"))))]
+impl<W: Write> Write for Escaper<'_, W> {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		let mut rest = s;
+		while let Some(i) = rest.find(['\\', '\n', '\r', '=']) {
+			self.0.write_str(&rest[..i])?;
+			self.0.write_str(match rest.as_bytes()[i] {
+				b'\\' => r"\\",
+				b'\n' => r"\n",
+				b'\r' => r"\r",
+				_     => r"\=",
+			})?;
+			rest = &rest[i + 1..];
+		}
+		self.0.write_str(rest)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn escape(s: &str) -> String {
+		let mut out = String::new();
+		Escaper(&mut out).write_str(s).unwrap();
+		out
+	}
+
+	#[test]
+	fn escaping_roundtrips() {
+		for (key, value) in [
+			("path",     "C:\\assets\\a.png"),
+			("a=b",      "c=d"),
+			("lines",    "one\ntwo\r\n"),
+			("",         ""),
+			("unicode",  "ключ=значение"),
+		] {
+			let text = format!("{}={}", escape(key), escape(value));
+			assert!(!text.contains('\n'));
+			assert_eq!(parse_kv(&text), Some((key.to_string(), value.to_string())));
+		}
+	}
+
+	#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+	#[test]
+	fn fields_are_escaped() {
+		let text = Field("a=b", &"c\nd").to_string();
+		assert_eq!(text, r"a\=b=c\nd");
+		assert_eq!(parse_kv(&text), Some(("a=b".to_string(), "c\nd".to_string())));
+	}
+
+	#[test]
+	fn malformed_is_rejected() {
+		assert_eq!(parse_kv("no separator"), None);
+		assert_eq!(parse_kv("escaped\\=separator"), None);
+		assert_eq!(parse_kv("bad\\escape=1"), None);
+		assert_eq!(parse_kv("dangling=\\"), None);
+	}
+}
@@ -0,0 +1,122 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "enabled")]
+use crate::{Plot, PlotEmit};
+
+/// Maximum amount of pending sends per tag, so a tag, which is never
+/// received, can't exhaust the memory.
+const MAX_PENDING: usize = 1024;
+
+static PROBES: Mutex<Option<HashMap<&'static str, Probe>>> = Mutex::new(None);
+
+struct Probe {
+	pending: VecDeque<Instant>,
+	#[cfg(feature = "enabled")]
+	plot:    Plot,
+}
+
+/// Cross-thread latency measurement.
+///
+/// Zones are per-thread, so they can't show how long it takes for
+/// an event to travel from one thread to another, e.g. input to
+/// render or network to simulation. [`LatencyProbe::send`] timestamps
+/// the event on one thread and [`LatencyProbe::receive`] measures the
+/// time it took on another one. The latency is plotted as
+/// `<tag>: latency, ms`.
+///
+/// Sends & receives of the same tag are matched in order, as events
+/// usually travel through a channel, so every send should have a
+/// matching receive. Only the latest 1024 sends are kept per tag.
+///
+/// The latency is measured the same way, even if the instrumentation
+/// is disabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::sync::mpsc;
+/// # fn render(_: u32) {}
+/// let (input, events) = mpsc::channel();
+/// std::thread::spawn(move || {
+///     LatencyProbe::send("input→render");
+///     input.send(42).unwrap();
+/// });
+/// for event in events {
+///     LatencyProbe::receive("input→render");
+///     render(event);
+/// }
+/// ```
+pub enum LatencyProbe {}
+
+impl LatencyProbe {
+	/// Timestamps the event of the given tag being sent.
+	///
+	/// The plot name is interned and never freed, as Tracy requires it
+	/// to live until the end of the program, refer to
+	/// [`set_interner_cap`].
+	///
+	/// [`set_interner_cap`]: crate::set_interner_cap
+	pub fn send(tag: &'static str) {
+		let now        = Instant::now();
+		let mut probes = PROBES.lock().unwrap_or_else(|e| e.into_inner());
+		let probe      = probes.get_or_insert_with(HashMap::new).entry(tag).or_insert_with(|| Probe {
+			pending: VecDeque::new(),
+			#[cfg(feature = "enabled")]
+			plot:    Plot::new(crate::intern::intern(&format!("{tag}: latency, ms"))),
+		});
+		if probe.pending.len() == MAX_PENDING {
+			probe.pending.pop_front();
+		}
+		probe.pending.push_back(now);
+	}
+
+	/// Measures & plots the latency of the oldest event of the given
+	/// tag, which is not received yet.
+	///
+	/// Returns `None` if there is no such event.
+	pub fn receive(tag: &'static str) -> Option<Duration> {
+		let now        = Instant::now();
+		let mut probes = PROBES.lock().unwrap_or_else(|e| e.into_inner());
+		let probe      = probes.as_mut()?.get_mut(tag)?;
+		let latency    = now - probe.pending.pop_front()?;
+
+		#[cfg(feature = "enabled")]
+		probe.plot.emit(latency.as_secs_f64() * 1000.0);
+
+		Some(latency)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn cross_thread_latency() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		assert_eq!(LatencyProbe::receive("test latency"), None);
+
+		let (tx, rx) = std::sync::mpsc::channel();
+		std::thread::spawn(move || {
+			for i in 0..2 {
+				LatencyProbe::send("test latency");
+				tx.send(i).unwrap();
+			}
+		});
+		for _ in rx {
+			std::thread::sleep(Duration::from_millis(1));
+			assert!(LatencyProbe::receive("test latency").unwrap() >= Duration::from_millis(1));
+		}
+		assert_eq!(LatencyProbe::receive("test latency"), None);
+
+		for _ in 0..MAX_PENDING + 1 {
+			LatencyProbe::send("test overflow");
+		}
+		let pending = PROBES.lock().unwrap().as_ref().unwrap()["test overflow"].pending.len();
+		assert_eq!(pending, MAX_PENDING);
+	}
+}
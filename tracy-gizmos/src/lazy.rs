@@ -0,0 +1,179 @@
+//! Deferred zones, which are emitted only if they turn out to be
+//! interesting.
+//!
+//! A lazy zone only records its start time and location in a
+//! thread-local stack. Before anything else is emitted on the same
+//! thread (a child zone or a message), all pending zones are flushed
+//! to Tracy with their original start time, so the nesting stays
+//! correct. When a lazy zone ends without being flushed, it is either
+//! emitted retroactively (if it lasted long enough) or dropped
+//! silently.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::{clock, self_profile, zone_path};
+
+/// Amount of not yet flushed lazy zones across all threads. Allows to
+/// skip the thread-local lookup on every emission, when lazy zones
+/// are not used.
+static UNFLUSHED: AtomicUsize = AtomicUsize::new(0);
+
+struct Pending {
+	location: *const sys::___tracy_source_location_data,
+	time:     i64,
+	emitted:  bool,
+}
+
+thread_local! {
+	static PENDING: RefCell<Vec<Pending>> = const { RefCell::new(Vec::new()) };
+}
+
+// Tracy doesn't verify zones (`TRACY_NO_VERIFY`), so only the
+// activity flag matters.
+const ACTIVE: sys::TracyCZoneCtx = sys::TracyCZoneCtx { id: 0, active: 1 };
+
+/// Records the lazy zone start.
+///
+/// # Safety
+///
+/// `location` must be of a static `ZoneLocation`.
+pub(crate) unsafe fn begin(location: &sys::___tracy_source_location_data) {
+	// SAFETY: Tracy is running, while zones could be constructed.
+	let time = unsafe { sys::___tracy_gizmos_time() };
+	PENDING.with_borrow_mut(|pending| pending.push(Pending { location, time, emitted: false }));
+	UNFLUSHED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Emits all pending lazy zones of the current thread.
+///
+/// Must be called before anything is emitted into the current
+/// thread's timeline.
+#[inline(always)]
+pub(crate) fn flush() {
+	if UNFLUSHED.load(Ordering::Relaxed) != 0 && !crate::muted() {
+		flush_slow();
+	}
+}
+
+#[cold]
+fn flush_slow() {
+	PENDING.with_borrow_mut(|pending| {
+		// Emitted zones always form a prefix of the stack.
+		for zone in pending.iter_mut().filter(|zone| !zone.emitted) {
+			if !emit_begin(zone) {
+				break;
+			}
+		}
+	});
+}
+
+/// Emits the zone begin, returns `false` if the instrumentation got
+/// muted meanwhile.
+fn emit_begin(zone: &mut Pending) -> bool {
+	let Some(emission) = crate::emission() else {
+		return false;
+	};
+	// SAFETY: Location is valid until the zone ends, see `begin`.
+	unsafe {
+		sys::___tracy_gizmos_emit_zone_begin_at(zone.location, zone.time);
+	}
+	// User hooks must not hold the shutdown back.
+	drop(emission);
+	// SAFETY: Location is valid until the zone ends, see `begin`.
+	unsafe {
+		zone_path::enter((*zone.location).name, ACTIVE);
+		#[cfg(debug_assertions)]
+		crate::lifo::enter(ACTIVE);
+		// `ZoneLocation` is a transparent wrapper of the location, see
+		// `begin`.
+		#[cfg(feature = "zone-hooks")]
+		crate::hooks::enter(Some(&*zone.location.cast::<crate::ZoneLocation>()), ACTIVE);
+		#[cfg(feature = "mirror")]
+		crate::mirror::zone_begin_cstr((*zone.location).name);
+	}
+	self_profile::count_event();
+	zone.emitted = true;
+	UNFLUSHED.fetch_sub(1, Ordering::Relaxed);
+	true
+}
+
+/// Ends the innermost lazy zone of the current thread. If it wasn't
+/// emitted yet, it is emitted only if it lasted at least `min`.
+pub(crate) fn end(min: Option<Duration>) {
+	let Some(mut zone) = PENDING.with_borrow_mut(Vec::pop) else {
+		return;
+	};
+	if !zone.emitted {
+		if crate::muted() || min.is_none_or(|min| clock::elapsed_since(zone.time) < min) {
+			UNFLUSHED.fetch_sub(1, Ordering::Relaxed);
+			return;
+		}
+		// Outer zones are emitted first, so emitted zones keep
+		// forming a prefix of the stack.
+		flush_slow();
+		if !emit_begin(&mut zone) {
+			UNFLUSHED.fetch_sub(1, Ordering::Relaxed);
+			return;
+		}
+	}
+	zone_path::leave(ACTIVE);
+	#[cfg(debug_assertions)]
+	crate::lifo::leave(ACTIVE);
+	#[cfg(feature = "zone-hooks")]
+	crate::hooks::leave(ACTIVE);
+	let Some(_emission) = crate::emission() else {
+		return;
+	};
+	// SAFETY: The zone begin was emitted above or during a flush.
+	unsafe {
+		sys::___tracy_gizmos_emit_zone_end_at(sys::___tracy_gizmos_time());
+	}
+	#[cfg(feature = "mirror")]
+	crate::mirror::zone_end();
+	self_profile::count_event();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+
+	fn emitted() -> Vec<bool> {
+		PENDING.with_borrow(|pending| pending.iter().map(|zone| zone.emitted).collect())
+	}
+
+	#[test]
+	fn flushed_by_nested_events() {
+		let _tracy = start_capture();
+		{
+			zone!(lazy "outer");
+			zone!(lazy "inner", Color::ORANGE);
+			assert_eq!(emitted(), [false, false]);
+			zone!("child");
+			assert_eq!(emitted(), [true, true]);
+		}
+		{
+			zone!(lazy "quiet", min: Duration::from_secs(60));
+			assert_eq!(emitted(), [false]);
+			zone!("tiny", min: 1ns);
+			assert_eq!(emitted(), [false, false]);
+		}
+		assert_eq!(emitted(), []);
+		{
+			zone!(lazy "outer", min: Duration::from_secs(60));
+			{
+				zone!(lazy "inner", min: Duration::ZERO);
+				assert_eq!(emitted(), [false, false]);
+			}
+			assert_eq!(emitted(), [true]);
+		}
+		assert_eq!(emitted(), []);
+		{
+			zone!(lazy "noisy");
+			message!("flush");
+			assert_eq!(emitted(), [true]);
+		}
+	}
+}
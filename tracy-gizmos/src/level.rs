@@ -0,0 +1,181 @@
+#[cfg(feature = "enabled")]
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::Color;
+
+/// Verbosity level of a zone, refer to [`set_zone_level`].
+///
+/// Levels are ordered from the most to the least important one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Level {
+	/// Zones, which are always interesting, e.g. frame phases.
+	Essential,
+	/// Regular zones.
+	Normal,
+	/// Zones of finer granularity, e.g. per subsystem steps.
+	Detailed,
+	/// Zones of the finest granularity, e.g. per item of work.
+	Verbose,
+}
+
+/// Level of a message, refer to [`message!`].
+///
+/// Levels are ordered from the most to the least important one, same
+/// as in `log`.
+///
+/// [`message!`]: crate::message!
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum MessageLevel {
+	/// Errors, shown in red.
+	Error = 1,
+	/// Warnings, shown in yellow.
+	Warn,
+	/// Regular messages, shown in white.
+	Info,
+	/// Debugging details, shown in light blue.
+	Debug,
+	/// The finest details, shown in gray.
+	Trace,
+}
+
+impl MessageLevel {
+	/// Returns the color of the messages of this level.
+	pub const fn color(self) -> Color {
+		match self {
+			Self::Error => Color::RED,
+			Self::Warn  => Color::YELLOW,
+			Self::Info  => Color::WHITE,
+			Self::Debug => Color::LIGHT_STEEL_BLUE,
+			Self::Trace => Color::GRAY,
+		}
+	}
+
+	/// Returns `true`, if messages of this level are compiled in.
+	///
+	/// The most verbose level is selected by `max-level-*` features,
+	/// or `release-max-level-*` features in release builds, same as
+	/// in `log`. Every level is compiled in by default.
+	pub const fn is_enabled(self) -> bool {
+		self as u8 <= MAX_MESSAGE_LEVEL
+	}
+}
+
+/// The most verbose level of messages, which are compiled in, `0`
+/// means none.
+const MAX_MESSAGE_LEVEL: u8 = {
+	let release = if cfg!(feature = "release-max-level-off") {
+		Some(0)
+	} else if cfg!(feature = "release-max-level-error") {
+		Some(MessageLevel::Error as u8)
+	} else if cfg!(feature = "release-max-level-warn") {
+		Some(MessageLevel::Warn as u8)
+	} else if cfg!(feature = "release-max-level-info") {
+		Some(MessageLevel::Info as u8)
+	} else if cfg!(feature = "release-max-level-debug") {
+		Some(MessageLevel::Debug as u8)
+	} else if cfg!(feature = "release-max-level-trace") {
+		Some(MessageLevel::Trace as u8)
+	} else {
+		None
+	};
+
+	match release {
+		Some(release) if !cfg!(debug_assertions) => release,
+		_ if cfg!(feature = "max-level-off")     => 0,
+		_ if cfg!(feature = "max-level-error")   => MessageLevel::Error as u8,
+		_ if cfg!(feature = "max-level-warn")    => MessageLevel::Warn as u8,
+		_ if cfg!(feature = "max-level-info")    => MessageLevel::Info as u8,
+		_ if cfg!(feature = "max-level-debug")   => MessageLevel::Debug as u8,
+		_                                        => MessageLevel::Trace as u8,
+	}
+};
+
+/// The most verbose level of zones, which are emitted.
+#[cfg(feature = "enabled")]
+static ZONE_LEVEL: AtomicU8 = AtomicU8::new(Level::Verbose as u8);
+
+/// Sets the most verbose level of zones, which are emitted.
+///
+/// It allows to toggle entire classes of zones at run-time, e.g.
+/// from a debug menu, without recompiling or passing `enabled:`
+/// expressions everywhere. Only zones with an explicit `level:`
+/// argument of [`zone!`] are affected, which costs a single atomic
+/// load per zone. Every level is emitted by default.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn cull(object: u32) {}
+/// # let objects = [1, 2, 3];
+/// set_zone_level(Level::Normal);
+/// zone!("culling", level: Level::Normal);
+/// for object in objects {
+///     zone!("cull object", level: Level::Verbose); // Not emitted.
+///     cull(object);
+/// }
+/// ```
+///
+/// [`zone!`]: crate::zone!
+pub fn set_zone_level(level: Level) {
+	#[cfg(feature = "enabled")]
+	ZONE_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Returns the most verbose level of zones, which are emitted.
+#[cfg(feature = "debug-http")]
+pub(crate) fn zone_level() -> Level {
+	#[cfg(feature = "enabled")]
+	return match ZONE_LEVEL.load(Ordering::Relaxed) {
+		0 => Level::Essential,
+		1 => Level::Normal,
+		2 => Level::Detailed,
+		_ => Level::Verbose,
+	};
+
+	#[cfg(not(feature = "enabled"))]
+	Level::Verbose
+}
+
+/// Returns `true`, if zones of the given level are emitted.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn is_emitted(level: Level) -> bool {
+	level as u8 <= ZONE_LEVEL.load(Ordering::Relaxed)
+}
+
+#[cfg(all(test, feature = "enabled"))]
+mod tests {
+	use super::*;
+	use crate::zone;
+
+	#[test]
+	fn levels_are_filtered() {
+		let _tracy = crate::start_capture();
+		assert!(is_emitted(Level::Verbose));
+		set_zone_level(Level::Normal);
+		zone!(normal, "normal", level: Level::Normal);
+		zone!(verbose, "verbose", crate::Color::RED, level: Level::Verbose);
+		assert_eq!((normal.ctx.active, verbose.ctx.active), (1, 0));
+		assert!(!is_emitted(Level::Detailed));
+		set_zone_level(Level::Verbose);
+		zone!("verbose", level: Level::Verbose);
+	}
+
+	#[test]
+	fn leveled_messages() {
+		use crate::message;
+		use crate::record::Event::*;
+
+		assert!(MessageLevel::Error < MessageLevel::Trace);
+		let events = crate::record::record(|| {
+			message!(MessageLevel::Error, "error");
+			message!(MessageLevel::Trace, "trace");
+		});
+		let expected = if MessageLevel::Trace.is_enabled() { 2 } else { 1 };
+		assert_eq!(events.len(), expected);
+		assert_eq!(events[0], Message("error".into()));
+	}
+}
@@ -1,6 +1,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg), deny(rustdoc::broken_intra_doc_links))]
 #![cfg_attr(any(doc, feature = "enabled"), deny(missing_docs))]
 #![cfg_attr(not(feature = "enabled"), allow(unused_variables))]
+// Feature list items continue on the unindented lines.
+#![allow(clippy::doc_lazy_continuation)]
 #![cfg_attr(
 	feature = "unstable-function-names",
 	allow(incomplete_features),
@@ -61,9 +63,107 @@
 //! - **`enabled`** - enables the instrumentation and everything
 //! related to it.
 //! - **`attributes`** - includes support for the `#[instrument]` attribute.
+//! - **`migration-shims`** - includes [`profile_function!`] &
+//! [`profile_scope!`] shims of the `puffin` macros and makes
+//! `#[instrument]` accept the arguments of `tracing::instrument`,
+//! so large codebases could switch the profilers by a find &
+//! replace. Implies `attributes` feature.
 //! - **`unstable-function-names`** *(nightly only)* -
 //! includes the enclosing function name into every zone without
 //! additional runtime overhead.
+//! - **`function-names`** - includes the enclosing function name
+//! into every zone on stable toolchains. The name is resolved once
+//! per zone location on its first use, which leaks a copy of the
+//! location, and costs an extra atomic load per zone afterwards.
+//! `unstable-function-names` takes precedence, if enabled as well.
+//! - **`name-length-check`** - turns too long zone, plot & frame
+//! names into compilation errors instead of confusingly truncated
+//! names in the profiler. The limit is 64 bytes, unless specified
+//! via `TRACY_GIZMOS_MAX_NAME_LENGTH` environment variable during
+//! the build.
+//! - **`build-profiling`** - includes helpers to profile build
+//! scripts and procedural macros, refer to [`build`].
+//! - **`zone-path`** - tracks currently active zones per thread, so
+//! [`message_os_error!`] can tell where exactly the error has
+//! happened. Adds a small run-time cost to every zone.
+//! - **`etw`** *(Windows only)* - mirrors frame marks and messages
+//! as ETW events of the `TracyGizmos` TraceLogging provider, so
+//! captures taken with WPA or xperf can be correlated with Tracy
+//! captures.
+//! - **`trace-marker`** *(Linux only)* - mirrors frame marks and
+//! zones into the kernel trace buffer via tracefs `trace_marker`,
+//! so `perf record -e ftrace:print`, `trace-cmd` or Perfetto
+//! sessions can be correlated with Tracy captures. Requires write
+//! access to tracefs and makes every zone a syscall. Lazy zones are
+//! not mirrored.
+//! - **`mirror`** - delivers every zone begin & end, message and
+//! plot value also to the callback registered via [`set_mirror`],
+//! e.g. for in-app overlays or custom logging. Adds a small
+//! run-time cost to every event.
+//! - **`zone-hooks`** - calls the hooks registered via
+//! [`on_zone_enter`] & [`on_zone_exit`] with the location of every
+//! zone, e.g. for custom HUD timers. Adds a small run-time cost to
+//! every zone.
+//! - **`futures`** - includes [`TracyStreamExt`] to instrument
+//! asynchronous streams.
+//! - **`wgpu`** - includes [`WgpuFrameImages`] to capture frame
+//! images from `wgpu` textures, e.g. the surface ones. Implies
+//! `frame-images` feature.
+//! - **`winit`** - includes [`TracyApp`] to mark the main frames of
+//! `winit` applications on redraws. A `winit` backend, e.g. `x11`,
+//! should be enabled by the application.
+//! - **`rayon`** - includes [`thread::rayon_lifecycle`] to report
+//! the `rayon` pool threads start & exit.
+//! - **`tokio`** - includes [`thread::tokio_lifecycle`] to report
+//! the `tokio` runtime threads start & exit.
+//! - **`log`** - includes [`TracyLogger`], a `log` backend, which
+//! forwards records to Tracy's log.
+//! - **`tracing`** - includes [`TracyLayer`], a `tracing-subscriber`
+//! layer, which turns spans into zones and events into messages.
+//! - **`zone-end-callstack`** - emits a `<name> end` message with a
+//! callstack right before every zone ends, to find out who returned
+//! early out of a scope. Its depth is 16 frames, unless changed via
+//! [`set_zone_end_callstack_depth`]. It is quite expensive, refer to
+//! [`zone!`] for the per-zone alternative. Implies `zone-path`
+//! feature, which provides the names. Can't be combined with
+//! `no-callstack` feature.
+//! - **`no-dynamic-strings`** - removes every code path formatting
+//! strings at run-time, for binary-size sensitive builds. Only
+//! literal names and messages are accepted, dynamic [`message!`]
+//! & [`set_thread_name!`] forms, [`zone_dynamic!`] and
+//! [`message_os_error!`] fail to compile, while [`Zone::kv`],
+//! [`Zone::text_fmt`], [`Zone::begin_dynamic`], signed &
+//! floating-point zone values, capture segments, repeating timers,
+//! state tracks, zone templates, latency probes, app info batches,
+//! context zones, tracked arcs, thread lifecycle messages and the
+//! asset pipeline preset are not available.
+//! - **`instr-level-1`**, **`instr-level-2`**, **`instr-level-3`** -
+//! select the instrumentation level. [`zone!`]s are always on
+//! (level 1), while [`zone_l2!`] & [`zone_l3!`] are compiled out,
+//! unless the corresponding level is selected. Higher levels
+//! include the lower ones.
+//! - **`max-level-off`**, **`max-level-error`**, ...,
+//! **`max-level-trace`** - select the most verbose
+//! [`MessageLevel`], which is compiled in, same as in `log`.
+//! **`release-max-level-*`** features do the same only in release
+//! builds, taking precedence there. Every level is compiled in by
+//! default.
+//! - **`self-profile`** - makes the crate report its own overhead
+//! (emitted events and copied text bytes per second, interner size)
+//! as plots, so it is possible to verify the instrumentation itself
+//! isn't the bottleneck.
+//! - **`shared-zone-locations`** - stores [`zone!`] source locations
+//! as constants instead of a static per expansion, so the compiler
+//! is free to merge identical ones (same name, color, function, file
+//! & line), e.g. when a macro unrolls the same zone many times into
+//! one function. It could reduce static data in generated code, but
+//! the merge is up to the compiler & not guaranteed, so such zones
+//! could still show up as separate locations in the capture. With
+//! `function-names` the location is still copied per zone at
+//! run-time.
+//! - **`debug-http`** - includes [`serve_debug_http`] to inspect &
+//! control the profiler of headless services over HTTP on the
+//! localhost.
 //!
 //! # Tracy features
 //!
@@ -103,12 +203,29 @@
 //! Influences `TRACY_NO_CODE_TRANSFER`.
 //! - **`vsync`** - enables the hardware Vsync events capture
 //! (assuming having the privilege), which will be reported as frame
-//! events per monitor. Influences `TRACY_NO_VSYNC_CAPTURE`.
+//! events per monitor. Requires `system-tracing` feature, use
+//! [`vsync_status`] to find out whether the capture works.
+//! Influences `TRACY_NO_VSYNC_CAPTURE`.
+//! - **`frame-images`** - enables [`emit_frame_image`] to attach
+//! screenshots to frames, which are shown in the frame timeline.
+//! Influences `TRACY_NO_FRAME_IMAGE`.
+//! - **`timer-fallback`** - makes Tracy use the OS clock on x86 CPUs
+//! without the invariant time stamp counter, instead of refusing to
+//! start. Use [`timer_source`] to find out, which timer is in use.
+//! Influences `TRACY_TIMER_FALLBACK`.
 //! - **`no-exit`** - enables the short-lived application profiling
 //! improvement. When `TRACY_NO_EXIT` environment variable is set to
 //! `1`, profiled application will wait for the server connection to
 //! transfer the data, even if it has already finished executing.
 //! Influences `TRACY_NO_EXIT`.
+//! - **`no-callstack`** - compiles out all the callstack collection
+//! machinery from the Tracy client. Requesting a callstack via any
+//! `callstack:` macro argument becomes a compilation error instead
+//! of being silently ignored. Can't be combined with `sampling`.
+//! Influences `TRACY_NO_CALLSTACK`.
+//! - **`fibers`** - enables the fibers support, which is required
+//! for [`FiberPool`]. Adds a small run-time cost to every event.
+//! Influences `TRACY_FIBERS`.
 //! - **`broadcast`** - enables the local network announcement, so
 //! profiling servers can find the client. Influences
 //! `TRACY_NO_BROADCAST`.
@@ -117,22 +234,210 @@
 //! `TRACY_ONLY_LOCALHOST`.
 //! - **`only-ipv4`** - restricts Tracy to only listenting on IPv4
 //! network interfaces. Influences `TRACY_ONLY_IPV4`.
+//! - **`cpp-exceptions`** - keeps C++ exceptions enabled when
+//! building the Tracy client. By default, it is built with
+//! `-fno-exceptions` (where supported), which keeps the client code
+//! itself free of exceptions, but doesn't stop the C++ standard
+//! library from throwing through it. The entry points added by this
+//! crate are `noexcept` regardless, so such an exception terminates
+//! the process there instead of unwinding into Rust code. Enable it
+//! only for toolchains, which fail to build without exceptions.
 
 #[cfg(feature = "enabled")]
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+#[cfg(all(feature = "enabled", feature = "zone-end-callstack"))]
+use std::sync::atomic::AtomicI32;
+#[cfg(feature = "enabled")]
+use std::time::Instant;
+use std::ffi::CStr;
 use std::marker::PhantomData;
+use std::time::Duration;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "attributes")))]
 #[doc(inline)]
 #[cfg(feature = "attributes")]
 pub use attrs::{instrument, capture};
 
+#[cfg(any(feature = "enabled", feature = "debug-http"))]
+mod background;
+#[cfg_attr(docsrs, doc(cfg(feature = "build-profiling")))]
+#[cfg(feature = "build-profiling")]
+pub mod build;
+pub mod category;
+mod aggregate;
+#[cfg(not(feature = "no-dynamic-strings"))]
+mod app_info_batch;
+mod clock;
+mod coalesce;
+mod commands;
+#[cfg(not(feature = "no-dynamic-strings"))]
+mod context;
+#[cfg(feature = "debug-http")]
+mod debug_http;
+#[cfg(all(debug_assertions, feature = "enabled", not(feature = "no-dynamic-strings")))]
+mod collisions;
 mod color;
+mod dyn_zone;
+#[cfg(feature = "enabled")]
+mod emission;
+#[cfg(all(windows, feature = "etw", feature = "enabled"))]
+mod etw;
+#[cfg(feature = "enabled")]
+mod feature_matrix;
+#[cfg(feature = "fibers")]
+mod fiber;
+#[cfg(any(test, all(feature = "enabled", not(feature = "no-dynamic-strings"))))]
+mod fixed_buf;
+#[cfg(feature = "frame-images")]
+mod frame_image;
+#[cfg(feature = "enabled")]
+mod frame_plot;
+mod frame_set;
+mod frame_stats;
+#[cfg(any(test, all(feature = "enabled", not(feature = "no-dynamic-strings"))))]
+mod hex;
+#[cfg(feature = "zone-hooks")]
+mod hooks;
+pub mod integration;
+mod intern;
+#[cfg(not(feature = "no-dynamic-strings"))]
+mod jit;
+mod kv;
+#[cfg(feature = "enabled")]
+mod lazy;
+#[cfg(not(feature = "no-dynamic-strings"))]
+mod latency;
+mod level;
+#[cfg(all(debug_assertions, feature = "enabled"))]
+mod lifo;
+#[cfg(all(feature = "log", not(feature = "no-dynamic-strings")))]
+mod logger;
+#[doc(hidden)]
+pub mod limits;
+mod manual;
 mod memory;
+#[cfg(feature = "migration-shims")]
+mod migration;
+#[cfg(feature = "mirror")]
+mod mirror;
+mod network;
+mod pacer;
 mod plot;
+#[cfg(feature = "enabled")]
+mod plot_batch;
+pub mod presets;
+mod query;
+#[cfg(feature = "enabled")]
+mod rate;
+#[cfg(all(test, feature = "enabled"))]
+mod record;
+mod retry;
+#[cfg(not(feature = "no-dynamic-strings"))]
+mod segment;
+#[cfg(feature = "enabled")]
+mod self_profile;
+mod session;
+#[cfg(not(feature = "no-dynamic-strings"))]
+mod state;
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(not(feature = "no-dynamic-strings"))]
+mod template;
+pub mod thread;
+mod thread_filter;
+#[cfg(not(feature = "no-dynamic-strings"))]
+mod timer;
+mod toggle;
+mod token;
+#[cfg(not(feature = "no-dynamic-strings"))]
+mod tracked_arc;
+#[cfg(all(feature = "tracing", not(feature = "no-dynamic-strings")))]
+mod tracing_layer;
+#[cfg(all(target_os = "linux", feature = "trace-marker", feature = "enabled"))]
+mod trace_marker;
+mod version;
+mod vsync;
+#[cfg(feature = "wgpu")]
+mod wgpu_frame_image;
+#[cfg(feature = "winit")]
+mod winit_app;
+#[cfg(feature = "enabled")]
+mod zone_path;
 
+pub use aggregate::{AggregatedZone, flush_aggregates};
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub use app_info_batch::AppInfoBatch;
+pub use clock::{TimerSource, from_tracy_time, timer_source, to_tracy_time};
+pub use coalesce::*;
 pub use color::*;
+pub use commands::{ProfilerCommand, profiler_commands};
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub use context::{clear_context, set_context};
+#[cfg(feature = "debug-http")]
+#[cfg_attr(docsrs, doc(cfg(feature = "debug-http")))]
+pub use debug_http::{DebugHttp, serve_debug_http};
+pub use dyn_zone::AnyNamed;
+#[doc(hidden)]
+pub use dyn_zone::seal;
+#[cfg(feature = "fibers")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fibers")))]
+pub use fiber::*;
+#[cfg(feature = "frame-images")]
+#[cfg_attr(docsrs, doc(cfg(feature = "frame-images")))]
+pub use frame_image::emit_frame_image;
+pub use frame_set::{FrameSet, run_frame, run_named_frame};
+pub use frame_stats::FrameStats;
+#[cfg(feature = "zone-hooks")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zone-hooks")))]
+pub use hooks::{ZoneHook, on_zone_enter, on_zone_exit};
+pub use intern::{DEFAULT_INTERNER_CAP, InternerStats, interner_stats, set_interner_cap};
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub use jit::{register_jit_symbol, unregister_jit_symbol};
+pub use kv::parse_kv;
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub use latency::LatencyProbe;
+pub use level::{Level, MessageLevel, set_zone_level};
+#[cfg(all(feature = "log", not(feature = "no-dynamic-strings")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "log")))]
+pub use logger::TracyLogger;
+pub use manual::ManualZone;
+pub use memory::{MemoryPool, PoolStats};
+#[cfg(feature = "mirror")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mirror")))]
+pub use mirror::{MirrorEvent, MirrorSink, set_mirror};
+pub use network::*;
+pub use pacer::FramePacer;
 pub use plot::*;
+pub use query::QueryStats;
+pub use retry::{Attempt, RetryZone};
+pub use session::capture_id;
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub use segment::*;
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub use state::*;
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+pub use stream::{InstrumentedStream, TracyStreamExt};
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub use template::ZoneTemplate;
+pub use thread_filter::set_thread_filter;
+pub use toggle::Toggle;
+pub use token::ZoneToken;
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub use timer::*;
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub use tracked_arc::TrackedArc;
+#[cfg(all(feature = "tracing", not(feature = "no-dynamic-strings")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+pub use tracing_layer::TracyLayer;
+pub use version::*;
+pub use vsync::{VsyncStatus, vsync_monitors, vsync_status};
+#[cfg(feature = "wgpu")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wgpu")))]
+pub use wgpu_frame_image::WgpuFrameImages;
+#[cfg(feature = "winit")]
+#[cfg_attr(docsrs, doc(cfg(feature = "winit")))]
+pub use winit_app::TracyApp;
 
 /// Sets the current thread's name.
 ///
@@ -168,6 +473,7 @@ pub use plot::*;
 /// # fn loop_and_do_work() {}
 /// let id = get_next_worker_id();
 /// std::thread::spawn(move || {
+///     # #[cfg(not(feature = "no-dynamic-strings"))]
 ///     set_thread_name!("worker-thread {}", id);
 ///     zone!("Working"); // Tracy will show it on this thread track.
 ///     loop_and_do_work();
@@ -183,14 +489,14 @@ macro_rules! set_thread_name {
 		}
 	};
 
-	($format:literal, $($args:expr),*) => {{
+	($format:literal, $($args:expr),*) => {$crate::dynamic_string! {{
 		let name = format!(concat!($format, '\0'), $($args),*).into_bytes();
 		// SAFETY: We null-terminated the string during formatting.
 		unsafe {
 			let name = std::ffi::CString::from_vec_with_nul_unchecked(name);
 			$crate::details::set_thread_name(name.as_ptr().cast());
 		}
-	}};
+	}}};
 }
 
 #[macro_export]
@@ -210,6 +516,48 @@ macro_rules! set_thread_name {
 	}};
 }
 
+// Every `callstack:` macro argument goes through this, so asking for
+// callstacks is a compilation error when they are compiled out.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "no-callstack"))]
+macro_rules! callstack_depth {
+	($depth:expr) => {{
+		const DEPTH: i32 = $crate::limits::check_callstack_depth($depth);
+		DEPTH
+	}};
+}
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "no-callstack")]
+macro_rules! callstack_depth {
+	($depth:expr) => {
+		::core::compile_error!("Callstacks are disabled by `no-callstack` feature.")
+	};
+}
+
+#[cfg(all(feature = "no-callstack", feature = "sampling"))]
+compile_error!("`sampling` feature requires callstacks, it can't be combined with `no-callstack`.");
+#[cfg(all(feature = "no-callstack", feature = "zone-end-callstack"))]
+compile_error!("`zone-end-callstack` feature requires callstacks, it can't be combined with `no-callstack`.");
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "no-dynamic-strings"))]
+macro_rules! dynamic_string {
+	($($code:tt)*) => { $($code)* };
+}
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "no-dynamic-strings")]
+macro_rules! dynamic_string {
+	($($code:tt)*) => {
+		::core::compile_error!(
+			"Dynamic strings are disabled by `no-dynamic-strings` feature, only literals are allowed."
+		);
+	};
+}
+
 /// Sends a message to Tracy's log.
 ///
 /// Fast navigation in large data sets and correlating zones with what
@@ -233,6 +581,23 @@ macro_rules! set_thread_name {
 /// message!(Color::YELLOW, "App failed to find something.");
 /// ```
 ///
+/// ## Leveled messages
+///
+/// A [`MessageLevel`] could be given instead of the color, so the
+/// message gets the color of the level. Messages of the levels, which
+/// are more verbose than selected by `max-level-*` features, are
+/// compiled out, refer to [`MessageLevel::is_enabled`]. The level
+/// should be spelled as `MessageLevel::<Level>`, as it is matched by
+/// the macro.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # let path = "level.bin";
+/// message!(MessageLevel::Warn, "Asset is missing, falling back.");
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
+/// message!(MessageLevel::Trace, "Loading {}", path);
+/// ```
+///
 /// ## Dynamic messages
 ///
 /// It is also possible to use dynamic data as the message text.
@@ -248,13 +613,39 @@ macro_rules! set_thread_name {
 /// # use tracy_gizmos::*;
 /// # let i = 0;
 /// # let file_path = "file".to_string();
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
 /// message!("Trying {}", i);
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
 /// message!(&file_path);
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
 /// message!(Color::GREEN, "{} is good!", file_path);
 /// ```
+///
+/// ## Callstacks
+///
+/// A message could also carry the callstack of the given depth where
+/// it was sent from, same as [`zone!`] does. It comes last, after the
+/// format arguments, if any.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # let path = "level.bin";
+/// # #[cfg(not(feature = "no-callstack"))]
+/// message!("Out of memory.", callstack: 16);
+/// # #[cfg(not(any(feature = "no-dynamic-strings", feature = "no-callstack")))]
+/// message!(MessageLevel::Error, "Failed to load {}", path, callstack: 8);
+/// ```
+///
+/// It is a compilation error with `no-callstack` feature.
 #[macro_export]
 #[cfg(any(doc, feature = "enabled"))]
 macro_rules! message {
+	(MessageLevel::$level:ident, $($rest:tt)+) => {
+		if $crate::MessageLevel::$level.is_enabled() {
+			$crate::message!($crate::MessageLevel::$level.color(), $($rest)+);
+		}
+	};
+
 	($text:literal) => {
 		// SAFETY: We null-terminate the string.
 		unsafe {
@@ -262,15 +653,28 @@ macro_rules! message {
 		}
 	};
 
-	($text:expr) => {
-		$crate::details::message_size($text);
+	($text:literal, callstack:$depth:expr) => {
+		// SAFETY: We null-terminate the string.
+		unsafe {
+			$crate::details::message_callstack(
+				concat!($text, '\0').as_ptr(),
+				$crate::callstack_depth!($depth),
+			);
+		}
 	};
 
-	($format:literal, $($args:expr),*) => {
-		let _text = format!($format, $($args),*);
-		$crate::details::message_size(&_text);
+	($format:literal, $($rest:tt)+) => {
+		$crate::message!(@format [] $format, [] $($rest)+);
 	};
 
+	($text:expr) => {$crate::dynamic_string! {
+		$crate::details::message_size($text);
+	}};
+
+	($text:expr, callstack:$depth:expr) => {$crate::dynamic_string! {
+		$crate::details::message_size_callstack($text, $crate::callstack_depth!($depth));
+	}};
+
 	($color:expr, $text:literal) => {
 		// SAFETY: We null-terminate the string.
 		unsafe {
@@ -281,17 +685,57 @@ macro_rules! message {
 		}
 	};
 
-	($color:expr, $text:expr) => {
+	($color:expr, $text:literal, callstack:$depth:expr) => {
+		let _color = $color;
+		// SAFETY: We null-terminate the string.
+		unsafe {
+			$crate::details::message_color_callstack(
+				concat!($text, '\0').as_ptr(),
+				_color,
+				$crate::callstack_depth!($depth),
+			);
+		}
+	};
+
+	($color:expr, $text:expr) => {$crate::dynamic_string! {
 		$crate::details::message_size_color(
 			$text,
 			$color,
 		);
+	}};
+
+	($color:expr, $text:expr, callstack:$depth:expr) => {$crate::dynamic_string! {
+		$crate::details::message_size_color_callstack(
+			$text,
+			$color,
+			$crate::callstack_depth!($depth),
+		);
+	}};
+
+	($color:expr, $format:literal, $($rest:tt)+) => {
+		$crate::message!(@format [$color] $format, [] $($rest)+);
 	};
 
-	($color:expr, $format:literal, $($args:expr),*) => {
-		let _text = format!($format, $($args),*);
-		$crate::details::message_size_color(&_text, $color);
+	// Format arguments are munched one token at a time, as the trailing
+	// `callstack:` can't follow a repetition of expressions.
+	(@format [$($color:expr)?] $format:literal, [$($args:tt)*] callstack:$depth:expr) => {
+		$crate::message!(@emit [$($color)?] $format, [$($args)*] $crate::callstack_depth!($depth));
 	};
+	(@format [$($color:expr)?] $format:literal, [$($args:tt)*] $next:tt $($rest:tt)*) => {
+		$crate::message!(@format [$($color)?] $format, [$($args)* $next] $($rest)*);
+	};
+	(@format [$($color:expr)?] $format:literal, [$($args:tt)*]) => {
+		$crate::message!(@emit [$($color)?] $format, [$($args)*] 0);
+	};
+
+	(@emit [] $format:literal, [$($args:tt)*] $depth:expr) => {$crate::dynamic_string! {
+		let _text = format!($format, $($args)*);
+		$crate::details::message_size_callstack(&_text, $depth);
+	}};
+	(@emit [$color:expr] $format:literal, [$($args:tt)*] $depth:expr) => {$crate::dynamic_string! {
+		let _text = format!($format, $($args)*);
+		$crate::details::message_size_color_callstack(&_text, $color, $depth);
+	}};
 }
 
 #[macro_export]
@@ -324,6 +768,153 @@ macro_rules! message {
 			_ = $args;
 		)*
 	};
+
+	// Strips the trailing `callstack:`, it can't follow a repetition of
+	// expressions.
+	(@strip [$($args:tt)*], callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::message!($($args)*);
+	};
+	(@strip [$($args:tt)*] $next:tt $($rest:tt)*) => {
+		$crate::message!(@strip [$($args)* $next] $($rest)*);
+	};
+
+	($($rest:tt)+) => {
+		$crate::message!(@strip [] $($rest)+);
+	};
+}
+
+/// Sends a message formatted without heap allocations to Tracy's log.
+///
+/// The message is formatted into an on-stack buffer of the given
+/// capacity in bytes. If it doesn't fit, it is truncated and ends
+/// with `...`. It is useful for platforms or code paths, where heap
+/// use is prohibited.
+///
+/// Optionally, a custom [`Color`] could be assigned to the message.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # let (x, y) = (1, 2);
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
+/// message_static!(64, "x={}, y={}", x, y);
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
+/// message_static!(64, Color::RED, "x={x} is out of bounds");
+/// ```
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! message_static {
+	($capacity:literal, $format:literal $(, $args:expr)* $(,)?) => {$crate::dynamic_string! {
+		let mut _text = $crate::details::FixedBuf::<$capacity>::new();
+		_ = ::core::fmt::Write::write_fmt(&mut _text, format_args!($format $(, $args)*));
+		$crate::details::message_size(_text.as_str());
+	}};
+
+	($capacity:literal, $color:expr, $format:literal $(, $args:expr)* $(,)?) => {$crate::dynamic_string! {
+		let mut _text = $crate::details::FixedBuf::<$capacity>::new();
+		_ = ::core::fmt::Write::write_fmt(&mut _text, format_args!($format $(, $args)*));
+		$crate::details::message_size_color(_text.as_str(), $color);
+	}};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! message_static {
+	($capacity:literal, $format:literal $(, $args:expr)* $(,)?) => {
+		// Silence unused expression warnings.
+		$(
+			_ = $args;
+		)*
+	};
+
+	($capacity:literal, $color:expr, $format:literal $(, $args:expr)* $(,)?) => {
+		// Silence unused expression warnings.
+		_ = $color;
+		$(
+			_ = $args;
+		)*
+	};
+}
+
+/// Sends an operating system error message to Tracy's log.
+///
+/// It standardizes how failures appear in captures: the message is
+/// always [`Color::RED`] and contains the passed text, the error
+/// description and its code (or kind, if the error is not coming
+/// from the operating system).
+///
+/// If `zone-path` feature is enabled, currently active zones of this
+/// thread are also attached.
+///
+/// Same costs and limitations as for dynamic [`message!`]s apply.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// if let Err(e) = std::fs::File::open("config.toml") {
+///     // Will send something like:
+///     // "open failed: No such file or directory (os error 2)"
+///     # #[cfg(not(feature = "no-dynamic-strings"))]
+///     message_os_error!("open failed", e);
+/// }
+/// ```
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! message_os_error {
+	($text:literal, $err:expr) => {$crate::dynamic_string! {
+		$crate::details::message_os_error($text, &$err);
+	}};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! message_os_error {
+	($text:literal, $err:expr) => {
+		// Silences unused expression warning.
+		_ = &$err;
+	};
+}
+
+/// Sends a bounded hex dump of binary data to Tracy's log.
+///
+/// It is useful for protocol debugging, when packet contents need to
+/// appear alongside the timing. The message starts with the label
+/// and the data length, followed by rows of 16 bytes in hex & ASCII.
+/// At most `max_len` bytes are dumped, the rest is only counted.
+///
+/// Same costs and limitations as for dynamic [`message!`]s apply.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # let packet = [0u8; 1500];
+/// // Will send something like:
+/// // rx: 1500 bytes
+/// // 0000  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|
+/// // ...
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
+/// message_hex!("rx", &packet, 64);
+/// ```
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! message_hex {
+	($label:literal, $data:expr, $max_len:expr) => {$crate::dynamic_string! {
+		$crate::details::message_hex($label, $data, $max_len);
+	}};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! message_hex {
+	($label:literal, $data:expr, $max_len:expr) => {
+		// Silences unused expression warnings.
+		_ = $data;
+		_ = $max_len;
+	};
 }
 
 /// Marks the completed frame end moment.
@@ -391,6 +982,9 @@ macro_rules! message {
 /// }
 /// ```
 ///
+/// If the frame set name is known only at run-time, use [`FrameSet`]
+/// instead.
+///
 /// ## Discontinuous frames
 ///
 /// As discontinuous frame doesn't start immediately after previous
@@ -406,6 +1000,10 @@ macro_rules! message {
 ///     // do the I/O work.
 /// }
 /// ```
+///
+/// The same name can't be used for both continuous and
+/// discontinuous frames. Debug builds report such collisions via a
+/// message.
 #[macro_export]
 #[cfg(any(doc, feature = "enabled"))]
 macro_rules! frame {
@@ -417,6 +1015,7 @@ macro_rules! frame {
 	};
 
 	($name:literal) => {
+		const _: () = $crate::limits::check_name_length($name);
 		// SAFETY: We null-terminate the string.
 		unsafe {
 			$crate::details::mark_frame_end(concat!($name, '\0').as_ptr());
@@ -424,6 +1023,7 @@ macro_rules! frame {
 	};
 
 	($var:ident, $name:literal) => {
+		const _: () = $crate::limits::check_name_length($name);
 		#[allow(unused_variables)]
 		// SAFETY: We null-terminate the string.
 		let $var = unsafe {
@@ -448,49 +1048,395 @@ macro_rules! frame {
 
 #[cfg(feature = "enabled")]
 static STARTED: AtomicBool = AtomicBool::new(false);
+/// Reasons for the instrumentation to be a no-op, see [`muted`].
+#[cfg(feature = "enabled")]
+static MUTED: AtomicU8 = AtomicU8::new(MUTED_STOPPED);
+/// The instrumentation was disabled via [`after_fork`].
+#[cfg(feature = "enabled")]
+const MUTED_FORKED: u8 = 1 << 0;
+/// The capture is not started yet or is shutting down.
+#[cfg(feature = "enabled")]
+const MUTED_STOPPED: u8 = 1 << 1;
+/// The instrumentation was paused via [`set_paused`].
+#[cfg(feature = "enabled")]
+const MUTED_PAUSED: u8 = 1 << 2;
+/// Callstack depth of the zone end messages, refer to
+/// [`set_zone_end_callstack_depth`].
+#[cfg(all(feature = "enabled", feature = "zone-end-callstack"))]
+static ZONE_END_CALLSTACK_DEPTH: AtomicI32 = AtomicI32::new(16);
+
+/// Returns `true` if the instrumentation was disabled via
+/// [`after_fork`] or [`set_paused`], there is no running capture or
+/// the current thread is filtered out via [`set_thread_filter`].
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn muted() -> bool {
+	MUTED.load(Ordering::Relaxed) != 0 || thread_filter::filtered_out()
+}
+
+/// Returns the guard to call into Tracy with, unless [`muted`].
+///
+/// The capture shutdown waits for the guards to be dropped, so Tracy
+/// must be called only while the guard is held.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn emission() -> Option<emission::Emission> {
+	if thread_filter::filtered_out() {
+		return None;
+	}
+	let emission = emission::Emission::enter();
+	// The shutdown mutes first and then waits for the emissions, so
+	// either the emission is waited for or it sees the muted state.
+	(MUTED.load(Ordering::SeqCst) == 0).then_some(emission)
+}
+
+/// Returns `true` if a Tracy server is connected to the running
+/// capture, regardless of the instrumentation being paused or
+/// filtered out.
+#[cfg(all(feature = "enabled", feature = "debug-http"))]
+pub(crate) fn connected() -> bool {
+	let _emission = emission::Emission::enter();
+	MUTED.load(Ordering::SeqCst) & (MUTED_FORKED | MUTED_STOPPED) == 0
+		// SAFETY: The capture is running & the shutdown waits for us.
+		&& unsafe { sys::___tracy_connected() } != 0
+}
+
+/// Disables the instrumentation in a forked child process.
+///
+/// After `fork()` only the forking thread exists in the child, while
+/// Tracy relies on its own background threads, so the copied client
+/// is broken and would corrupt the capture. Call this in the child
+/// right after `fork()` returns, before using any instrumentation.
+///
+/// Afterwards, all the instrumentation in the child is a no-op and
+/// the inherited [`TracyCapture`] is just forgotten, when dropped:
+/// its threads are not joined and integrations are not shut down.
+/// Tracy can't be restarted in the child, so [`start_capture`] will
+/// panic there. The parent process is not affected at all.
+///
+/// There is no need to call it, if the child immediately does
+/// `exec()`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn fork() -> i32 { 0 }
+/// # fn serve() {}
+/// let _tracy = tracy_gizmos::start_capture();
+/// // Daemonizing.
+/// if fork() == 0 {
+///     tracy_gizmos::after_fork();
+///     serve();
+/// }
+/// ```
+#[cfg(any(doc, unix))]
+#[cfg_attr(docsrs, doc(cfg(unix)))]
+pub fn after_fork() {
+	#[cfg(feature = "enabled")]
+	MUTED.fetch_or(MUTED_FORKED, Ordering::Relaxed);
+}
+
+/// Pauses or resumes the instrumentation at run-time, e.g. from a
+/// debug console, while the capture keeps running.
+///
+/// While paused, nothing is emitted into the capture. It is better to
+/// pause & resume outside of any zone, as zones, which are active
+/// during the change, might end up not being closed in the capture.
+/// The instrumentation is not paused by default.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn load_level() {}
+/// let _tracy = start_capture();
+/// set_paused(true);
+/// load_level(); // Not emitted.
+/// set_paused(false);
+/// ```
+pub fn set_paused(paused: bool) {
+	#[cfg(feature = "enabled")]
+	if paused {
+		MUTED.fetch_or(MUTED_PAUSED, Ordering::Relaxed);
+	} else {
+		MUTED.fetch_and(!MUTED_PAUSED, Ordering::Relaxed);
+	}
+	#[cfg(not(feature = "enabled"))]
+	{
+		// Silences unused variable warning.
+		_ = paused;
+	}
+}
+
+/// Returns `true`, if the instrumentation is paused via
+/// [`set_paused`].
+pub fn is_paused() -> bool {
+	#[cfg(feature = "enabled")]
+	return MUTED.load(Ordering::Relaxed) & MUTED_PAUSED != 0;
+
+	#[cfg(not(feature = "enabled"))]
+	false
+}
+
+/// Sets the callstack depth of the `<name> end` messages, which
+/// `zone-end-callstack` feature emits right before every zone ends.
+/// It is 16 by default.
+///
+/// # Panics
+///
+/// Panics if the depth is not within `1..=62`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// let _tracy = start_capture();
+/// set_zone_end_callstack_depth(4); // Only the closest callers.
+/// ```
+#[cfg(feature = "zone-end-callstack")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zone-end-callstack")))]
+pub fn set_zone_end_callstack_depth(depth: u32) {
+	let depth = limits::check_callstack_depth(depth);
+	#[cfg(feature = "enabled")]
+	ZONE_END_CALLSTACK_DEPTH.store(depth, Ordering::Relaxed);
+	#[cfg(not(feature = "enabled"))]
+	{
+		// Silences unused variable warning.
+		_ = depth;
+	}
+}
 
 /// Starts the Tracy capture.
 ///
 /// Must be called *before* any other Tracy usage.
 ///
+/// This is the same as calling [`start_capture_with`] using the
+/// default [`CaptureOptions`].
+///
 /// # Panics
 ///
 /// Only one active capture can exist. Hence any consecutive
 /// `start_capture()` will panic, unless previously started capture is
 /// dropped.
 ///
+/// Panics if the CPU has no invariant time stamp counter, unless
+/// `timer-fallback` feature is enabled, refer to [`timer_source`].
+///
 /// # Examples
 ///
 /// ```no_run
 /// let _tracy = tracy_gizmos::start_capture();
 /// ```
 pub fn start_capture() -> TracyCapture {
+	start_capture_with(CaptureOptions::default())
+}
+
+/// Starts the Tracy capture with the specified options.
+///
+/// Must be called *before* any other Tracy usage.
+///
+/// The features the client was built with, e.g. `sampling` or
+/// callstacks, are reported via [`app_info`], so the capture tells
+/// why some data is missing.
+///
+/// # Panics
+///
+/// Only one active capture can exist. Hence any consecutive
+/// `start_capture_with()` will panic, unless previously started
+/// capture is dropped.
+///
+/// Panics if the CPU has no invariant time stamp counter, unless
+/// `timer-fallback` feature is enabled, refer to [`timer_source`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use tracy_gizmos::*;
+/// let _tracy = start_capture_with(CaptureOptions {
+///     connection_timeout: Some(Duration::from_secs(5)),
+///     ..Default::default()
+/// });
+/// ```
+pub fn start_capture_with(options: CaptureOptions) -> TracyCapture {
 	#[cfg(feature = "enabled")]
 	{
 		if STARTED.swap(true, Ordering::Acquire) {
 			panic!("Tracy capture has been started already.");
 		}
+		let timer = clock::timer_source();
+		if timer == clock::TimerSource::Unsupported {
+			// Tracy would terminate the process right away.
+			STARTED.store(false, Ordering::Release);
+			panic!(
+				"The CPU has no invariant TSC, so Tracy can't start. \
+				 Enable `timer-fallback` feature to use the OS clock instead."
+			);
+		}
+		network::init();
 		// SAFETY: Check above ensures this happens once.
 		unsafe {
 			sys::___tracy_startup_profiler();
 		}
+		MUTED.fetch_and(!MUTED_STOPPED, Ordering::SeqCst);
+		app_info(timer.describe());
+		app_info(&feature_matrix::describe());
+		#[cfg(all(windows, feature = "etw"))]
+		etw::register();
+		#[cfg(all(target_os = "linux", feature = "trace-marker"))]
+		trace_marker::open();
+		session::begin();
+		#[cfg(not(feature = "no-dynamic-strings"))]
+		AppInfoBatch::global().flush();
 	}
 
-	TracyCapture(PhantomData)
-}
+	#[cfg(feature = "enabled")]
+	thread::LIFECYCLE_MESSAGES.swap(options.thread_lifecycle_messages, Ordering::AcqRel);
+	#[cfg(feature = "enabled")]
+	plot_batch::set_window(options.plot_batching);
+	#[cfg(feature = "enabled")]
+	frame_plot::set_config(options.frame_time_plot);
+	#[cfg(feature = "enabled")]
+	if let Some(expected) = options.expected_viewer_protocol {
+		version::check_viewer(expected);
+	}
 
-/// Represents an active Tracy capture.
-///
-/// Obtaining a [`TracyCapture`] is *required* to instrument the code.
-/// Otherwise, the behaviour of instrumenting is undefined.
-///
-/// It is not allowed to have multiple copies of the [`TracyCapture`].
-///
-/// When it is dropped, the Tracy connection will be shutdown, which
+	#[cfg(feature = "enabled")]
+	let mut background = Vec::new();
+	#[cfg(feature = "enabled")]
+	if let Some(timeout) = options.connection_timeout {
+		let handler = options.on_connection_timeout;
+		background.push(background::Background::spawn(
+			"tracy-gizmos connection watcher",
+			move |stop| watch_connection(timeout, handler, stop),
+		));
+	}
+	#[cfg(all(feature = "enabled", feature = "self-profile"))]
+	background.push(self_profile::spawn());
+
+	#[cfg(feature = "enabled")]
+	clock::calibrate();
+	#[cfg(feature = "enabled")]
+	integration::start();
+
+	TracyCapture {
+		#[cfg(feature = "enabled")]
+		background,
+		_unsend: PhantomData,
+	}
+}
+
+/// Options controlling the Tracy capture behaviour.
+///
+/// Refer to [`start_capture_with`] for the usage how-to.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CaptureOptions {
+	/// If set, the capture will complain when no Tracy server
+	/// connects within the given time after the capture start.
+	///
+	/// A diagnostic is emitted as a message and also added to the
+	/// [`app_info`], so it will be visible in the trace description
+	/// when the server finally connects. It is also passed to
+	/// [`on_connection_timeout`], e.g. to print it to stderr.
+	///
+	/// This is helpful for figuring out why the trace ends up being
+	/// empty.
+	///
+	/// [`on_connection_timeout`]: CaptureOptions::on_connection_timeout
+	pub connection_timeout: Option<Duration>,
+	/// If set, it is called with the diagnostic, once no Tracy server
+	/// has connected within [`connection_timeout`].
+	///
+	/// It is called from a background thread.
+	///
+	/// [`connection_timeout`]: CaptureOptions::connection_timeout
+	pub on_connection_timeout: Option<fn(&str)>,
+	/// If `true`, threads spawned via [`thread::spawn_named`] or
+	/// reporting themselves via [`thread::emit_thread_started`] &
+	/// [`thread::emit_thread_exiting`] will have their lifecycle
+	/// visible as messages.
+	pub thread_lifecycle_messages: bool,
+	/// If set, plot values are coalesced per thread: only the latest
+	/// value of every plot is forwarded to Tracy once per the given
+	/// window.
+	///
+	/// This reduces the queue contention, when many threads plot
+	/// frequently, at the cost of the plot resolution. Batched values
+	/// are forwarded with the next plot emission after the window
+	/// ends, when the thread exits or on [`flush_plots`].
+	pub plot_batching: Option<Duration>,
+	/// If set, the capture will complain when the Tracy viewer of
+	/// the given network protocol version is not able to connect to
+	/// the bundled client, i.e. the version differs from
+	/// [`TracyVersion::PROTOCOL`].
+	///
+	/// Only the protocol version matters, viewers of other releases
+	/// with the same protocol connect fine. Same as for
+	/// [`connection_timeout`], a diagnostic message is sent and also
+	/// added to the [`app_info`].
+	///
+	/// [`connection_timeout`]: CaptureOptions::connection_timeout
+	pub expected_viewer_protocol: Option<u32>,
+	/// If set, every main frame mark also emits the time since the
+	/// previous one into the `Frame time, ms` plot with the given
+	/// configuration.
+	///
+	/// It saves measuring the frame time by hand to get a graph next
+	/// to the frame marks. Refer to [`FramePacer`] for the FPS plots
+	/// & the stutter detection.
+	pub frame_time_plot: Option<PlotConfig>,
+}
+
+/// Represents an active Tracy capture.
+///
+/// Obtaining a [`TracyCapture`] is *required* to instrument the code.
+/// Otherwise, the instrumentation is a no-op.
+///
+/// It is not allowed to have multiple copies of the [`TracyCapture`].
+///
+/// When it is dropped, the Tracy connection will be shutdown, which
 /// will also finish the capture.
-pub struct TracyCapture(PhantomData<*mut ()>);
+///
+/// # Shutdown
+///
+/// Other threads could still be instrumented, when the capture is
+/// dropped. Once the shutdown begins, the instrumentation becomes a
+/// no-op for them, e.g. zones begun before are just not ended, and
+/// the emissions already in progress are waited for before the
+/// profiler is stopped. Still, the data emitted by the threads
+/// around the shutdown is lost, so prefer to join the instrumented
+/// threads first, e.g. via [`TracyCapture::shutdown_after_join`].
+pub struct TracyCapture {
+	#[cfg(feature = "enabled")]
+	background: Vec<background::Background>,
+	_unsend: PhantomData<*mut ()>,
+}
 
 impl TracyCapture {
+	/// Joins the given threads and only then shuts the capture down,
+	/// so the instrumented threads can't race with the shutdown.
+	///
+	/// Returns the join results in the same order.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// let tracy = start_capture();
+	/// let workers: Vec<_> = (0..4).map(|_| std::thread::spawn(|| {
+	///     zone!("work");
+	/// })).collect();
+	/// for result in tracy.shutdown_after_join(workers) {
+	///     result.expect("Worker should not panic.");
+	/// }
+	/// ```
+	pub fn shutdown_after_join<T>(
+		self,
+		threads: impl IntoIterator<Item = std::thread::JoinHandle<T>>,
+	) -> Vec<std::thread::Result<T>> {
+		threads.into_iter().map(|thread| thread.join()).collect()
+	}
+
 	/// Returns `true` if a connection is currently established with
 	/// the Tracy server.
 	///
@@ -516,7 +1462,7 @@ impl TracyCapture {
 		// SAFETY: self could exist only if startup was issued and
 		// succeeded.
 		unsafe {
-			sys::___tracy_connected() != 0
+			emission().is_some_and(|_emission| sys::___tracy_connected() != 0)
 		}
 
 		#[cfg(not(feature = "enabled"))]
@@ -527,15 +1473,92 @@ impl TracyCapture {
 #[cfg(feature = "enabled")]
 impl Drop for TracyCapture {
 	fn drop(&mut self) {
+		if MUTED.load(Ordering::Relaxed) & MUTED_FORKED != 0 {
+			// Nothing besides the current thread exists in the
+			// forked child, so there is nothing to stop or join.
+			integration::abandon();
+			for b in self.background.drain(..) {
+				std::mem::forget(b);
+			}
+			session::end();
+			return;
+		}
+		#[cfg(not(feature = "no-dynamic-strings"))]
+		end_segment();
+		integration::stop();
+		plot_batch::flush();
+		plot_batch::set_window(None);
+		frame_plot::set_config(None);
+		// Threads exiting from now on must not report it into the
+		// stopped profiler.
+		thread::LIFECYCLE_MESSAGES.swap(false, Ordering::AcqRel);
+		// Background threads are using Tracy, so they must be gone
+		// before the shutdown.
+		for b in self.background.drain(..) {
+			b.stop();
+		}
+		// Other threads could still be emitting, so they see the
+		// instrumentation as a no-op from now on, e.g. zones begun
+		// before are not ended after the shutdown. Emissions already
+		// in progress are waited for.
+		MUTED.fetch_or(MUTED_STOPPED, Ordering::SeqCst);
+		emission::wait_drained();
 		// SAFETY: self could exist only if startup was issued and
 		// succeeded.
 		unsafe {
 			sys::___tracy_shutdown_profiler();
 		}
+		#[cfg(all(windows, feature = "etw"))]
+		etw::unregister();
+		session::end();
 		STARTED.store(false, Ordering::Release);
 	}
 }
 
+/// Waits for the server connection and complains if it doesn't
+/// happen in time.
+#[cfg(feature = "enabled")]
+fn watch_connection(timeout: Duration, handler: Option<fn(&str)>, stop: &background::Stop) {
+	const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+	let deadline = Instant::now() + timeout;
+	loop {
+		// SAFETY: Capture is alive until we are stopped.
+		if unsafe { sys::___tracy_connected() } != 0 {
+			return;
+		}
+
+		let now = Instant::now();
+		if now >= deadline {
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			{
+				let text = format!(
+					"No Tracy server has connected within {timeout:?} after the capture start. \
+					 Make sure the profiler is running and is connecting to this machine."
+				);
+				diagnostic(&text);
+				if let Some(handler) = handler {
+					handler(&text);
+				}
+			}
+			#[cfg(feature = "no-dynamic-strings")]
+			{
+				const TEXT: &str = "No Tracy server has connected in time after the capture start. \
+				                    Make sure the profiler is running and is connecting to this machine.";
+				diagnostic(TEXT);
+				if let Some(handler) = handler {
+					handler(TEXT);
+				}
+			}
+			return;
+		}
+
+		if stop.wait(POLL_INTERVAL.min(deadline - now)) {
+			return;
+		}
+	}
+}
+
 #[doc(hidden)]
 #[macro_export]
 #[cfg(feature = "unstable-function-names")]
@@ -556,6 +1579,91 @@ macro_rules! create_function_name_for_zone {
 	};
 }
 
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "shared-zone-locations"))]
+macro_rules! zone_location_storage {
+	($LOC: ident = $location:expr) => {
+		static $LOC: $crate::ZoneLocation = $location;
+	};
+}
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "shared-zone-locations")]
+macro_rules! zone_location_storage {
+	($LOC: ident = $location:expr) => {
+		// References to a constant are promoted to anonymous statics,
+		// which have no address identity of their own, so identical
+		// locations could be merged into one. It is up to the
+		// compiler, so nothing relies on it.
+		const $LOC: $crate::ZoneLocation = $location;
+	};
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(feature = "function-names", not(feature = "unstable-function-names")))]
+macro_rules! function_named_location {
+	($LOC: ident) => {{
+		// Only the path of a nested type is known on stable.
+		struct __TracyGizmosFunction;
+		static NAMED: ::std::sync::OnceLock<&'static $crate::ZoneLocation> = ::std::sync::OnceLock::new();
+		*NAMED.get_or_init(|| {
+			$crate::details::with_function_name(&$LOC, ::std::any::type_name::<__TracyGizmosFunction>())
+		})
+	}};
+}
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(all(feature = "function-names", not(feature = "unstable-function-names"))))]
+macro_rules! function_named_location {
+	($LOC: ident) => {
+		&$LOC
+	};
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(feature = "function-names", not(feature = "unstable-function-names")))]
+macro_rules! function_name_for_dynamic_zone {
+	() => {{
+		struct __TracyGizmosFunction;
+		$crate::details::function_name(::std::any::type_name::<__TracyGizmosFunction>()).as_bytes()
+	}};
+}
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(all(feature = "function-names", not(feature = "unstable-function-names"))))]
+macro_rules! function_name_for_dynamic_zone {
+	() => {{
+		$crate::create_function_name_for_zone!(FUNCTION);
+		FUNCTION
+	}};
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! zone_min_duration {
+	($min:literal) => {{
+		const MIN: ::core::time::Duration = $crate::limits::parse_duration(stringify!($min));
+		MIN
+	}};
+	($min:expr) => { $min };
+}
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! zone_min_duration {
+	($min:literal) => {
+		const _: ::core::time::Duration = $crate::limits::parse_duration(stringify!($min));
+	};
+	($min:expr) => {
+		// Silences unused expression warning.
+		_ = $min;
+	};
+}
+
 /// Instruments the current scope with a profiling zone.
 ///
 /// A zone represents the lifetime of a special on-stack profiler
@@ -584,15 +1692,28 @@ macro_rules! create_function_name_for_zone {
 ///
 /// Optionally, a custom [`Color`] could be assigned for to zone.
 /// Note, that the color value will be constant in the recording.
+/// It could be a constant [`Color`] expression or a `0xRRGGBB`
+/// integer literal.
 ///
 /// ```no_run
 /// # use tracy_gizmos::*;
 /// fn do_stuff() {
 ///     zone!("stuff", Color::BISQUE);
+///     zone!("more stuff", 0xFF8000);
 ///     // now actually do stuff :-)
 /// }
 /// ```
 ///
+/// A color, which is known only at run-time, e.g. computed from an
+/// entity type or a severity, is applied right after the zone begins
+/// instead, which costs an extra event.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn severity_color() -> Color { Color::RED }
+/// zone!("validate", color: severity_color());
+/// ```
+///
 /// ## Nesting
 ///
 /// Multiple active zones can exist and they will be nested in
@@ -647,9 +1768,249 @@ macro_rules! create_function_name_for_zone {
 /// zone!(parsing, "Parsing");
 /// parsing.text(file_path);
 /// ```
+///
+/// Labelled fields could be attached right away, which keeps zone
+/// payloads self-describing. Every `key = value` pair is attached via
+/// [`Zone::field`], so values have to implement [`Display`].
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # let (path, size) = ("./main.rs", 1024);
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
+/// zone!("load", { path = path, size = size });
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
+/// zone!(loading, "load", Color::TEAL, { path = path });
+/// ```
+///
+/// Fields are not available with `no-dynamic-strings` feature.
+///
+/// ## Callstacks
+///
+/// A zone could also record the callstack of the given depth where
+/// it was entered from. The depth is a constant up to 62, which is
+/// the Tracy limit. Collecting the callstack is quite expensive, so
+/// use it sparingly.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// fn alloc_chunk() {
+///     # #[cfg(not(feature = "no-callstack"))]
+///     zone!("alloc chunk", callstack: 8);
+///     // now actually allocate :-)
+/// }
+/// ```
+///
+/// It is a compilation error with `no-callstack` feature.
+///
+/// The callstack could be recorded at the zone end instead, to find
+/// out who returned early out of the scope. It is recorded via a
+/// `<name> end` message right before the zone ends. Enable
+/// `zone-end-callstack` feature to record it for every zone, refer to
+/// [`set_zone_end_callstack_depth`] for its depth.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn validate() -> Result<(), ()> { Ok(()) }
+/// fn load_config() -> Result<(), ()> {
+///     # #[cfg(not(feature = "no-callstack"))]
+///     zone!("load config", end_callstack: 8);
+///     validate()?;
+///     Ok(())
+/// }
+/// ```
+///
+/// Zones could also be toggled at run-time by their verbosity
+/// [`Level`], refer to [`set_zone_level`].
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// zone!("cull object", level: Level::Verbose);
+/// ```
+///
+/// Very hot zones, which last less than a microsecond, could be
+/// aggregated instead of being emitted, refer to [`AggregatedZone`].
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// zone!("hash lookup", aggregate);
+/// ```
+///
+/// Zones in tight loops could be rate limited instead, so at most
+/// `max_hz` of them are emitted per second. Every emitted zone gets
+/// the amount of zones suppressed since the previous one attached as
+/// a text (a number with `no-dynamic-strings` feature).
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// zone!("poll socket", max_hz: 60);
+/// ```
+///
+/// Zone names could also be shared `&'static str` constants, which
+/// are marked with `const`, unless the name is the only argument.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// const LOADING: &str = "loading";
+///
+/// zone!(LOADING);
+/// zone!(loading, const LOADING, Color::GREEN);
+/// ```
+///
+/// ## Source location
+///
+/// Zones are attributed to the place of [`zone!`] invocation. Code
+/// produced by macros, template engines, or scripting layers could
+/// point them at the original source instead. Such source location
+/// must be known at compile-time, refer to [`Zone::begin_at`] for
+/// the run-time one.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// zone!("update player", file: "player.lua", line: 42, function: "update");
+/// ```
+///
+/// ## Categories
+///
+/// A zone could belong to a [`category`], e.g. rendering or I/O.
+/// Zones of categories, which are not enabled via
+/// `TRACY_GIZMOS_CATEGORIES` environment variable during the build,
+/// are statically removed, so a build with only some of the
+/// instrumentation doesn't require touching every call site.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// zone!("draw", category: RENDER);
+/// zone!(sync, "sync saves", Color::ORANGE, category: IO | NET);
+/// ```
+///
+/// ## Lazy zones
+///
+/// Hot dispatch code could produce lots of uninteresting zones,
+/// which clutter the timeline. A `lazy` zone is emitted only if
+/// something happens inside of it: a child zone begins or a message
+/// is logged on the same thread. It is emitted with its original
+/// start time, so the timeline stays correct. Optionally, a minimum
+/// duration can be given, so the zone is also emitted if it took
+/// long enough by itself.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::time::Duration;
+/// # fn handle(event: u32) {}
+/// # let event = 42;
+/// zone!(lazy "dispatch");
+/// zone!(lazy "dispatch", Color::ORANGE, min: Duration::from_micros(50));
+/// handle(event);
+/// ```
+///
+/// Lazy zones carry no dynamic data and add a small run-time cost to
+/// every zone and message while they are active.
+///
+/// ## Minimum duration
+///
+/// Millions of tiny zones could bloat the capture without giving any
+/// insight. A zone with a minimum duration is skipped entirely, if
+/// it took less time than the threshold. The threshold is either a
+/// literal with a unit (`ns`, `us`, `ms` or `s`) or a [`Duration`]
+/// expression.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::time::Duration;
+/// # fn op() {}
+/// zone!("tiny op", min: 5us);
+/// zone!("tiny op", Color::GREEN, min: Duration::from_micros(5));
+/// op();
+/// ```
+///
+/// Such zones are lazy ones under the hood, so they are still
+/// emitted when something happens inside of them.
+///
+/// ## Timed zones
+///
+/// A named zone could also measure its own duration, so it could be
+/// ended early & the measured time reused, refer to [`TimedZone`].
+/// Plain zones don't query the clock, so the timing is opt-in.
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn simulate() {}
+/// zone!(simulation, "simulate", timed);
+/// zone!(physics, "physics", Color::BLUE, timed);
+/// simulate();
+/// let spent = simulation.end();
+/// # _ = (spent, physics);
+/// ```
+///
+/// [`Duration`]: std::time::Duration
+/// [`Display`]: std::fmt::Display
 #[macro_export]
 #[cfg(any(doc, feature = "enabled"))]
 macro_rules! zone {
+	// Integer color literals are checked & converted first.
+	(lazy        $name:literal, $color:literal $($rest:tt)*) => { $crate::zone!(lazy        $name, $crate::limits::check_color_literal($color) $($rest)*) };
+	(            $name:literal, $color:literal $($rest:tt)*) => { $crate::zone!(            $name, $crate::limits::check_color_literal($color) $($rest)*) };
+	($var:ident, $name:literal, $color:literal $($rest:tt)*) => { $crate::zone!($var,       $name, $crate::limits::check_color_literal($color) $($rest)*) };
+
+	// Fields go first, as `{ .. }` is not a valid color expression.
+	(            $name:literal,              { $($key:ident = $value:expr),* $(,)? }) => { $crate::zone!(_z,   $name, $crate::Color::UNSPECIFIED, { $($key = $value),* }) };
+	($var:ident, $name:literal,              { $($key:ident = $value:expr),* $(,)? }) => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, { $($key = $value),* }) };
+	(            $name:literal, $color:expr, { $($key:ident = $value:expr),* $(,)? }) => { $crate::zone!(_z,   $name, $color,                     { $($key = $value),* }) };
+	($var:ident, $name:literal, $color:expr, { $($key:ident = $value:expr),* $(,)? }) => {
+		$crate::zone!($var, $name, $color);
+		$crate::dynamic_string! {
+			$($var.field(stringify!($key), &$value);)*
+		}
+	};
+
+	($name:literal, aggregate) => {
+		const _: () = $crate::limits::check_name_length($name);
+		// SAFETY: We null-terminate the strings.
+		static SITE: $crate::details::AggregateSite = unsafe {
+			$crate::details::AggregateSite::new(
+				$name,
+				::std::ffi::CStr::from_bytes_with_nul_unchecked(concat!($name, ": count\0").as_bytes()),
+				::std::ffi::CStr::from_bytes_with_nul_unchecked(concat!($name, ": total, ns\0").as_bytes()),
+				::std::ffi::CStr::from_bytes_with_nul_unchecked(concat!($name, ": min, ns\0").as_bytes()),
+				::std::ffi::CStr::from_bytes_with_nul_unchecked(concat!($name, ": max, ns\0").as_bytes()),
+			)
+		};
+		let _z = $crate::details::aggregated_zone(&SITE);
+	};
+
+	// Constant names are null-terminated at compile-time.
+	($name:ident)                               => { $crate::zone!(_z,   const $name, $crate::Color::UNSPECIFIED) };
+	(            const $name:expr)              => { $crate::zone!(_z,   const $name, $crate::Color::UNSPECIFIED) };
+	($var:ident, const $name:expr)              => { $crate::zone!($var, const $name, $crate::Color::UNSPECIFIED) };
+	(            const $name:expr, $color:expr) => { $crate::zone!(_z,   const $name, $color) };
+	($var:ident, const $name:expr, $color:expr) => {
+		let location = $crate::zone!(@loc const $name, $color);
+		#[allow(unused_variables)]
+		// SAFETY: This macro ensures that location & context data are correct.
+		let $var = unsafe { $crate::details::zone(location, 1) };
+	};
+
+	(lazy $name:literal,              min:$min:expr) => { $crate::zone!(@lazy $name, $crate::Color::UNSPECIFIED, ::core::option::Option::Some($crate::zone_min_duration!($min))) };
+	(lazy $name:literal)                             => { $crate::zone!(@lazy $name, $crate::Color::UNSPECIFIED, ::core::option::Option::None)       };
+	(lazy $name:literal, $color:expr, min:$min:expr) => { $crate::zone!(@lazy $name, $color,                     ::core::option::Option::Some($crate::zone_min_duration!($min))) };
+	(lazy $name:literal, $color:expr)                => { $crate::zone!(@lazy $name, $color,                     ::core::option::Option::None)       };
+	(@lazy $name:literal, $color:expr, $min:expr) => {
+		let location = $crate::zone!(@loc $name, $color);
+		let min: ::core::option::Option<::core::time::Duration> = $min;
+		// SAFETY: This macro ensures that location data is correct.
+		let _z = unsafe { $crate::details::lazy_zone(location, min) };
+	};
+
+	// Goes before the colored forms, as `timed` is a valid color
+	// expression.
+	($var:ident, $name:literal,              timed) => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, timed) };
+	($var:ident, $name:literal, $color:expr, timed) => {
+		$crate::zone!($var, $name, $color);
+		let $var = $crate::TimedZone::new($var);
+	};
+
+	(            $name:literal,              min:$min:expr)   => { $crate::zone!(lazy $name,          min:$min) };
+	(            $name:literal, $color:expr, min:$min:expr)   => { $crate::zone!(lazy $name, $color,  min:$min) };
 	(            $name:literal)                               => { $crate::zone!(_z,   $name, $crate::Color::UNSPECIFIED, enabled:true) };
 	($var:ident, $name:literal)                               => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, enabled:true) };
 	(            $name:literal, $color:expr)                  => { $crate::zone!(_z,   $name, $color,                     enabled:true) };
@@ -658,147 +2019,1332 @@ macro_rules! zone {
 	($var:ident, $name:literal,              enabled:$e:expr) => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, enabled:$e)   };
 	(            $name:literal, $color:expr, enabled:$e:expr) => { $crate::zone!(_z,   $name, $color,                     enabled:$e)   };
 	($var:ident, $name:literal, $color:expr, enabled:$e:expr) => {
+		let location = $crate::zone!(@loc $name, $color);
+		let enabled  = if $e {1} else {0};
 		#[allow(unused_variables)]
 		// SAFETY: This macro ensures that location & context data are correct.
-		let $var = unsafe {
-			$crate::details::zone($crate::zone!(@loc $name, $color), if $e {1} else {0})
+		let $var = unsafe { $crate::details::zone(location, enabled) };
+	};
+
+	(            $name:literal,                              callstack:$depth:expr) => { $crate::zone!(_z,   $name, $crate::Color::UNSPECIFIED, enabled:true, callstack:$depth) };
+	($var:ident, $name:literal,                              callstack:$depth:expr) => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, enabled:true, callstack:$depth) };
+	(            $name:literal, $color:expr,                 callstack:$depth:expr) => { $crate::zone!(_z,   $name, $color,                     enabled:true, callstack:$depth) };
+	($var:ident, $name:literal, $color:expr,                 callstack:$depth:expr) => { $crate::zone!($var, $name, $color,                     enabled:true, callstack:$depth) };
+	(            $name:literal,              enabled:$e:expr, callstack:$depth:expr) => { $crate::zone!(_z,   $name, $crate::Color::UNSPECIFIED, enabled:$e,   callstack:$depth) };
+	($var:ident, $name:literal,              enabled:$e:expr, callstack:$depth:expr) => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, enabled:$e,   callstack:$depth) };
+	(            $name:literal, $color:expr, enabled:$e:expr, callstack:$depth:expr) => { $crate::zone!(_z,   $name, $color,                     enabled:$e,   callstack:$depth) };
+	($var:ident, $name:literal, $color:expr, enabled:$e:expr, callstack:$depth:expr) => {
+		let location = $crate::zone!(@loc $name, $color);
+		let enabled  = if $e {1} else {0};
+		let depth    = $crate::callstack_depth!($depth);
+		#[allow(unused_variables)]
+		// SAFETY: This macro ensures that location & context data are correct.
+		let $var = unsafe { $crate::details::zone_with_callstack(location, depth, enabled) };
+	};
+
+	(            $name:literal,              end_callstack:$depth:expr) => { $crate::zone!(_z,   $name, $crate::Color::UNSPECIFIED, end_callstack:$depth) };
+	($var:ident, $name:literal,              end_callstack:$depth:expr) => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, end_callstack:$depth) };
+	(            $name:literal, $color:expr, end_callstack:$depth:expr) => { $crate::zone!(_z,   $name, $color,                     end_callstack:$depth) };
+	($var:ident, $name:literal, $color:expr, end_callstack:$depth:expr) => {
+		$crate::zone!($var, $name, $color);
+		// Declared after the zone, so the message is emitted inside of it.
+		let _end = $crate::details::EndCallstack::new(concat!($name, " end\0"), $crate::callstack_depth!($depth));
+	};
+
+	(            $name:literal, color:$color:expr) => { $crate::zone!(_z, $name, color:$color) };
+	($var:ident, $name:literal, color:$color:expr) => {
+		$crate::zone!($var, $name);
+		$var.color($color);
+	};
+
+	(            $name:literal,              level:$level:expr) => { $crate::zone!(_z,   $name, $crate::Color::UNSPECIFIED, level:$level) };
+	($var:ident, $name:literal,              level:$level:expr) => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, level:$level) };
+	(            $name:literal, $color:expr, level:$level:expr) => { $crate::zone!(_z,   $name, $color,                     level:$level) };
+	($var:ident, $name:literal, $color:expr, level:$level:expr) => {
+		$crate::zone!($var, $name, $color, enabled:$crate::details::level_emitted($level));
+	};
+
+	(            $name:literal,              max_hz:$hz:expr) => { $crate::zone!(_z,   $name, $crate::Color::UNSPECIFIED, max_hz:$hz) };
+	($var:ident, $name:literal,              max_hz:$hz:expr) => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, max_hz:$hz) };
+	(            $name:literal, $color:expr, max_hz:$hz:expr) => { $crate::zone!(_z,   $name, $color,                     max_hz:$hz) };
+	($var:ident, $name:literal, $color:expr, max_hz:$hz:expr) => {
+		#[allow(unused_variables)]
+		let $var = {
+			static RATE: $crate::details::RateLimit = $crate::details::RateLimit::new($hz);
+			let admitted = RATE.admit();
+			$crate::zone!(zone, $name, $color, enabled:admitted);
+			if admitted {
+				RATE.report(&zone);
+			}
+			zone
+		};
+	};
+
+	(            $name:literal,              category:$cat:expr) => { $crate::zone!(_z,   $name, $crate::Color::UNSPECIFIED, category:$cat) };
+	($var:ident, $name:literal,              category:$cat:expr) => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, category:$cat) };
+	(            $name:literal, $color:expr, category:$cat:expr) => { $crate::zone!(_z,   $name, $color,                     category:$cat) };
+	($var:ident, $name:literal, $color:expr, category:$cat:expr) => {
+		#[allow(unused_variables)]
+		let $var = {
+			const ENABLED: bool = $crate::category::is_enabled($crate::zone!(@category $cat));
+			if ENABLED {
+				let location = $crate::zone!(@loc $name, $color);
+				// SAFETY: This macro ensures that location & context data are correct.
+				unsafe { $crate::details::zone(location, 1) }
+			} else {
+				$crate::details::removed_zone()
+			}
+		};
+	};
+
+	(            $name:literal,              file:$file:literal, line:$line:expr, function:$function:literal) => { $crate::zone!(_z,   $name, $crate::Color::UNSPECIFIED, file:$file, line:$line, function:$function) };
+	($var:ident, $name:literal,              file:$file:literal, line:$line:expr, function:$function:literal) => { $crate::zone!($var, $name, $crate::Color::UNSPECIFIED, file:$file, line:$line, function:$function) };
+	(            $name:literal, $color:expr, file:$file:literal, line:$line:expr, function:$function:literal) => { $crate::zone!(_z,   $name, $color,                     file:$file, line:$line, function:$function) };
+	($var:ident, $name:literal, $color:expr, file:$file:literal, line:$line:expr, function:$function:literal) => {
+		let location = {
+			const _: () = $crate::limits::check_name_length($name);
+			const NAME:     &str          = concat!($name, '\0');
+			const FILE:     &str          = concat!($file, '\0');
+			const FUNCTION: &[u8]         = concat!($function, '\0').as_bytes();
+			const COLOR:    $crate::Color = $color;
+
+			static LOC: $crate::ZoneLocation = {
+				// Not a named constant, so the line could refer to
+				// any constant.
+				let line: u32 = $line;
+				// SAFETY: All passed data is created here and is correct.
+				unsafe { $crate::details::zone_location(NAME, FUNCTION, FILE, line, COLOR.as_u32()) }
+			};
+			&LOC
 		};
+		#[allow(unused_variables)]
+		// SAFETY: This macro ensures that location & context data are correct.
+		let $var = unsafe { $crate::details::zone(location, 1) };
 	};
 
-	(@loc $name:literal, $color: expr) => {{
+	(@category $cat:expr) => {{
+		// Built-in categories are available without a path.
+		#[allow(unused_imports)]
+		use $crate::category::*;
+		const CATEGORY: u64 = $cat;
+		CATEGORY
+	}};
+
+	(@loc $name:literal, $color:expr) => {{
+		const _: () = $crate::limits::check_name_length($name);
+		$crate::zone!(@loc_nul concat!($name, '\0'), $color)
+	}};
+
+	(@loc const $name:expr, $color:expr) => {{
+		// Named unusually, so it doesn't shadow the passed constant.
+		const __TRACY_GIZMOS_NAME: &str = $name;
+		const _: () = $crate::limits::check_name_length(__TRACY_GIZMOS_NAME);
+		const __TRACY_GIZMOS_NAME_NUL: &[u8] =
+			&$crate::details::null_terminated::<{ __TRACY_GIZMOS_NAME.len() + 1 }>(__TRACY_GIZMOS_NAME);
+		// SAFETY: It is a null-terminated copy of `&str`.
+		$crate::zone!(@loc_nul unsafe { ::core::str::from_utf8_unchecked(__TRACY_GIZMOS_NAME_NUL) }, $color)
+	}};
+
+	(@loc_nul $name:expr, $color:expr) => {{
 		// This is an implementation detail and can be changed at any moment.
 		$crate::create_function_name_for_zone!(FUNCTION);
+		const NAME:  &str = $name;
+		const FILE:  &str = concat!(file!(), '\0');
+		const LINE:  u32  = line!();
+		// Makes a non-`Color` or non-constant expression fail right
+		// at the call site.
+		const COLOR: $crate::Color = $color;
 
-		// SAFETY: All passed data is created here and is correct.
-		static LOC: $crate::ZoneLocation = unsafe {
+		$crate::zone_location_storage!(LOC = unsafe {
+			// SAFETY: All passed data is created here and is correct.
 			$crate::details::zone_location(
-				concat!($name, '\0'),
+				NAME,
 				FUNCTION,
-				concat!(file!(), '\0'),
-				line!(),
-				$crate::Color::as_u32(&$color),
+				FILE,
+				LINE,
+				COLOR.as_u32(),
 			)
-		};
-		&LOC
+		});
+		$crate::function_named_location!(LOC)
 	}};
 }
 
 #[macro_export]
 #[cfg(all(not(doc), not(feature = "enabled")))]
 macro_rules! zone {
-	($($var:ident,)? $name:literal, enabled:$e:expr) => {
-		// Silences unused enabled expression warning.
-		_ = $e;
-		$crate::zone!($($var,)? $name, (), enabled:$e);
+	($($var:ident,)? $name:literal,              { $($key:ident = $value:expr),* $(,)? }) => {
+		$crate::zone!($($var,)? $name);
+		// Silences unused expression warnings.
+		$(_ = $value;)*
+	};
+	($($var:ident,)? $name:literal, $color:expr, { $($key:ident = $value:expr),* $(,)? }) => {
+		$crate::zone!($($var,)? $name, $color);
+		// Silences unused expression warnings.
+		$(_ = $value;)*
 	};
 
-	($($var:ident,)? $name:literal $(,$color:expr)? $(,enabled:$e:expr)?) => {
-		// $var could be used to add dynamic zone data, so we need to
-		// define it to keep the macro-using code compilable.
-		$(
-			#[allow(unused_variables)]
-			let $var = $crate::Zone::new();
-		)?
+	($name:literal, aggregate) => {};
+
+	($name:ident) => {
+		const _: &str = $name;
+	};
+	(const $name:expr $(, $color:expr)?) => {
+		const _: &str = $name;
 		// Silences unused `Color` import warning.
-		$(
-			_ = $color;
-		)?
+		$(_ = $color;)?
+	};
+	($var:ident, const $name:expr $(, $color:expr)?) => {
+		const _: &str = $name;
+		#[allow(unused_variables)]
+		let $var = $crate::Zone::new();
+		// Silences unused `Color` import warning.
+		$(_ = $color;)?
 	};
-}
 
-/// Profiling zone.
-///
-/// Refer to [`zone!`] for the usage how-to.
-///
-/// It instruments the current scope. Hence, the profiling zone will
-/// end when [`Zone`] is dropped.
-pub struct Zone {
+	($($var:ident,)? $name:literal,              file:$file:literal, line:$line:expr, function:$function:literal) => {
+		$crate::zone_compiled_out!($($var,)? $name, file:$file, line:$line, function:$function);
+	};
+	($($var:ident,)? $name:literal, $color:expr, file:$file:literal, line:$line:expr, function:$function:literal) => {
+		$crate::zone_compiled_out!($($var,)? $name, $color, file:$file, line:$line, function:$function);
+	};
+	($var:ident, $name:literal,              timed) => {
+		$crate::zone_compiled_out!($var, $name, timed);
+	};
+	($var:ident, $name:literal, $color:expr, timed) => {
+		$crate::zone_compiled_out!($var, $name, $color, timed);
+	};
+	($($var:ident,)? $name:literal, color:$color:expr) => {
+		$crate::zone_compiled_out!($($var,)? $name, color:$color);
+	};
+	($($var:ident,)? $name:literal,              level:$level:expr) => {
+		$crate::zone_compiled_out!($($var,)? $name, level:$level);
+	};
+	($($var:ident,)? $name:literal, $color:expr, level:$level:expr) => {
+		$crate::zone_compiled_out!($($var,)? $name, $color, level:$level);
+	};
+	($($var:ident,)? $name:literal,              max_hz:$hz:expr) => {
+		$crate::zone_compiled_out!($($var,)? $name, max_hz:$hz);
+	};
+	($($var:ident,)? $name:literal, $color:expr, max_hz:$hz:expr) => {
+		$crate::zone_compiled_out!($($var,)? $name, $color, max_hz:$hz);
+	};
+	($($var:ident,)? $name:literal,              category:$cat:expr) => {
+		$crate::zone_compiled_out!($($var,)? $name, category:$cat);
+	};
+	($($var:ident,)? $name:literal, $color:expr, category:$cat:expr) => {
+		$crate::zone_compiled_out!($($var,)? $name, $color, category:$cat);
+	};
+
+	($(lazy)? $name:literal, min:$min:expr) => {
+		$crate::zone_min_duration!($min);
+	};
+	($(lazy)? $name:literal, $color:expr, min:$min:expr) => {
+		$crate::zone_min_duration!($min);
+		$crate::zone!(lazy $name, $color);
+	};
+	(lazy $name:literal $(,$color:expr)?) => {
+		// Silences unused `Color` import warning.
+		$(_ = $color;)?
+	};
+
+	($($var:ident,)? $name:literal,                              callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone!($($var,)? $name);
+	};
+	($($var:ident,)? $name:literal, $color:expr,                 callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone!($($var,)? $name, $color);
+	};
+	($($var:ident,)? $name:literal,              enabled:$e:expr, callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone!($($var,)? $name, enabled:$e);
+	};
+	($($var:ident,)? $name:literal, $color:expr, enabled:$e:expr, callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone!($($var,)? $name, $color, enabled:$e);
+	};
+	($($var:ident,)? $name:literal,              end_callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone!($($var,)? $name);
+	};
+	($($var:ident,)? $name:literal, $color:expr, end_callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone!($($var,)? $name, $color);
+	};
+
+	($($var:ident,)? $name:literal, enabled:$e:expr) => {
+		// Silences unused enabled expression warning.
+		_ = $e;
+		$crate::zone!($($var,)? $name, (), enabled:$e);
+	};
+
+	($($var:ident,)? $name:literal $(,$color:expr)? $(,enabled:$e:expr)?) => {
+		// $var could be used to add dynamic zone data, so we need to
+		// define it to keep the macro-using code compilable.
+		$(
+			#[allow(unused_variables)]
+			let $var = $crate::Zone::new();
+		)?
+		// Silences unused `Color` import warning.
+		$(
+			_ = $color;
+		)?
+	};
+}
+
+/// Instruments the current scope with a profiling zone, which also
+/// warns when it takes longer than the threshold.
+///
+/// A yellow message with the zone name and its duration is emitted
+/// right before the zone ends, if it exceeded the threshold. The
+/// threshold is either a literal with a unit (`ns`, `us`, `ms` or
+/// `s`) or a [`Duration`] expression.
+///
+/// With `no-dynamic-strings` feature only the zone name is reported.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn save_game() {}
+/// zone_warn_slow!("save game", 50ms);
+/// save_game();
+/// ```
+///
+/// Same as with [`zone!`], the zone could be named to attach dynamic
+/// data:
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::time::Duration;
+/// # let path = "save.dat";
+/// zone_warn_slow!(save, "save game", Duration::from_millis(50));
+/// save.text(path);
+/// ```
+///
+/// [`Duration`]: std::time::Duration
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! zone_warn_slow {
+	($name:literal, $threshold:expr) => { $crate::zone_warn_slow!(_z, $name, $threshold) };
+	($var:ident, $name:literal, $threshold:expr) => {
+		$crate::zone!($var, $name);
+		let threshold: ::core::time::Duration = $crate::zone_min_duration!($threshold);
+		// Declared after the zone, so the warning is emitted inside of it.
+		let _slow = $crate::details::SlowWarning::new(concat!($name, '\0'), threshold);
+	};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! zone_warn_slow {
+	($($var:ident,)? $name:literal, $threshold:expr) => {
+		$crate::zone!($($var,)? $name);
+		$crate::zone_min_duration!($threshold);
+	};
+}
+
+/// Instruments the current scope with a profiling zone, which name
+/// is known only at run-time.
+///
+/// [`zone!`] accepts only literal names, while this zone could be
+/// named after an asset path, a job name, etc. The name is copied
+/// into the source location allocated by Tracy, so nothing is
+/// leaked, but it is a bit more expensive than the regular
+/// [`zone!`]. Refer to [`Zone::begin_dynamic`] for the function
+/// form.
+///
+/// It is a compilation error with `no-dynamic-strings` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn load(path: &str) {}
+/// # let assets = ["textures/grass.png"];
+/// for path in assets {
+///     # #[cfg(not(feature = "no-dynamic-strings"))]
+///     zone_dynamic!(path);
+///     load(path);
+/// }
+/// ```
+///
+/// Same as with [`zone!`], the zone could be named to attach dynamic
+/// data:
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # let (job, level) = ("decompress", "level 9");
+/// # #[cfg(not(feature = "no-dynamic-strings"))]
+/// # {
+/// zone_dynamic!(zone, job);
+/// zone.text(level);
+/// # }
+/// ```
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! zone_dynamic {
+	($var:ident, $name:expr) => {$crate::dynamic_string! {
+		#[allow(unused_variables)]
+		let $var = $crate::details::dynamic_zone(
+			::core::convert::AsRef::<str>::as_ref(&$name),
+			$crate::function_name_for_dynamic_zone!(),
+		);
+	}};
+	($name:expr) => {
+		$crate::zone_dynamic!(_z, $name);
+	};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! zone_dynamic {
+	($var:ident, $name:expr) => {
+		// Silences unused expression warning.
+		_ = &$name;
+		#[allow(unused_variables)]
+		let $var = $crate::Zone::new();
+	};
+	($name:expr) => {
+		// Silences unused expression warning.
+		_ = &$name;
+	};
+}
+
+/// Instruments the current scope with a profiling zone, which name
+/// is a static C string, e.g. a constant supplied by C code.
+///
+/// Names coming from C are already static & null-terminated, so they
+/// are used as is, without any conversion through `&str`. Refer to
+/// [`Zone::begin_from_cstr`] for the function form.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::ffi::{CStr, c_char};
+/// # unsafe extern "C" fn job_name(_job: u32) -> *const c_char { c"job".as_ptr() }
+/// # let job = 0;
+/// // SAFETY: Job names are static constants on the C side.
+/// let name: &'static CStr = unsafe { CStr::from_ptr(job_name(job)) };
+/// zone_cstr!(zone, name);
+/// zone.color(Color::ORANGE);
+/// ```
+#[macro_export]
+macro_rules! zone_cstr {
+	($var:ident, $name:expr) => {
+		#[allow(unused_variables)]
+		let $var = $crate::Zone::begin_from_cstr($name);
+	};
+	($name:expr) => {
+		$crate::zone_cstr!(_z, $name);
+	};
+}
+
+/// Instruments the current scope with a level 2 profiling zone.
+///
+/// Works exactly as [`zone!`], but is compiled out unless
+/// `instr-level-2` (or higher) feature is enabled. It allows to keep
+/// detail-heavy zones in the code, while paying for them only in
+/// deep-dive builds.
+///
+/// A named zone is still declared when compiled out, so the code
+/// attaching dynamic data keeps compiling, but it is disabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn cull(object: u32) {}
+/// # let objects = [1, 2, 3];
+/// zone!("culling");
+/// for object in objects {
+///     zone_l2!(culling, "cull object");
+///     culling.number(object as u64);
+///     cull(object);
+/// }
+/// ```
+#[macro_export]
+#[cfg(any(doc, all(feature = "enabled", feature = "instr-level-2")))]
+macro_rules! zone_l2 {
+	($($args:tt)*) => { $crate::zone!($($args)*) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(not(doc), not(all(feature = "enabled", feature = "instr-level-2"))))]
+macro_rules! zone_l2 {
+	($($args:tt)*) => { $crate::zone_compiled_out!($($args)*) };
+}
+
+/// Instruments the current scope with a level 3 profiling zone.
+///
+/// Works exactly as [`zone!`], but is compiled out unless
+/// `instr-level-3` feature is enabled. Refer to [`zone_l2!`] for the
+/// details.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn visit(node: u32) {}
+/// # let nodes = [1, 2, 3];
+/// for node in nodes {
+///     zone_l3!("visit node");
+///     visit(node);
+/// }
+/// ```
+#[macro_export]
+#[cfg(any(doc, all(feature = "enabled", feature = "instr-level-3")))]
+macro_rules! zone_l3 {
+	($($args:tt)*) => { $crate::zone!($($args)*) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(not(doc), not(all(feature = "enabled", feature = "instr-level-3"))))]
+macro_rules! zone_l3 {
+	($($args:tt)*) => { $crate::zone_compiled_out!($($args)*) };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! zone_compiled_out {
+	(lazy $($args:tt)*) => { $crate::zone_compiled_out!($($args)*) };
+
+	($($var:ident,)? $name:literal,              { $($key:ident = $value:expr),* $(,)? }) => {
+		$crate::zone_compiled_out!($($var,)? $name);
+		$(_ = $value;)*
+	};
+	($($var:ident,)? $name:literal, $color:expr, { $($key:ident = $value:expr),* $(,)? }) => {
+		$crate::zone_compiled_out!($($var,)? $name, $color);
+		$(_ = $value;)*
+	};
+
+	($name:literal, aggregate) => {};
+
+	($name:ident) => {
+		const _: &str = $name;
+	};
+	(const $name:expr $(, $color:expr)?) => {
+		const _: &str = $name;
+		// Silences unused `Color` import warning.
+		$(_ = $color;)?
+	};
+	($var:ident, const $name:expr $(, $color:expr)?) => {
+		const _: &str = $name;
+		#[allow(unused_variables)]
+		let $var = $crate::Zone::new();
+		// Silences unused `Color` import warning.
+		$(_ = $color;)?
+	};
+
+	($($var:ident,)? $name:literal, color:$color:expr) => {
+		// Silences unused `Color` import warning.
+		_ = $color;
+		$crate::zone_compiled_out!($($var,)? $name);
+	};
+	($($var:ident,)? $name:literal,              level:$level:expr) => {
+		// Silences unused expression warning.
+		_ = $level;
+		$crate::zone_compiled_out!($($var,)? $name);
+	};
+	($($var:ident,)? $name:literal, $color:expr, level:$level:expr) => {
+		// Silences unused expression warning.
+		_ = $level;
+		$crate::zone_compiled_out!($($var,)? $name, $color);
+	};
+
+	($($var:ident,)? $name:literal,              max_hz:$hz:expr) => {
+		const _: u32 = $hz;
+		$crate::zone_compiled_out!($($var,)? $name);
+	};
+	($($var:ident,)? $name:literal, $color:expr, max_hz:$hz:expr) => {
+		const _: u32 = $hz;
+		$crate::zone_compiled_out!($($var,)? $name, $color);
+	};
+
+	// Source locations are only checked.
+	($($var:ident,)? $name:literal,              file:$file:literal, line:$line:expr, function:$function:literal) => {
+		const _: u32 = $line;
+		$crate::zone_compiled_out!($($var,)? $name);
+	};
+	($($var:ident,)? $name:literal, $color:expr, file:$file:literal, line:$line:expr, function:$function:literal) => {
+		const _: u32 = $line;
+		$crate::zone_compiled_out!($($var,)? $name, $color);
+	};
+
+	// Categories are only checked.
+	($($var:ident,)? $name:literal,              category:$cat:expr) => {
+		const _: u64 = {
+			#[allow(unused_imports)]
+			use $crate::category::*;
+			$cat
+		};
+		$crate::zone_compiled_out!($($var,)? $name);
+	};
+	($($var:ident,)? $name:literal, $color:expr, category:$cat:expr) => {
+		const _: u64 = {
+			#[allow(unused_imports)]
+			use $crate::category::*;
+			$cat
+		};
+		$crate::zone_compiled_out!($($var,)? $name, $color);
+	};
+
+	// Callstack depth is only checked.
+	($($var:ident,)? $name:literal,                              callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone_compiled_out!($($var,)? $name);
+	};
+	($($var:ident,)? $name:literal, $color:expr,                 callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone_compiled_out!($($var,)? $name, $color);
+	};
+	($($var:ident,)? $name:literal,              enabled:$e:expr, callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone_compiled_out!($($var,)? $name, enabled:$e);
+	};
+	($($var:ident,)? $name:literal, $color:expr, enabled:$e:expr, callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone_compiled_out!($($var,)? $name, $color, enabled:$e);
+	};
+	($($var:ident,)? $name:literal,              end_callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone_compiled_out!($($var,)? $name);
+	};
+	($($var:ident,)? $name:literal, $color:expr, end_callstack:$depth:expr) => {
+		$crate::callstack_depth!($depth);
+		$crate::zone_compiled_out!($($var,)? $name, $color);
+	};
+
+	($name:literal,              min:$min:literal) => {
+		const _: ::core::time::Duration = $crate::limits::parse_duration(stringify!($min));
+	};
+	($name:literal,              min:$min:expr)    => { _ = $min; };
+	($name:literal, $color:expr, min:$min:literal) => {
+		_ = $color;
+		$crate::zone_compiled_out!($name, min:$min);
+	};
+	($name:literal, $color:expr, min:$min:expr)    => {
+		_ = $color;
+		_ = $min;
+	};
+
+	($var:ident, $name:literal,              timed) => {
+		$crate::zone_compiled_out!($var, $name);
+		let $var = $crate::TimedZone::new($var);
+	};
+	($var:ident, $name:literal, $color:expr, timed) => {
+		$crate::zone_compiled_out!($var, $name, $color);
+		let $var = $crate::TimedZone::new($var);
+	};
+
+	// Named zones are kept, but disabled, as they could be used to
+	// add dynamic zone data.
+	($var:ident, $name:literal,              enabled:$e:expr) => {
+		_ = $e;
+		$crate::zone!($var, $name, enabled:false);
+	};
+	($var:ident, $name:literal $(,$color:expr)?) => {
+		$crate::zone!($var, $name $(,$color)?, enabled:false);
+	};
+	($var:ident, $name:literal, $color:expr, enabled:$e:expr) => {
+		_ = $e;
+		$crate::zone!($var, $name, $color, enabled:false);
+	};
+
+	($name:literal, enabled:$e:expr) => {
+		// Silences unused expression warning.
+		_ = $e;
+	};
+	($name:literal $(,$color:expr)? $(,enabled:$e:expr)?) => {
+		// Silences unused `Color` import & expression warnings.
+		$(_ = $color;)?
+		$(_ = $e;)?
+	};
+}
+
+/// Profiling zone.
+///
+/// Refer to [`zone!`] for the usage how-to.
+///
+/// It instruments the current scope. Hence, the profiling zone will
+/// end when [`Zone`] is dropped.
+pub struct Zone {
 	#[cfg(feature = "enabled")]
 	ctx:     sys::TracyCZoneCtx,
 	_unsend: PhantomData<*mut ()>,
 }
 
-#[cfg(any(doc, feature = "enabled"))]
-impl Drop for Zone {
+#[cfg(any(doc, feature = "enabled"))]
+impl Drop for Zone {
+	#[inline(always)]
+	fn drop(&mut self) {
+		#[cfg(all(test, feature = "enabled"))]
+		if self.ctx.id == record::ZONE_ID && record::push(|| record::Event::ZoneEnd) {
+			return;
+		}
+		#[cfg(feature = "enabled")]
+		// Zones removed at compile-time are not even reported.
+		if self.ctx.active == 0 {
+			return;
+		}
+		#[cfg(all(feature = "enabled", feature = "zone-end-callstack"))]
+		{
+			// Longer names are cut.
+			let mut text = [0; 128];
+			if let Some(text) = zone_path::innermost_end(&mut text) {
+				details::message_size_callstack(text, ZONE_END_CALLSTACK_DEPTH.load(Ordering::Relaxed));
+			}
+		}
+		#[cfg(feature = "enabled")]
+		// SAFETY: The only way to have Zone is to construct it via
+		// zone! macro, which ensures that ctx value is correct.
+		unsafe {
+			zone_path::leave(self.ctx);
+			#[cfg(debug_assertions)]
+			lifo::leave(self.ctx);
+			#[cfg(feature = "zone-hooks")]
+			hooks::leave(self.ctx);
+			let (ctx, _emission) = self.live_ctx();
+			sys::___tracy_emit_zone_end(ctx);
+			self_profile::count_event();
+			#[cfg(all(target_os = "linux", feature = "trace-marker"))]
+			if ctx.active != 0 {
+				trace_marker::zone_end();
+			}
+			#[cfg(feature = "mirror")]
+			if ctx.active != 0 {
+				mirror::zone_end();
+			}
+		}
+	}
+}
+
+impl Zone {
+	#[doc(hidden)]
+	#[cfg(not(feature = "enabled"))]
+	pub fn new() -> Self {
+		Self { _unsend: PhantomData }
+	}
+
+	/// Allows to control the zone color dynamically.
+	///
+	/// This can be called multiple times, however only the latest
+	/// call will have an effect.
+	pub fn color(&self, color: Color) {
+		#[cfg(all(test, feature = "enabled"))]
+		if self.ctx.id == record::ZONE_ID && record::push(|| record::Event::ZoneColor(color.as_u32())) {
+			return;
+		}
+		#[cfg(feature = "enabled")]
+		// SAFETY: self always contains a valid `ctx`.
+		unsafe {
+			let (ctx, _emission) = self.live_ctx();
+			sys::___tracy_emit_zone_color(ctx, color.as_u32());
+			self_profile::count_event();
+		}
+		#[cfg(not(feature = "enabled"))]
+		{
+			// Silences unused import warning.
+			_ = color;
+		}
+	}
+
+	/// Adds a custom numeric value that will be displayed along with
+	/// the zone information. E.g. a loop iteration or size of the
+	/// processed buffer.
+	///
+	/// This method can be called multiple times, all of the passed
+	/// values will be attached to the zone matching the call order.
+	#[cfg(feature = "enabled")]
+	pub fn number(&self, value: u64) {
+		#[cfg(feature = "enabled")]
+		// SAFETY: self always contains a valid `ctx`.
+		unsafe {
+			let (ctx, _emission) = self.live_ctx();
+			sys::___tracy_emit_zone_value(ctx, value);
+			self_profile::count_event();
+		}
+	}
+
+	/// Adds a custom floating-point value that will be displayed along
+	/// with the zone information. E.g. a ratio or a timing.
+	///
+	/// Tracy supports only unsigned zone values natively, so it is
+	/// attached as a text and the same costs as for
+	/// [`Zone::text_fmt`] apply.
+	///
+	/// This method can be called multiple times, all of the passed
+	/// values will be attached to the zone matching the call order.
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub fn value_f64(&self, value: f64) {
+		#[cfg(feature = "enabled")]
+		self.text_fmt(format_args!("{value}"));
+	}
+
+	/// Adds a custom signed value that will be displayed along with
+	/// the zone information. E.g. a delta, which could be negative.
+	///
+	/// Tracy supports only unsigned zone values natively, so it is
+	/// attached as a text and the same costs as for
+	/// [`Zone::text_fmt`] apply.
+	///
+	/// This method can be called multiple times, all of the passed
+	/// values will be attached to the zone matching the call order.
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub fn value_i64(&self, value: i64) {
+		#[cfg(feature = "enabled")]
+		self.text_fmt(format_args!("{value}"));
+	}
+
+	/// Adds a custom text string that will be displayed along with
+	/// the zone information. E.g. name of the file you are
+	/// processing.
+	///
+	/// The profiler will allocate a temporary internal buffer and
+	/// copy the passed value there. Hence, this operation involves a
+	/// small run-time cost.
+	///
+	/// This method can be called multiple times, all of the passed
+	/// values will be attached to the zone matching the call order.
+	///
+	/// Be aware that the passed text slice couldn't be larger than 64
+	/// Kb.
+	pub fn text(&self, s: &str) {
+		#[cfg(feature = "enabled")]
+		{
+			debug_assert!(s.len() < u16::MAX as usize);
+			// SAFETY: self always contains a valid `ctx`.
+			unsafe {
+				let (ctx, _emission) = self.live_ctx();
+				sys::___tracy_emit_zone_text(ctx, s.as_ptr().cast(), s.len());
+			}
+			self_profile::count_text(s.len());
+		}
+	}
+
+	/// Adds a custom text that will be displayed along with the zone
+	/// information, but builds it only if it is going to be seen.
+	///
+	/// The closure is called only if the zone is active and a Tracy
+	/// server is connected, so expensive annotations cost nothing,
+	/// while nobody is capturing. Otherwise, the same costs and
+	/// limitations as for [`Zone::text`] apply.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// # fn describe_scene() -> String { String::new() }
+	/// zone!(culling, "Culling");
+	/// culling.text_lazy(|| describe_scene());
+	/// ```
+	pub fn text_lazy<S: AsRef<str>>(&self, text: impl FnOnce() -> S) {
+		#[cfg(feature = "enabled")]
+		let wanted = {
+			let (ctx, _emission) = self.live_ctx();
+			// SAFETY: An active zone could exist only while Tracy is
+			// running.
+			ctx.active != 0 && unsafe { sys::___tracy_connected() } != 0
+		};
+		#[cfg(feature = "enabled")]
+		if wanted {
+			self.text(text().as_ref());
+		}
+	}
+
+	/// Overrides the zone name displayed in the profiler, e.g. when a
+	/// generic zone finds out what it is actually doing.
+	///
+	/// The profiler will copy the passed name, the same costs and
+	/// limitations as for [`Zone::text`] apply. Only the latest call
+	/// will have an effect.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// # fn job_kind() -> &'static str { "Decompress" }
+	/// zone!(job, "Job");
+	/// job.set_name(job_kind());
+	/// ```
+	pub fn set_name(&self, name: &str) {
+		#[cfg(feature = "enabled")]
+		{
+			debug_assert!(name.len() < u16::MAX as usize);
+			// SAFETY: self always contains a valid `ctx`.
+			unsafe {
+				let (ctx, _emission) = self.live_ctx();
+				sys::___tracy_emit_zone_name(ctx, name.as_ptr().cast(), name.len());
+			}
+			self_profile::count_text(name.len());
+		}
+	}
+
+	/// Adds a custom formatted text that will be displayed along with
+	/// the zone information.
+	///
+	/// Unlike `zone.text(&format!(..))`, the text is formatted into a
+	/// reused thread-local buffer, so hot zones don't allocate on
+	/// every call. The same limitations as for [`Zone::text`] apply.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// # let (chunk, total) = (3, 10);
+	/// zone!(streaming, "Stream chunk");
+	/// streaming.text_fmt(format_args!("{chunk}/{total}"));
+	/// ```
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub fn text_fmt(&self, args: std::fmt::Arguments) {
+		#[cfg(feature = "enabled")]
+		{
+			use std::cell::RefCell;
+			use std::fmt::Write;
+
+			thread_local! {
+				static BUFFER: RefCell<String> = const { RefCell::new(String::new()) };
+			}
+
+			// Static text doesn't need any formatting.
+			if let Some(text) = args.as_str() {
+				self.text(text);
+				return;
+			}
+			BUFFER.with(|buffer| match buffer.try_borrow_mut() {
+				Ok(mut buffer) => {
+					buffer.clear();
+					// Writing into a `String` never fails.
+					_ = buffer.write_fmt(args);
+					self.text(&buffer);
+				},
+				// Formatting itself adds a text to some zone.
+				Err(_) => self.text(&args.to_string()),
+			});
+		}
+	}
+
+	/// Adds a custom `key=value` text entry that will be displayed
+	/// along with the zone information.
+	///
+	/// Both key and value are escaped, so every entry is a single
+	/// line and can be reliably parsed back from the exported
+	/// captures via [`parse_kv`], which also describes the escaping
+	/// convention.
+	///
+	/// The same costs and limitations as for [`Zone::text`] apply.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// # let (path, size) = ("./main.rs", 1024);
+	/// zone!(parsing, "Parsing");
+	/// parsing.kv("path", path);
+	/// parsing.kv("size", size);
+	/// ```
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub fn kv(&self, key: &str, value: impl std::fmt::Display) {
+		self.field(key, value);
+	}
+
+	/// Adds a labelled field that will be displayed along with the
+	/// zone information, so zone payloads stay self-describing even
+	/// when dozens of values are attached.
+	///
+	/// The field is attached as a `key=value` text entry, the same way
+	/// as [`Zone::kv`] does, but it is formatted into the reused
+	/// buffer, the same way as [`Zone::text_fmt`] does. Fields could
+	/// also be attached right in [`zone!`].
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// # let (path, size) = ("./main.rs", 1024);
+	/// zone!(loading, "load");
+	/// loading.field("path", path);
+	/// loading.field("size", size);
+	/// ```
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub fn field(&self, key: &str, value: impl std::fmt::Display) {
+		#[cfg(feature = "enabled")]
+		self.text_fmt(format_args!("{}", kv::Field(key, &value)));
+	}
+
+	/// Adds a bounded hex dump of binary data as a custom text, that
+	/// will be displayed along with the zone information.
+	///
+	/// At most `max_len` bytes are dumped, refer to [`message_hex!`]
+	/// for the format. The same costs and limitations as for
+	/// [`Zone::text`] apply.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// # let packet = [0u8; 1500];
+	/// zone!(decoding, "Decode packet");
+	/// decoding.hex_dump(&packet, 64);
+	/// ```
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub fn hex_dump(&self, data: &[u8], max_len: usize) {
+		#[cfg(feature = "enabled")]
+		self.text(&hex::format_hex(None, data, max_len));
+	}
+
+	/// Begins a zone, which name is known only at run-time, e.g. an
+	/// asset path or a job name. The zone ends when dropped.
+	///
+	/// The name is copied into the source location allocated by
+	/// Tracy, so nothing is leaked, but it is a bit more expensive
+	/// than the regular [`zone!`]. Prefer [`zone_dynamic!`], which
+	/// also records the function name.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// # let asset_path = "textures/grass.png";
+	/// let loading = Zone::begin_dynamic(asset_path);
+	/// loading.color(Color::GREEN);
+	/// ```
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	#[track_caller]
+	pub fn begin_dynamic(name: &str) -> Self {
+		#[cfg(feature = "enabled")]
+		{
+			Self::begin_copied(name, name.as_bytes(), std::panic::Location::caller())
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		Self::new()
+	}
+
+	/// Begins a zone, which name is a static C string, e.g. a
+	/// constant supplied by C code. The zone ends when dropped.
+	///
+	/// The name is used as is, without any conversion through `&str`,
+	/// while the source location is the caller's one, with
+	/// `Zone::begin_from_cstr` as the function name. The location is
+	/// built & leaked once per name & call site, so the names should
+	/// come from a bounded set, e.g. C constants. Prefer
+	/// [`zone_cstr!`], which also declares the zone variable.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// # use std::ffi::{CStr, c_char};
+	/// /// Called from C with the static name of a pass.
+	/// unsafe extern "C" fn begin_pass(name: *const c_char) -> *mut Zone {
+	///     // SAFETY: Pass names are static constants on the C side.
+	///     let name = unsafe { CStr::from_ptr(name) };
+	///     Box::into_raw(Box::new(Zone::begin_from_cstr(name)))
+	/// }
+	/// ```
+	#[track_caller]
+	pub fn begin_from_cstr(name: &'static std::ffi::CStr) -> Self {
+		#[cfg(feature = "enabled")]
+		{
+			Self::begin_cstr(name, c"Zone::begin_from_cstr", std::panic::Location::caller())
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		{
+			// Silences unused variable warning.
+			_ = name;
+			Self::new()
+		}
+	}
+
+	/// Begins a zone, which source location is known only at
+	/// run-time, e.g. a line of a script. The zone ends when dropped.
+	///
+	/// Code produced by macros, template engines, or scripting layers
+	/// could point its zones at the original source this way. All
+	/// passed strings are copied, the same way as for
+	/// [`Zone::begin_dynamic`]. Prefer [`zone!`] with a literal source
+	/// location, if it is known at compile-time.
+	///
+	/// # Examples
+	///
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// # let (script, line, function) = ("player.lua", 42, "update");
+	/// let _update = Zone::begin_at("update player", function, script, line);
+	/// ```
+	pub fn begin_at(name: &str, function: &str, file: &str, line: u32) -> Self {
+		#[cfg(feature = "enabled")]
+		{
+			Self::begin_copied_at(name, function.as_bytes(), file, line)
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		Self::new()
+	}
+
+	/// Begins a zone at the given location. The zone ends when
+	/// dropped.
+	///
+	/// It is a function-based alternative to [`zone!`] for the code,
+	/// which can't use it. Refer to [`ZoneLocation`] for the details.
 	#[inline(always)]
-	fn drop(&mut self) {
+	pub fn begin(location: &'static ZoneLocation) -> Self {
 		#[cfg(feature = "enabled")]
-		// SAFETY: The only way to have Zone is to construct it via
-		// zone! macro, which ensures that ctx value is correct.
+		// SAFETY: Location data is static & null-terminated, as
+		// `ZoneLocation::new` takes only static `CStr`s.
 		unsafe {
-			sys::___tracy_emit_zone_end(self.ctx);
+			details::zone(location, 1)
 		}
+
+		#[cfg(not(feature = "enabled"))]
+		Self::new()
 	}
 }
 
+#[cfg(feature = "enabled")]
 impl Zone {
-	#[doc(hidden)]
-	#[cfg(not(feature = "enabled"))]
-	pub fn new() -> Self {
-		Self { _unsend: PhantomData }
+	/// Returns the context, which is inactive after [`after_fork`]
+	/// or the capture shutdown, so Tracy ignores the zone, and the
+	/// guard to use it with, refer to [`emission`].
+	#[inline(always)]
+	fn live_ctx(&self) -> (sys::TracyCZoneCtx, Option<emission::Emission>) {
+		let emission = emission();
+		(sys::TracyCZoneCtx { active: self.ctx.active & emission.is_some() as i32, ..self.ctx }, emission)
 	}
 
-	/// Allows to control the zone color dynamically.
+	/// Begins a zone, which name is a static C string known only at
+	/// run-time. Its location is leaked once per name & call site.
 	///
-	/// This can be called multiple times, however only the latest
-	/// call will have an effect.
-	pub fn color(&self, color: Color) {
-		#[cfg(feature = "enabled")]
-		// SAFETY: self always contains a valid `ctx`.
-		unsafe {
-			sys::___tracy_emit_zone_color(self.ctx, color.as_u32());
+	/// `function` is a placeholder, which tells how the zone was
+	/// begun, as the actual function is not known.
+	pub(crate) fn begin_cstr(
+		name:     &'static CStr,
+		function: &'static CStr,
+		caller:   &'static std::panic::Location<'static>,
+	) -> Self {
+		match cstr_location(name, function, caller) {
+			// SAFETY: Location data is static & null-terminated.
+			Some(location) => unsafe { details::zone(location, 1) },
+			// The interner is full, so the location is only copied.
+			None => {
+				let name = name.to_string_lossy();
+				Self::begin_copied(&name, function.to_bytes(), caller)
+			},
 		}
-		#[cfg(not(feature = "enabled"))]
-		{
-			// Silences unused import warning.
-			_ = color;
+	}
+
+	/// Begins a zone, which name is not static, so it is only
+	/// copied.
+	pub(crate) fn begin_copied(name: &str, function: &[u8], location: &std::panic::Location) -> Self {
+		Self::begin_copied_at(name, function, location.file(), location.line())
+	}
+
+	/// Begins a zone, which name & source location are not static,
+	/// so they are only copied.
+	fn begin_copied_at(name: &str, function: &[u8], file: &str, line: u32) -> Self {
+		#[cfg(test)]
+		if let Some(zone) = record::zone(|| name.to_owned()) {
+			return zone;
+		}
+		let ctx = Self::begin_transient(name.as_bytes(), function, file, line);
+		zone_path::enter_dynamic(name, ctx);
+		#[cfg(debug_assertions)]
+		lifo::enter(ctx);
+		#[cfg(feature = "zone-hooks")]
+		hooks::enter(None, ctx);
+		#[cfg(all(target_os = "linux", feature = "trace-marker"))]
+		if ctx.active != 0 {
+			trace_marker::zone_begin_bytes(name.as_bytes());
 		}
+		#[cfg(feature = "mirror")]
+		if ctx.active != 0 {
+			mirror::zone_begin(name);
+		}
+		Self { ctx, _unsend: PhantomData }
 	}
 
-	/// Adds a custom numeric value that will be displayed along with
-	/// the zone information. E.g. a loop iteration or size of the
-	/// processed buffer.
-	///
-	/// This method can be called multiple times, all of the passed
-	/// values will be attached to the zone matching the call order.
+	/// Begins a zone with the source location allocated by Tracy.
+	fn begin_transient(name: &[u8], function: &[u8], file: &str, line: u32) -> sys::TracyCZoneCtx {
+		debug_assert!(name.len() + function.len() + file.len() < u16::MAX as usize / 2);
+		lazy::flush();
+		let Some(_emission) = emission() else {
+			return sys::TracyCZoneCtx { id: 0, active: 0 };
+		};
+		// SAFETY: Tracy copies the passed strings, which don't need
+		// to be null-terminated.
+		let ctx = unsafe {
+			let srcloc = sys::___tracy_alloc_srcloc_name(
+				line,
+				file.as_ptr().cast(),
+				file.len(),
+				function.as_ptr().cast(),
+				function.len(),
+				name.as_ptr().cast(),
+				name.len(),
+			);
+			sys::___tracy_emit_zone_begin_alloc(srcloc, 1)
+		};
+		self_profile::count_event();
+		ctx
+	}
+}
+
+/// Profiling zone, which measures its duration.
+///
+/// Refer to the `timed` form of [`zone!`] for the usage how-to.
+///
+/// Plain zones don't query the clock at all, so the timing is
+/// opt-in. The zone dereferences to [`Zone`], so the zone data could
+/// be added as usual.
+pub struct TimedZone {
+	zone:    Zone,
 	#[cfg(feature = "enabled")]
-	pub fn number(&self, value: u64) {
-		#[cfg(feature = "enabled")]
-		// SAFETY: self always contains a valid `ctx`.
-		unsafe {
-			sys::___tracy_emit_zone_value(self.ctx, value);
+	started: Instant,
+}
+
+impl TimedZone {
+	#[doc(hidden)]
+	#[inline(always)]
+	pub fn new(zone: Zone) -> Self {
+		Self {
+			zone,
+			#[cfg(feature = "enabled")]
+			started: Instant::now(),
 		}
 	}
 
-	/// Adds a custom text string that will be displayed along with
-	/// the zone information. E.g. name of the file you are
-	/// processing.
+	/// Ends the zone right away, returning its duration.
 	///
-	/// The profiler will allocate a temporary internal buffer and
-	/// copy the passed value there. Hence, this operation involves a
-	/// small run-time cost.
+	/// It allows to end the zone before the end of the scope & reuse
+	/// the measured time, e.g. for plots or adaptive scheduling,
+	/// without a second timer. The duration is measured regardless of
+	/// the profiler connection, but it is always zero, unless
+	/// `enabled` feature is on.
 	///
-	/// This method can be called multiple times, all of the passed
-	/// values will be attached to the zone matching the call order.
+	/// # Examples
 	///
-	/// Be aware that the passed text slice couldn't be larger than 64
-	/// Kb.
-	pub fn text(&self, s: &str) {
+	/// ```no_run
+	/// # use tracy_gizmos::*;
+	/// # fn simulate() {}
+	/// zone!(simulation, "simulate", timed);
+	/// simulate();
+	/// let spent = simulation.end();
+	/// plot!("Simulation, ms", spent.as_secs_f64() * 1000.0);
+	/// ```
+	#[must_use = "use `drop` to just end the zone"]
+	pub fn end(self) -> Duration {
 		#[cfg(feature = "enabled")]
 		{
-			debug_assert!(s.len() < u16::MAX as usize);
-			// SAFETY: self always contains a valid `ctx`.
-			unsafe {
-				sys::___tracy_emit_zone_text(self.ctx, s.as_ptr().cast(), s.len())
-			}
+			let spent = self.started.elapsed();
+			drop(self.zone);
+			spent
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		Duration::ZERO
+	}
+}
+
+impl std::ops::Deref for TimedZone {
+	type Target = Zone;
+
+	#[inline(always)]
+	fn deref(&self) -> &Zone {
+		&self.zone
+	}
+}
+
+/// Returns the static location of the zone named by the static C
+/// string at the call site, leaking it on the first use. Returns
+/// `None`, if the file name can't be interned.
+#[cfg(feature = "enabled")]
+fn cstr_location(
+	name:     &'static CStr,
+	function: &'static CStr,
+	caller:   &'static std::panic::Location<'static>,
+) -> Option<&'static ZoneLocation> {
+	use std::cell::RefCell;
+	use std::collections::HashMap;
+	use std::sync::Mutex;
+
+	// Name & file addresses with the line. The same file could end up
+	// at several addresses, which only duplicates the location. The
+	// function is the same for every call site.
+	type Site = (usize, usize, u32);
+
+	static LOCATIONS: Mutex<Option<HashMap<Site, &'static ZoneLocation>>> = Mutex::new(None);
+	thread_local! {
+		static CACHE: RefCell<HashMap<Site, &'static ZoneLocation>> = RefCell::new(HashMap::new());
+	}
+
+	let site = (name.as_ptr() as usize, caller.file().as_ptr() as usize, caller.line());
+	if let Some(location) = CACHE.with_borrow(|cache| cache.get(&site).copied()) {
+		return Some(location);
+	}
+	let location = {
+		let mut locations = LOCATIONS.lock().unwrap_or_else(|e| e.into_inner());
+		let locations     = locations.get_or_insert_with(HashMap::new);
+		match locations.get(&site) {
+			Some(location) => *location,
+			None           => {
+				let file     = intern::try_intern(caller.file())?;
+				let location = &*Box::leak(Box::new(ZoneLocation::new(name, function, file, caller.line(), Color::UNSPECIFIED)));
+				locations.insert(site, location);
+				location
+			},
 		}
+	};
+	CACHE.with_borrow_mut(|cache| cache.insert(site, location));
+	Some(location)
+}
+
+/// Lazy profiling zone.
+///
+/// Refer to [`zone!`] for the usage how-to.
+///
+/// It instruments the current scope, but is emitted only if
+/// something happens inside of it or it lasts long enough.
+pub struct LazyZone {
+	#[cfg(feature = "enabled")]
+	min:     Option<Duration>,
+	_unsend: PhantomData<*mut ()>,
+}
+
+#[cfg(any(doc, feature = "enabled"))]
+impl Drop for LazyZone {
+	#[inline(always)]
+	fn drop(&mut self) {
+		#[cfg(feature = "enabled")]
+		lazy::end(self.min);
 	}
 }
 
 /// A statically allocated location for a profiling zone.
 ///
-/// It is an implementation detail and can be changed at any moment.
-#[doc(hidden)]
+/// Tracy identifies zones by their locations, so a location has to
+/// live until the end of the program and is usually a `static`. It
+/// allows code generators, DSLs and other macros, which can't go
+/// through [`zone!`], to emit zones via [`Zone::begin`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// static LOADING: ZoneLocation = ZoneLocation::new(
+///     c"Loading",
+///     c"load_level",
+///     c"levels.dsl",
+///     42,
+///     Color::ORANGE,
+/// );
+///
+/// let _loading = Zone::begin(&LOADING);
+/// ```
 #[repr(transparent)]
 pub struct ZoneLocation(#[cfg(feature = "enabled")] sys::___tracy_source_location_data);
 
@@ -806,6 +3352,79 @@ pub struct ZoneLocation(#[cfg(feature = "enabled")] sys::___tracy_source_locatio
 unsafe impl Send for ZoneLocation {}
 unsafe impl Sync for ZoneLocation {}
 
+impl ZoneLocation {
+	/// Creates the zone location with the given zone name, function
+	/// name, source file name, line and color.
+	///
+	/// Pass [`Color::UNSPECIFIED`] to use the default zone color.
+	pub const fn new(
+		name:     &'static CStr,
+		function: &'static CStr,
+		file:     &'static CStr,
+		line:     u32,
+		color:    Color,
+	) -> Self {
+		Self(
+			#[cfg(feature = "enabled")]
+			sys::___tracy_source_location_data {
+				name:     name.as_ptr(),
+				function: function.as_ptr(),
+				file:     file.as_ptr(),
+				line,
+				color:    color.as_u32(),
+			}
+		)
+	}
+
+	/// Returns the zone name.
+	pub fn name(&self) -> &str {
+		#[cfg(feature = "enabled")]
+		// SAFETY: Names are null-terminated & outlive the location.
+		return unsafe { location_str(self.0.name) };
+
+		#[cfg(not(feature = "enabled"))]
+		""
+	}
+
+	/// Returns the enclosing function name.
+	pub fn function(&self) -> &str {
+		#[cfg(feature = "enabled")]
+		// SAFETY: Function names are null-terminated & outlive the
+		// location.
+		return unsafe { location_str(self.0.function) };
+
+		#[cfg(not(feature = "enabled"))]
+		""
+	}
+
+	/// Returns the source file name.
+	pub fn file(&self) -> &str {
+		#[cfg(feature = "enabled")]
+		// SAFETY: File names are null-terminated & outlive the
+		// location.
+		return unsafe { location_str(self.0.file) };
+
+		#[cfg(not(feature = "enabled"))]
+		""
+	}
+
+	/// Returns the source line.
+	pub fn line(&self) -> u32 {
+		#[cfg(feature = "enabled")]
+		return self.0.line;
+
+		#[cfg(not(feature = "enabled"))]
+		0
+	}
+}
+
+/// Strings of locations are created from `str`s, unless some weird
+/// `CStr` is passed explicitly.
+#[cfg(feature = "enabled")]
+unsafe fn location_str<'a>(s: *const std::os::raw::c_char) -> &'a str {
+	CStr::from_ptr(s).to_str().unwrap_or("<non-UTF-8>")
+}
+
 /// Discontinuous frame.
 ///
 /// Refer to [`frame!`] for usage howto.
@@ -823,7 +3442,19 @@ impl Drop for Frame {
 		// frame! macro, which ensures that contained pointer is
 		// correct.
 		unsafe {
+			#[cfg(test)]
+			if record::push(|| record::Event::FrameEnd(record::string(self.0))) {
+				return;
+			}
+			let Some(_emission) = emission() else {
+				return;
+			};
 			sys::___tracy_emit_frame_mark_end(self.0.cast());
+			#[cfg(all(windows, feature = "etw"))]
+			etw::frame_end(self.0.cast());
+			#[cfg(all(target_os = "linux", feature = "trace-marker"))]
+			trace_marker::frame("frame end", self.0.cast());
+			self_profile::count_event();
 		}
 	}
 }
@@ -834,7 +3465,8 @@ impl Drop for Frame {
 /// crate version, application's environment, etc.
 ///
 /// This can be called multiple times. Tracy will accumulate all the
-/// information and display it altogether.
+/// information and display it altogether. Use [`AppInfoBatch`] to
+/// keep the order stable, when it is called from multiple threads.
 ///
 /// Be aware that the passed text slice couldn't be larger than 64
 /// Kb.
@@ -847,16 +3479,25 @@ impl Drop for Frame {
 #[inline(always)]
 pub fn app_info(info: &str) {
 	#[cfg(feature = "enabled")]
-	{
+	if let Some(_emission) = emission() {
 		debug_assert!(info.len() < u16::MAX as usize);
 		// SAFETY: Slice should contain valid data and having no
 		// terminating zero is fine.
 		unsafe {
 			sys::___tracy_emit_message_appinfo(info.as_ptr().cast(), info.len());
 		}
+		self_profile::count_text(info.len());
 	}
 }
 
+/// Reports a problem of the capture itself, so it is visible both in
+/// the trace description and among the messages.
+#[cfg(feature = "enabled")]
+pub(crate) fn diagnostic(text: &str) {
+	app_info(text);
+	details::message_size_color(text, Color::YELLOW);
+}
+
 /// Implementation details, do not relay on anything from this module!
 ///
 /// It is public only due to the usage in public macro bodies.
@@ -866,7 +3507,31 @@ pub mod details {
 	use std::ffi::c_void;
 	use super::*;
 
+	pub use crate::aggregate::AggregateSite;
+	pub use crate::rate::RateLimit;
+
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub use crate::context::attach as attach_context;
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub use crate::fixed_buf::FixedBuf;
+
 	#[inline(always)]
+	/// Returns the null-terminated copy of the string, which must be
+	/// one byte shorter than `N`.
+	pub const fn null_terminated<const N: usize>(s: &str) -> [u8; N] {
+		let bytes   = s.as_bytes();
+		let mut nul = [0; N];
+		let mut i   = 0;
+		while i < bytes.len() {
+			if bytes[i] == 0 {
+				panic!("Zone name should not contain null bytes.");
+			}
+			nul[i] = bytes[i];
+			i += 1;
+		}
+		nul
+	}
+
 	pub const unsafe fn zone_location(
 		name: &'static str,
 		func: &'static [u8],
@@ -886,39 +3551,248 @@ pub mod details {
 	}
 
 	#[inline(always)]
-	pub unsafe fn zone(location: &ZoneLocation, enabled: i32) -> Zone {
-		let ctx = sys::___tracy_emit_zone_begin(&location.0, enabled);
+	pub unsafe fn zone(location: &'static ZoneLocation, enabled: i32) -> Zone {
+		#[cfg(test)]
+		if enabled != 0 {
+			if let Some(zone) = record::location_zone(location) {
+				return zone;
+			}
+		}
+		lazy::flush();
+		let emission = emission();
+		let ctx = sys::___tracy_emit_zone_begin(&location.0, enabled & emission.is_some() as i32);
+		// User hooks must not hold the shutdown back.
+		drop(emission);
+		zone_begun(location, ctx)
+	}
+
+	#[inline(always)]
+	pub unsafe fn zone_with_callstack(location: &'static ZoneLocation, depth: i32, enabled: i32) -> Zone {
+		#[cfg(test)]
+		if enabled != 0 {
+			if let Some(zone) = record::location_zone(location) {
+				return zone;
+			}
+		}
+		lazy::flush();
+		let emission = emission();
+		let ctx = sys::___tracy_emit_zone_begin_callstack(&location.0, depth, enabled & emission.is_some() as i32);
+		// User hooks must not hold the shutdown back.
+		drop(emission);
+		zone_begun(location, ctx)
+	}
+
+	#[inline(always)]
+	pub fn aggregated_zone(site: &'static AggregateSite) -> crate::AggregatedZone {
+		crate::AggregatedZone::begin(site)
+	}
+
+	#[inline(always)]
+	pub fn level_emitted(level: Level) -> bool {
+		level::is_emitted(level)
+	}
+
+	/// Returns an inactive zone, which costs nothing.
+	#[inline(always)]
+	pub fn removed_zone() -> Zone {
+		Zone { ctx: sys::TracyCZoneCtx { id: 0, active: 0 }, _unsend: PhantomData }
+	}
+
+	#[inline(always)]
+	unsafe fn zone_begun(location: &'static ZoneLocation, ctx: sys::TracyCZoneCtx) -> Zone {
+		self_profile::count_event();
+		zone_path::enter(location.0.name, ctx);
+		#[cfg(debug_assertions)]
+		lifo::enter(ctx);
+		#[cfg(feature = "zone-hooks")]
+		hooks::enter(Some(location), ctx);
+		#[cfg(all(target_os = "linux", feature = "trace-marker"))]
+		if ctx.active != 0 {
+			trace_marker::zone_begin(location.0.name);
+		}
+		#[cfg(feature = "mirror")]
+		if ctx.active != 0 {
+			mirror::zone_begin_cstr(location.0.name);
+		}
 		Zone { ctx, _unsend: PhantomData }
 	}
 
+	#[inline(always)]
+	pub unsafe fn lazy_zone(location: &'static ZoneLocation, min: Option<Duration>) -> LazyZone {
+		lazy::begin(&location.0);
+		LazyZone { min, _unsend: PhantomData }
+	}
+
+	pub struct EndCallstack {
+		text:  &'static str,
+		depth: i32,
+	}
+
+	impl EndCallstack {
+		#[inline(always)]
+		pub fn new(text: &'static str, depth: i32) -> Self {
+			debug_assert!(text.ends_with('\0'));
+			Self { text, depth }
+		}
+	}
+
+	impl Drop for EndCallstack {
+		#[inline(always)]
+		fn drop(&mut self) {
+			// SAFETY: The text is static & null-terminated.
+			unsafe { message_callstack(self.text.as_ptr(), self.depth) };
+		}
+	}
+
+	pub struct SlowWarning {
+		name:      &'static str,
+		threshold: Duration,
+		started:   Instant,
+	}
+
+	impl SlowWarning {
+		#[inline(always)]
+		pub fn new(name: &'static str, threshold: Duration) -> Self {
+			debug_assert!(name.ends_with('\0'));
+			Self { name, threshold, started: Instant::now() }
+		}
+	}
+
+	impl Drop for SlowWarning {
+		#[inline(always)]
+		fn drop(&mut self) {
+			let elapsed = self.started.elapsed();
+			if elapsed <= self.threshold {
+				return;
+			}
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			message_size_color(
+				&format!(
+					"Slow: {} took {elapsed:.3?}, over {:?}",
+					&self.name[..self.name.len() - 1],
+					self.threshold,
+				),
+				Color::YELLOW,
+			);
+			#[cfg(feature = "no-dynamic-strings")]
+			// SAFETY: `new` ensures the name is null-terminated.
+			unsafe {
+				message_color(self.name.as_ptr(), Color::YELLOW);
+			}
+		}
+	}
+
+	#[track_caller]
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub fn dyn_zone(type_name: &'static str, method: &'static str) -> Zone {
+		Zone::begin_cstr(dyn_zone::zone_name(type_name, method), c"dyn_zone!", std::panic::Location::caller())
+	}
+
+	/// Builds the location of a zone named after the function, which
+	/// contains the nested type. Names are reported as `overflow`,
+	/// once the interner is full.
+	#[cfg(feature = "migration-shims")]
+	pub fn function_location(nested_type: &str, file: &'static CStr, line: u32) -> &'static ZoneLocation {
+		let function = nested_type.rsplit_once("::").map_or(nested_type, |(function, _)| function);
+		let name     = function.rsplit("::").next().unwrap_or(function);
+		Box::leak(Box::new(ZoneLocation::new(
+			intern::intern(name),
+			intern::intern(function),
+			file,
+			line,
+			Color::UNSPECIFIED,
+		)))
+	}
+
+	/// `function` is either null-terminated, as created by
+	/// `create_function_name_for_zone!`, or not.
+	#[track_caller]
+	pub fn dynamic_zone(name: &str, function: &[u8]) -> Zone {
+		let function = function.strip_suffix(b"\0").unwrap_or(function);
+		Zone::begin_copied(name, function, std::panic::Location::caller())
+	}
+
 	#[inline(always)]
 	pub unsafe fn set_thread_name(name: *const u8) {
+		thread_filter::set_name(std::ffi::CStr::from_ptr(name.cast()));
+		let Some(_emission) = emission() else {
+			return;
+		};
 		sys::___tracy_set_thread_name(name.cast());
 	}
 
 	#[inline(always)]
 	pub unsafe fn message(text: *const u8) {
+		message_callstack(text, 0);
+	}
+
+	#[inline(always)]
+	pub unsafe fn message_callstack(text: *const u8, depth: i32) {
+		#[cfg(test)]
+		if record::push(|| record::Event::Message(record::string(text.cast()))) {
+			return;
+		}
+		let Some(_emission) = emission() else {
+			return;
+		};
+		lazy::flush();
 		sys::___tracy_emit_messageL(
 			text.cast(),
-			0, // callstack depth, 0 is disabled.
+			depth, // callstack depth, 0 is disabled.
 		);
+		#[cfg(all(windows, feature = "etw"))]
+		etw::message_cstr(text.cast());
+		#[cfg(feature = "mirror")]
+		mirror::message_cstr(text.cast());
+		self_profile::count_event();
 	}
 
 	#[inline(always)]
 	pub fn message_size(text: &str) {
+		message_size_callstack(text, 0);
+	}
+
+	#[inline(always)]
+	pub fn message_size_callstack(text: &str, depth: i32) {
+		#[cfg(test)]
+		if record::push(|| record::Event::Message(text.to_owned())) {
+			return;
+		}
+		let Some(_emission) = emission() else {
+			return;
+		};
+		lazy::flush();
 		debug_assert!(text.len() < u16::MAX as usize);
 		// SAFETY: Dynamic non-zero-terminated string is fine.
 		unsafe {
 			sys::___tracy_emit_message(
 				text.as_ptr().cast(),
 				text.len(),
-				0, // callstack depth, 0 is disabled.
+				depth, // callstack depth, 0 is disabled.
 			);
 		}
+		#[cfg(all(windows, feature = "etw"))]
+		etw::message(text.as_bytes());
+		#[cfg(feature = "mirror")]
+		mirror::message(text);
+		self_profile::count_text(text.len());
 	}
 
 	#[inline(always)]
 	pub fn message_size_color(text: &str, color: Color) {
+		message_size_color_callstack(text, color, 0);
+	}
+
+	#[inline(always)]
+	pub fn message_size_color_callstack(text: &str, color: Color, depth: i32) {
+		#[cfg(test)]
+		if record::push(|| record::Event::Message(text.to_owned())) {
+			return;
+		}
+		let Some(_emission) = emission() else {
+			return;
+		};
+		lazy::flush();
 		debug_assert!(text.len() < u16::MAX as usize);
 		// SAFETY: Dynamic non-zero-terminated string is fine.
 		unsafe {
@@ -926,28 +3800,114 @@ pub mod details {
 				text.as_ptr().cast(),
 				text.len(),
 				color.as_u32(),
-				0, // callstack depth, 0 is disabled.
+				depth, // callstack depth, 0 is disabled.
 			);
 		}
+		#[cfg(all(windows, feature = "etw"))]
+		etw::message(text.as_bytes());
+		#[cfg(feature = "mirror")]
+		mirror::message(text);
+		self_profile::count_text(text.len());
+	}
+
+	#[inline(always)]
+	pub unsafe fn message_color(text: *const u8, color: Color) {
+		message_color_callstack(text, color, 0);
+	}
+
+	#[inline(always)]
+	pub unsafe fn message_color_callstack(text: *const u8, color: Color, depth: i32) {
+		#[cfg(test)]
+		if record::push(|| record::Event::Message(record::string(text.cast()))) {
+			return;
+		}
+		let Some(_emission) = emission() else {
+			return;
+		};
+		lazy::flush();
+		sys::___tracy_emit_messageLC(
+			text.cast(),
+			color.as_u32(),
+			depth, // callstack depth, 0 is disabled.
+		);
+		#[cfg(all(windows, feature = "etw"))]
+		etw::message_cstr(text.cast());
+		#[cfg(feature = "mirror")]
+		mirror::message_cstr(text.cast());
+		self_profile::count_event();
+	}
+
+	/// Truncates the text, so it fits into a message.
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub fn truncate_message(text: &mut String) {
+		let mut end = text.len().min(u16::MAX as usize - 1);
+		while !text.is_char_boundary(end) {
+			end -= 1;
+		}
+		text.truncate(end);
+	}
+
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub fn message_os_error(text: &str, err: &std::io::Error) {
+		use std::fmt::Write;
+
+		let mut message = format!("{text}: {err}");
+		if err.raw_os_error().is_none() {
+			// Writing into a `String` never fails.
+			_ = write!(message, " ({:?})", err.kind());
+		}
+		if let Some(path) = zone_path::current() {
+			_ = write!(message, " in {path}");
+		}
+		message_size_color(&message, Color::RED);
 	}
 
-	#[inline(always)]
-	pub unsafe fn message_color(text: *const u8, color: Color) {
-		sys::___tracy_emit_messageLC(
-			text.cast(),
-			color.as_u32(),
-			0, // callstack depth, 0 is disabled.
-		);
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	pub fn message_hex(label: &str, data: &[u8], max_len: usize) {
+		message_size(&hex::format_hex(Some(label), data, max_len));
 	}
 
 	#[inline(always)]
 	pub unsafe fn mark_frame_end(name: *const u8) {
+		#[cfg(test)]
+		if record::push(|| record::Event::FrameMark((!name.is_null()).then(|| record::string(name.cast())))) {
+			return;
+		}
+		let Some(emission) = emission() else {
+			return;
+		};
 		sys::___tracy_emit_frame_mark(name.cast());
+		#[cfg(all(windows, feature = "etw"))]
+		etw::frame_mark(name.cast());
+		#[cfg(all(target_os = "linux", feature = "trace-marker"))]
+		trace_marker::frame("frame", name.cast());
+		self_profile::count_event();
+		#[cfg(all(debug_assertions, not(feature = "no-dynamic-strings")))]
+		collisions::frame(name.cast());
+		// User integrations must not hold the shutdown back.
+		drop(emission);
+		if name.is_null() {
+			frame_plot::mark();
+			integration::mark_frame();
+		}
 	}
 
 	#[inline(always)]
 	pub unsafe fn discontinuous_frame(name: *const i8) -> Frame {
-		sys::___tracy_emit_frame_mark_start(name.cast());
+		#[cfg(test)]
+		if record::push(|| record::Event::FrameStart(record::string(name.cast()))) {
+			return Frame(name);
+		}
+		if let Some(_emission) = emission() {
+			sys::___tracy_emit_frame_mark_start(name.cast());
+			#[cfg(all(windows, feature = "etw"))]
+			etw::frame_start(name.cast());
+			#[cfg(all(target_os = "linux", feature = "trace-marker"))]
+			trace_marker::frame("frame start", name.cast());
+			self_profile::count_event();
+			#[cfg(all(debug_assertions, not(feature = "no-dynamic-strings")))]
+			collisions::discontinuous_frame(name.cast());
+		}
 		Frame(name)
 	}
 
@@ -958,7 +3918,11 @@ pub mod details {
 
 	#[inline(always)]
 	unsafe fn track_alloc_impl(name: *const u8, ptr: *const c_void, size: usize) {
+		let Some(_emission) = emission() else {
+			return;
+		};
 		sys::___tracy_emit_memory_alloc_named(ptr, size, 0, name.cast());
+		self_profile::count_event();
 	}
 
 	#[inline(always)]
@@ -968,7 +3932,31 @@ pub mod details {
 
 	#[inline(always)]
 	unsafe fn track_free_impl(name: *const u8, ptr: *const c_void) {
+		let Some(_emission) = emission() else {
+			return;
+		};
 		sys::___tracy_emit_memory_free_named(ptr, 0, name.cast());
+		self_profile::count_event();
+	}
+
+	/// Returns the location copy with the function name taken from the
+	/// type name of a type nested into the function.
+	#[cfg(all(feature = "function-names", not(feature = "unstable-function-names")))]
+	pub fn with_function_name(location: &'static ZoneLocation, nested_type: &str) -> &'static ZoneLocation {
+		// The location is kept as is, once the interner is full.
+		match intern::try_intern(function_name(nested_type)) {
+			Some(function) => Box::leak(Box::new(ZoneLocation(sys::___tracy_source_location_data {
+				function: function.as_ptr(),
+				..location.0
+			}))),
+			None           => location,
+		}
+	}
+
+	/// Strips the nested type name, e.g. `::X`, from its path.
+	#[cfg(all(feature = "function-names", not(feature = "unstable-function-names")))]
+	pub fn function_name(nested_type: &str) -> &str {
+		nested_type.rsplit_once("::").map_or(nested_type, |(function, _)| function)
 	}
 
 	// Function name trick only works with an unstable
@@ -1029,6 +4017,536 @@ mod tests {
 		let _tracy = start_capture();
 	}
 
+	#[cfg(feature = "enabled")]
+	#[test]
+	fn instrumentation_outliving_capture() {
+		use std::sync::atomic::AtomicBool;
+
+		static STOP: AtomicBool = AtomicBool::new(false);
+
+		let tracy = start_capture();
+		let worker = std::thread::spawn(|| {
+			while !STOP.load(Ordering::Relaxed) {
+				zone!("worker");
+				message!("working");
+			}
+		});
+		zone!(outliving, "outliving");
+		std::thread::sleep(Duration::from_millis(5));
+		drop(tracy);
+		outliving.text("after shutdown");
+		drop(outliving);
+		message!("after shutdown");
+		STOP.store(true, Ordering::Relaxed);
+		worker.join().unwrap();
+
+		let tracy   = start_capture();
+		let workers = (0..2).map(|i| std::thread::spawn(move || {
+			zone!("joined worker");
+			i
+		}));
+		let results = tracy.shutdown_after_join(workers);
+		assert_eq!(results.into_iter().map(Result::unwrap).collect::<Vec<_>>(), [0, 1]);
+	}
+
+	#[cfg(feature = "enabled")]
+	#[test]
+	fn connection_timeout_does_not_block_drop() {
+		let tracy = start_capture_with(CaptureOptions {
+			connection_timeout: Some(Duration::from_secs(60)),
+			..Default::default()
+		});
+		drop(tracy);
+		static TIMED_OUT: AtomicBool = AtomicBool::new(false);
+		let tracy = start_capture_with(CaptureOptions {
+			connection_timeout:    Some(Duration::from_millis(1)),
+			on_connection_timeout: Some(|_| TIMED_OUT.store(true, Ordering::Relaxed)),
+			..Default::default()
+		});
+		std::thread::sleep(Duration::from_millis(20));
+		drop(tracy);
+		assert!(TIMED_OUT.load(Ordering::Relaxed));
+	}
+
+	#[cfg(feature = "enabled")]
+	#[test]
+	fn color_literals() {
+		use crate::record::Event::*;
+
+		let _tracy = start_capture();
+		let events = crate::record::record(|| {
+			zone!("literal", 0xFF8000);
+			zone!(named, "literal", 0x00FF00, enabled: true);
+			named.text("text");
+			zone!(lazy "literal", 0x0000FF, min: 1us);
+			zone!("expression", Color::new(1, 2, 3));
+		});
+		assert_eq!(events, [
+			ZoneBegin("literal".into()),
+			ZoneColor(0xFF8000),
+			ZoneBegin("literal".into()),
+			ZoneColor(0x00FF00),
+			ZoneBegin("expression".into()),
+			ZoneColor(0x010203),
+			ZoneEnd,
+			ZoneEnd,
+			ZoneEnd,
+		]);
+	}
+
+	#[cfg(feature = "enabled")]
+	#[test]
+	fn leveled_zones() {
+		use crate::record::Event::*;
+
+		let _tracy = start_capture();
+		let events = crate::record::record(|| {
+			zone_l2!("l2");
+			zone_l2!(l2, "l2", Color::RED);
+			l2.text("text");
+			zone_l3!(lazy "l3", min: 5us);
+			zone_l3!("l3", Color::RED, enabled: true);
+			zone_l3!(l3, "l3", enabled: false);
+			l3.number(3);
+		});
+		let begun = |name: &str| events.iter().filter(|e| **e == ZoneBegin(name.into())).count();
+		assert_eq!(begun("l2"), if cfg!(feature = "instr-level-2") { 2 } else { 0 }, "{events:?}");
+		// Lazy zones are not recorded, while `enabled: false` one is
+		// suppressed at any level.
+		assert_eq!(begun("l3"), if cfg!(feature = "instr-level-3") { 1 } else { 0 }, "{events:?}");
+	}
+
+	#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+	#[test]
+	fn static_messages() {
+		use crate::record::Event::*;
+
+		let _tracy = start_capture();
+		let x = 42;
+		let events = crate::record::record(|| {
+			message_static!(8, "x={}", x);
+			message_static!(7, Color::RED, "x={x}, too long");
+		});
+		assert_eq!(events, [Message("x=42".into()), Message("x=42...".into())]);
+	}
+
+	#[cfg(feature = "enabled")]
+	#[test]
+	fn viewer_protocol_mismatch() {
+		use crate::record::Event::*;
+
+		assert_eq!(TracyVersion::CLIENT.to_string(), "0.10.0");
+		let tracy = start_capture_with(CaptureOptions {
+			expected_viewer_protocol: Some(TracyVersion::PROTOCOL),
+			..Default::default()
+		});
+		drop(tracy);
+		let _tracy = start_capture_with(CaptureOptions {
+			expected_viewer_protocol: Some(TracyVersion::PROTOCOL - 1),
+			..Default::default()
+		});
+
+		let events = crate::record::record(|| {
+			assert!(version::check_viewer(TracyVersion::PROTOCOL));
+			assert!(!version::check_viewer(TracyVersion::PROTOCOL - 1));
+		});
+		assert!(matches!(&events[..], [Message(text)] if text.contains("protocol")), "{events:?}");
+	}
+
+	#[cfg(feature = "enabled")]
+	#[test]
+	fn named_thread_lifecycle() {
+		let _tracy = start_capture_with(CaptureOptions {
+			thread_lifecycle_messages: true,
+			..Default::default()
+		});
+		let name = thread::spawn_named("lifecycle", || {
+			std::thread::current().name().map(str::to_string)
+		});
+		assert_eq!(name.unwrap().join().unwrap().as_deref(), Some("lifecycle"));
+	}
+
+	#[cfg(all(feature = "enabled", feature = "zone-path", not(feature = "no-dynamic-strings")))]
+	#[test]
+	fn zone_path_is_tracked() {
+		let _tracy = start_capture();
+		assert_eq!(zone_path::current(), None);
+		zone!("outer");
+		{
+			zone!("inner");
+			zone!("skipped", enabled: false);
+			assert_eq!(zone_path::current().as_deref(), Some("outer > inner"));
+		}
+		assert_eq!(zone_path::current().as_deref(), Some("outer"));
+		message_os_error!("test", std::io::Error::from(std::io::ErrorKind::NotFound));
+	}
+
+	#[cfg(feature = "enabled")]
+	#[test]
+	fn slow_zone_warns() {
+		use crate::record::Event::*;
+
+		let _tracy = start_capture();
+		let events = crate::record::record(|| {
+			zone_warn_slow!("fast", 1s);
+			zone_warn_slow!(slow, "slow", Duration::from_nanos(1));
+			slow.number(1);
+			std::thread::sleep(Duration::from_millis(1));
+		});
+		// Only the slow zone warns, before it ends.
+		assert!(matches!(
+			&events[..],
+			[ZoneBegin(fast), ZoneBegin(slow), Message(warning), ZoneEnd, ZoneEnd]
+				if fast == "fast" && slow == "slow" && warning.contains("slow"),
+		), "{events:?}");
+	}
+
+	#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+	#[test]
+	fn hex_dumps() {
+		let _tracy = start_capture();
+		zone!(zone, "hex");
+		zone.hex_dump(b"\x01\x02", 1);
+		message_hex!("rx", &[0u8; 40], 32);
+	}
+
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	#[test]
+	fn signed_and_float_values() {
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		zone!(zone, "values");
+		zone.value_f64(0.25);
+		zone.value_f64(f64::NAN);
+		zone.value_i64(-42);
+		zone.value_i64(i64::MIN);
+	}
+
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	#[test]
+	fn zone_fields() {
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		let path = "assets/a=b.png".to_string();
+		zone!("load", { path = path, size = 1024 });
+		zone!(loading, "load", crate::Color::RED, { path = path, });
+		loading.field("retries", 2);
+		zone!("empty", {});
+		zone_l3!(level, "load", { size = path.len() });
+		level.field("compiled out", path);
+	}
+
+	#[test]
+	fn location_zones() {
+		static LOCATION: crate::ZoneLocation = crate::ZoneLocation::new(
+			c"generated",
+			c"generated_fn",
+			c"generated.dsl",
+			7,
+			crate::Color::UNSPECIFIED,
+		);
+
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		let zone = crate::Zone::begin(&LOCATION);
+		zone.text("from a generator");
+		let _nested = crate::Zone::begin(&LOCATION);
+	}
+
+	#[test]
+	fn overridden_source_locations() {
+		const LINE: u32 = 7;
+
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		zone!("generated", file: "generated.dsl", line: 3, function: "generated_fn");
+		zone!(colored, "generated", crate::Color::RED, file: "generated.dsl", line: LINE, function: "generated_fn");
+		colored.text("from a generator");
+		zone_l3!("generated", file: "generated.dsl", line: LINE, function: "generated_fn");
+		let script = crate::Zone::begin_at("update player", "update", "player.lua", 42);
+		script.text("from a script");
+	}
+
+	#[test]
+	fn lazy_zone_text() {
+		#[cfg(feature = "enabled")]
+		let tracy = start_capture();
+		let mut built = false;
+		zone!(culling, "Culling");
+		culling.text_lazy(|| {
+			built = true;
+			"3 objects"
+		});
+		// Tests never have a Tracy server connected.
+		#[cfg(feature = "enabled")]
+		assert!(!tracy.is_connected());
+		assert!(!built);
+	}
+
+	#[test]
+	fn renamed_zone() {
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		zone!(job, "Job");
+		job.set_name("Decompress");
+		job.set_name("Decompress & verify");
+	}
+
+	#[cfg(all(feature = "enabled", feature = "function-names"))]
+	#[test]
+	fn stable_function_names() {
+		let locations = [(); 2].map(|_| zone!(@loc "named", Color::UNSPECIFIED));
+		// Resolved only once.
+		assert!(std::ptr::eq(locations[0], locations[1]));
+		// SAFETY: Function names are static & null-terminated.
+		let function = unsafe { std::ffi::CStr::from_ptr(locations[0].0.function) };
+		assert!(function.to_str().unwrap().ends_with("tests::stable_function_names::{{closure}}"), "{function:?}");
+
+		let _tracy = start_capture();
+		#[cfg(not(feature = "no-dynamic-strings"))]
+		zone_dynamic!("dynamic");
+	}
+
+	#[test]
+	fn constant_zone_names() {
+		// Clashes with the location constants of the macro.
+		const NAME: &str = "shared";
+		mod names {
+			pub const LOADING: &str = "loading";
+		}
+
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		zone!(NAME);
+		zone!(const names::LOADING);
+		zone!(loading, const names::LOADING, crate::Color::GREEN);
+		loading.text("textures");
+		zone_l3!(const NAME);
+	}
+
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	#[test]
+	fn formatted_text() {
+		struct Nested;
+		impl std::fmt::Display for Nested {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				zone!(nested, "nested");
+				nested.text_fmt(format_args!("{}", 42));
+				f.write_str("nested")
+			}
+		}
+
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		zone!(zone, "formatted");
+		zone.text_fmt(format_args!("static"));
+		zone.text_fmt(format_args!("{} & {}", 1, Nested));
+	}
+
+	#[cfg(not(feature = "no-callstack"))]
+	#[test]
+	fn callstack_zones() {
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		zone!("callstack", callstack: 4);
+		zone!(named, "callstack", callstack: 1);
+		named.text("named");
+		zone!("callstack", crate::Color::RED, callstack: 8);
+		zone!("callstack", 0xFF0000, enabled: true, callstack: 62);
+		zone!("callstack", enabled: false, callstack: 2);
+		zone_l3!(deep, "callstack", callstack: 4);
+		zone!("early return", end_callstack: 4);
+		zone!(early, "early return", crate::Color::RED, end_callstack: 8);
+		early.text("returned");
+		zone_l3!("early return", end_callstack: 2);
+		deep.text("deep");
+	}
+
+	#[cfg(all(feature = "enabled", feature = "zone-end-callstack"))]
+	#[test]
+	fn zone_end_callstacks() {
+		use crate::record::Event::*;
+
+		let _tracy = start_capture();
+		set_zone_end_callstack_depth(4);
+		zone!(outer, "outer");
+		zone!(inner, "inner");
+		let events = crate::record::record(|| {
+			drop(inner);
+			drop(outer);
+		});
+		set_zone_end_callstack_depth(16);
+		assert_eq!(events, [Message("inner end".into()), Message("outer end".into())]);
+	}
+
+	#[cfg(not(feature = "no-callstack"))]
+	#[test]
+	fn callstack_messages() {
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		let messages = || {
+			message!("literal", callstack: 4);
+			message!(crate::Color::RED, "colored", callstack: 8);
+			message!(crate::MessageLevel::Error.color(), "leveled", callstack: 62);
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			{
+				let path = String::from("level.bin");
+				message!(&path, callstack: 2);
+				message!(crate::Color::RED, &path, callstack: 2);
+				message!("loading {}", path, callstack: 4);
+				message!(crate::Color::RED, "loading {} of {}", 1, path, callstack: 4);
+				message!("loading {}", path);
+			}
+		};
+		messages();
+
+		#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+		{
+			use crate::record::Event::*;
+
+			assert_eq!(crate::record::record(messages), [
+				Message("literal".into()),
+				Message("colored".into()),
+				Message("leveled".into()),
+				Message("level.bin".into()),
+				Message("level.bin".into()),
+				Message("loading level.bin".into()),
+				Message("loading 1 of level.bin".into()),
+				Message("loading level.bin".into()),
+			]);
+		}
+	}
+
+	#[test]
+	fn runtime_colors() {
+		let zones = || {
+			for color in [crate::Color::RED, crate::Color::GREEN] {
+				zone!("colored", color: color);
+				zone!(colored, "colored", color: crate::Color::from_u32(0x102030));
+				colored.text("runtime");
+				zone_l3!("colored l3", color: color);
+			}
+		};
+		#[cfg(not(feature = "enabled"))]
+		zones();
+
+		#[cfg(feature = "enabled")]
+		{
+			use crate::record::Event::*;
+
+			let _tracy = start_capture();
+			let events = crate::record::record(zones);
+			let colors = |name: &str| -> Vec<u32> {
+				events
+					.windows(2)
+					.filter_map(|pair| match pair {
+						[ZoneBegin(n), ZoneColor(color)] if n == name => Some(*color),
+						_ => None,
+					})
+					.collect()
+			};
+			let (red, green) = (crate::Color::RED.as_u32(), crate::Color::GREEN.as_u32());
+			assert_eq!(colors("colored"), [red, 0x102030, green, 0x102030], "{events:?}");
+			// Unless the level is filtered out.
+			let l3 = colors("colored l3");
+			assert!(l3.len() <= 2 && l3.iter().all(|color| [red, green].contains(color)), "{events:?}");
+		}
+	}
+
+	#[test]
+	fn ended_zones() {
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		zone!(sleeping, "sleeping", timed);
+		sleeping.text("timed");
+		std::thread::sleep(std::time::Duration::from_millis(2));
+		let spent = sleeping.end();
+		zone_l3!(deep, "deep", crate::Color::RED, timed);
+		_ = deep.end();
+		#[cfg(feature = "enabled")]
+		assert!(spent >= std::time::Duration::from_millis(2));
+		#[cfg(not(feature = "enabled"))]
+		assert_eq!(spent, std::time::Duration::ZERO);
+		zone!("after the end");
+	}
+
+	#[test]
+	#[cfg(all(feature = "enabled", feature = "shared-zone-locations", not(feature = "function-names")))]
+	fn shared_zone_locations() {
+		macro_rules! unrolled {
+			() => {
+				[zone!(@loc "unrolled", crate::Color::UNSPECIFIED), zone!(@loc "unrolled", crate::Color::UNSPECIFIED)]
+			};
+		}
+		// Merging is up to the compiler, only the data is the same.
+		let [a, b] = unrolled!();
+		assert_eq!((a.name(), a.line()), (b.name(), b.line()));
+		let other = zone!(@loc "unrolled", crate::Color::RED);
+		assert!(!std::ptr::eq(a, other));
+	}
+
+	#[test]
+	fn cstr_zones() {
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		let name: &'static std::ffi::CStr = c"from C";
+		zone_cstr!(zone, name);
+		zone.color(crate::Color::RED);
+		zone_cstr!(c"nested from C");
+		let _again = crate::Zone::begin_from_cstr(name);
+
+		#[cfg(feature = "enabled")]
+		{
+			let site  = std::panic::Location::caller();
+			let first = cstr_location(name, c"test", site).unwrap();
+			assert!(std::ptr::eq(first, cstr_location(name, c"test", site).unwrap()));
+			assert!(!std::ptr::eq(first, cstr_location(c"other", c"test", site).unwrap()));
+			assert_eq!(first.name(), "from C");
+			assert_eq!(first.function(), "test");
+		}
+	}
+
+	#[test]
+	fn rate_limited_zones() {
+		let zones = || {
+			for _ in 0..3 {
+				zone!("rate limited", max_hz: 60);
+				zone!(limited, "rate limited", crate::Color::RED, max_hz: 1);
+				limited.text("limited");
+				zone_l3!("rate limited l3", max_hz: 30);
+			}
+		};
+		#[cfg(not(feature = "enabled"))]
+		zones();
+
+		#[cfg(feature = "enabled")]
+		{
+			use crate::record::Event::*;
+
+			let _tracy = start_capture();
+			let events = crate::record::record(zones);
+			let begins = |name: &str| events.iter().filter(|event| matches!(event, ZoneBegin(n) if n == name)).count();
+			// Only the first iteration is admitted by every site.
+			assert_eq!(begins("rate limited"), 2, "{events:?}");
+			// Unless the level is filtered out.
+			assert!(begins("rate limited l3") <= 1, "{events:?}");
+		}
+	}
+
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	#[test]
+	fn dynamic_zones() {
+		#[cfg(feature = "enabled")]
+		let _tracy = start_capture();
+		let name = format!("job {}", 42);
+		zone_dynamic!(job, name);
+		job.text("dynamic");
+		zone_dynamic!(name.as_str());
+		let _zone = crate::Zone::begin_dynamic(&name);
+		#[cfg(all(feature = "enabled", feature = "zone-path"))]
+		assert_eq!(zone_path::current().as_deref(), Some("job 42 > job 42 > job 42"));
+	}
+
 	#[cfg(feature = "enabled")]
 	#[test]
 	#[should_panic]
@@ -1036,4 +4554,192 @@ mod tests {
 		let _tracy1 = start_capture();
 		let _tracy2 = start_capture();
 	}
+
+	// Macro expansions define items (statics, consts & structs) right
+	// in the instrumented function, which must not conflict with
+	// generics, `impl Trait` & friends.
+	mod signatures {
+		use std::fmt::Display;
+
+		use crate::*;
+
+		fn everything<T: Display>(value: T) -> T {
+			zone!("everything");
+			zone!(named, "everything", Color::RED);
+			named.text("named");
+			#[cfg(not(feature = "no-callstack"))]
+			zone!("everything", enabled: true, callstack: 1);
+			zone!(lazy "everything", min: 1us);
+			zone_l2!("everything");
+			zone_warn_slow!("everything", 1s);
+			frame!("everything");
+			message!("everything");
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			{
+				zone_dynamic!("everything");
+				message!("{value}");
+				message_static!(32, "{value}");
+			}
+			plot!("everything", 1.0);
+			make_plot!(plot, "everything plot", PlotConfig::default());
+			plot!(plot, 1);
+			value
+		}
+
+		fn const_generic<const N: usize>() -> [u8; N] {
+			everything(N);
+			zone!("const generic");
+			[0; N]
+		}
+
+		fn const_generic_impl_trait<const N: usize, T: Copy + Display>(x: [T; N]) -> impl Iterator<Item = T> {
+			zone!("const generic impl trait");
+			x.into_iter().map(everything)
+		}
+
+		fn impl_trait_arg(x: impl Display) -> impl Display {
+			zone!("impl trait arg");
+			everything(x)
+		}
+
+		fn returns_closure<const N: usize>() -> impl Fn(usize) -> usize {
+			zone!("returns closure");
+			move |x| {
+				zone!("closure");
+				everything(x + N)
+			}
+		}
+
+		fn lifetimes<'a, 'b: 'a>(a: &'a str, b: &'b str) -> &'a str where 'a: 'a {
+			zone!("lifetimes");
+			if a.len() > b.len() { everything(a) } else { everything(b) }
+		}
+
+		struct Buf<const N: usize>([u8; N]);
+
+		impl<const N: usize> Buf<N> {
+			fn bytes(&self) -> impl Iterator<Item = &u8> + '_ {
+				zone!("bytes");
+				fn nested<const M: usize>() -> usize {
+					zone!("nested");
+					everything(M)
+				}
+				self.0.iter().take(nested::<N>())
+			}
+		}
+
+		#[cfg(feature = "attributes")]
+		mod attributes {
+			use std::fmt::Display;
+
+			use crate::instrument;
+
+			#[instrument(crate = crate)]
+			pub fn const_generic<const N: usize>() -> [u8; N] {
+				[0; N]
+			}
+
+			#[instrument("signatures", crate = crate)]
+			pub fn const_generic_impl_trait<const N: usize, T: Copy + Display>(x: [T; N]) -> impl Iterator<Item = T> {
+				x.into_iter()
+			}
+
+			#[instrument(crate = crate)]
+			pub fn impl_trait_arg(x: impl Display) -> impl Display {
+				x
+			}
+
+			#[instrument(crate = crate)]
+			pub fn returns_closure<const N: usize>() -> impl Fn(usize) -> usize {
+				move |x| x + N
+			}
+
+			#[instrument(crate = crate)]
+			pub fn lifetimes<'a, 'b: 'a>(a: &'a str, b: &'b str) -> &'a str where 'a: 'a {
+				if a.len() > b.len() { a } else { b }
+			}
+
+			pub struct Buf<const N: usize>(pub [u8; N]);
+
+			impl<const N: usize> Buf<N> {
+				#[instrument(crate = crate)]
+				pub fn bytes(&self) -> impl Iterator<Item = &u8> + '_ {
+					self.0.iter()
+				}
+
+				#[instrument(crate = crate)]
+				pub unsafe fn r#unsafe<const M: usize>(&self) -> [u8; M] where [u8; M]: Default {
+					Default::default()
+				}
+			}
+
+			#[instrument(crate = crate)]
+			pub extern "C" fn extern_c() -> u32 {
+				42
+			}
+
+			#[instrument(frame = "IO", crate = crate)]
+			pub fn framed(bytes: usize) -> usize {
+				bytes
+			}
+
+			#[instrument("callbacks", zone = false, frame = "Audio", crate = crate)]
+			pub fn frame_only() -> bool {
+				true
+			}
+
+			#[cfg(feature = "migration-shims")]
+			#[instrument(name = "traced load", skip(bytes), fields(size = bytes.len()), level = "debug", err, crate = crate)]
+			pub fn traced(bytes: &[u8]) -> Result<usize, ()> {
+				Ok(bytes.len())
+			}
+		}
+
+		#[test]
+		fn exotic_signatures() {
+			#[cfg(feature = "enabled")]
+			let _tracy = start_capture();
+			assert_eq!(const_generic::<3>(), [0; 3]);
+			assert_eq!(const_generic_impl_trait([1, 2]).sum::<i32>(), 3);
+			assert_eq!(impl_trait_arg("x").to_string(), "x");
+			assert_eq!(returns_closure::<1>()(1), 2);
+			assert_eq!(lifetimes("a", "bb"), "bb");
+			assert_eq!(Buf([1, 2, 3]).bytes().count(), 3);
+
+			#[cfg(feature = "attributes")]
+			{
+				assert_eq!(attributes::const_generic::<3>(), [0; 3]);
+				assert_eq!(attributes::const_generic_impl_trait([1, 2]).sum::<i32>(), 3);
+				assert_eq!(attributes::impl_trait_arg("x").to_string(), "x");
+				assert_eq!(attributes::returns_closure::<1>()(1), 2);
+				assert_eq!(attributes::lifetimes("a", "bb"), "bb");
+				let buf = attributes::Buf([1, 2, 3]);
+				assert_eq!(buf.bytes().count(), 3);
+				// SAFETY: Nothing unsafe is going on.
+				assert_eq!(unsafe { buf.r#unsafe::<2>() }, [0; 2]);
+				assert_eq!(attributes::extern_c(), 42);
+				assert_eq!(attributes::framed(16), 16);
+				assert!(attributes::frame_only());
+			}
+
+			#[cfg(all(feature = "attributes", feature = "enabled"))]
+			{
+				use record::Event::*;
+
+				let events = record::record(|| _ = attributes::frame_only());
+				assert_eq!(events, [FrameStart("Audio".into()), FrameEnd("Audio".into())]);
+				let events = record::record(|| _ = attributes::framed(16));
+				assert!(matches!(
+					&events[..],
+					[FrameStart(frame), ZoneBegin(_), ZoneEnd, FrameEnd(_)] if frame == "IO",
+				));
+
+				#[cfg(feature = "migration-shims")]
+				{
+					let events = record::record(|| _ = attributes::traced(&[1, 2]));
+					assert_eq!(events, [ZoneBegin("traced load".into()), ZoneEnd]);
+				}
+			}
+		}
+	}
 }
@@ -0,0 +1,129 @@
+//! Detection of zones ended out of order in debug builds.
+//!
+//! Tracy requires zones of a thread to end in the reverse order of
+//! their beginning, otherwise the capture is silently corrupted. It
+//! happens, when a zone guard is `mem::forget`-ed, guards are dropped
+//! manually in the wrong order or a zone is kept across a suspension
+//! point of a generator or a future. Active zones are recorded in a
+//! thread-local stack of their ids, so such mistakes are reported via
+//! a red message and a panic, unless the thread is already panicking.
+//!
+//! Zones without a Tracy id, i.e. lazy & manual ones, are recorded
+//! with the id 0, so they are checked only against the others. Zones
+//! of fibers are recorded separately, as fibers could be suspended &
+//! resumed on any thread.
+
+use std::cell::RefCell;
+#[cfg(feature = "fibers")]
+use std::collections::HashMap;
+#[cfg(feature = "fibers")]
+use std::sync::Mutex;
+
+use crate::Color;
+
+thread_local! {
+	static ACTIVE: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
+	/// Zones of the thread itself, while it runs a fiber.
+	#[cfg(feature = "fibers")]
+	static SUSPENDED: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Zones of the fibers, which are not running, by the fiber name.
+#[cfg(feature = "fibers")]
+static FIBERS: Mutex<Option<HashMap<usize, Vec<u32>>>> = Mutex::new(None);
+
+/// Switches the current thread to zones of the entered fiber.
+#[cfg(feature = "fibers")]
+pub(crate) fn enter_fiber(name: &'static std::ffi::CStr) {
+	let mut fibers = FIBERS.lock().unwrap_or_else(|e| e.into_inner());
+	let zones      = fibers.get_or_insert_with(HashMap::new).remove(&(name.as_ptr() as usize)).unwrap_or_default();
+	let own        = ACTIVE.replace(zones);
+	SUSPENDED.set(own);
+}
+
+/// Switches the current thread back to its own zones.
+#[cfg(feature = "fibers")]
+pub(crate) fn leave_fiber(name: &'static std::ffi::CStr) {
+	let zones = ACTIVE.replace(SUSPENDED.take());
+	if !zones.is_empty() {
+		let mut fibers = FIBERS.lock().unwrap_or_else(|e| e.into_inner());
+		fibers.get_or_insert_with(HashMap::new).insert(name.as_ptr() as usize, zones);
+	}
+}
+
+/// Records that the zone is entered.
+#[inline(always)]
+pub(crate) fn enter(ctx: sys::TracyCZoneCtx) {
+	if ctx.active != 0 {
+		ACTIVE.with_borrow_mut(|active| active.push(ctx.id));
+	}
+}
+
+/// Records that the zone is exited & checks it is the innermost one.
+#[inline(always)]
+pub(crate) fn leave(ctx: sys::TracyCZoneCtx) {
+	if ctx.active == 0 {
+		return;
+	}
+	let innermost = ACTIVE.with_borrow_mut(|active| {
+		let innermost = active.last() == Some(&ctx.id);
+		// The stack is fixed up, so only the offending zone is
+		// reported.
+		if let Some(at) = active.iter().rposition(|&id| id == ctx.id) {
+			active.remove(at);
+		}
+		innermost
+	});
+	if !innermost {
+		report();
+	}
+}
+
+#[cold]
+fn report() {
+	// SAFETY: The text is static & null-terminated.
+	unsafe {
+		crate::details::message_color(
+			c"Zone ended out of order, the capture is corrupted. Zones must end in LIFO order.".as_ptr().cast(),
+			Color::RED,
+		);
+	}
+	if !std::thread::panicking() {
+		panic!("Tracy zone ended out of order, zones of a thread must end in the reverse order of their beginning.");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn ordered_zones() {
+		let _tracy = start_capture();
+		zone!(outer, "outer");
+		zone!(inner, "inner");
+		drop(inner);
+		drop(outer);
+		zone!("next");
+	}
+
+	#[test]
+	#[should_panic(expected = "zone ended out of order")]
+	fn reordered_zones() {
+		let _tracy = start_capture();
+		zone!(outer, "outer");
+		zone!(inner, "inner");
+		drop(outer);
+		drop(inner);
+	}
+
+	#[test]
+	#[should_panic(expected = "zone ended out of order")]
+	fn forgotten_zones() {
+		let _tracy = start_capture();
+		zone!(outer, "outer");
+		zone!(inner, "inner");
+		std::mem::forget(inner);
+		drop(outer);
+	}
+}
@@ -0,0 +1,170 @@
+//! Compile-time checks of the instrumentation macro arguments.
+//!
+//! It is an implementation detail and can be changed at any moment.
+
+use std::time::Duration;
+
+use crate::Color;
+
+/// Default maximum name length, when it is not configured via the
+/// `TRACY_GIZMOS_MAX_NAME_LENGTH` environment variable.
+const DEFAULT_MAX_NAME_LENGTH: usize = 64;
+
+/// Maximum allowed length of zone, plot & frame names, if the
+/// `name-length-check` feature is enabled.
+pub const MAX_NAME_LENGTH: Option<usize> = if cfg!(feature = "name-length-check") {
+	match option_env!("TRACY_GIZMOS_MAX_NAME_LENGTH") {
+		Some(max) => Some(parse_usize(max)),
+		None      => Some(DEFAULT_MAX_NAME_LENGTH),
+	}
+} else {
+	None
+};
+
+/// Fails the constant evaluation, if the name is too long.
+///
+/// Used inside `const` items in macro expansions, so it results in a
+/// compilation error at the call site.
+#[inline(always)]
+pub const fn check_name_length(name: &str) {
+	if let Some(max) = MAX_NAME_LENGTH {
+		if name.len() > max {
+			panic!("Tracy name is too long, refer to `name-length-check` feature.");
+		}
+	}
+}
+
+const fn parse_usize(s: &str) -> usize {
+	let bytes = s.as_bytes();
+	if bytes.is_empty() {
+		panic!("TRACY_GIZMOS_MAX_NAME_LENGTH should be a positive integer.");
+	}
+
+	let mut value = 0;
+	let mut i     = 0;
+	while i < bytes.len() {
+		let digit = bytes[i];
+		if !digit.is_ascii_digit() {
+			panic!("TRACY_GIZMOS_MAX_NAME_LENGTH should be a positive integer.");
+		}
+		value = value * 10 + (digit - b'0') as usize;
+		i += 1;
+	}
+	value
+}
+
+/// Maximum callstack depth supported by Tracy.
+pub const MAX_CALLSTACK_DEPTH: u32 = 62;
+
+/// Fails the constant evaluation, if the callstack depth is not
+/// supported by Tracy, otherwise returns it as expected by the C API.
+pub const fn check_callstack_depth(depth: u32) -> i32 {
+	if depth == 0 || depth > MAX_CALLSTACK_DEPTH {
+		panic!("Callstack depth should be within 1..=62.");
+	}
+	depth as i32
+}
+
+/// Fails the constant evaluation, if the integer color literal is
+/// not `0xRRGGBB`, otherwise returns it as a [`Color`].
+pub const fn check_color_literal(rgb: u32) -> Color {
+	if rgb > 0xFFFFFF {
+		panic!("Color literal should be `0xRRGGBB`.");
+	}
+	Color::from_u32(rgb)
+}
+
+/// Parses a duration literal with a unit suffix, e.g. `5us`.
+///
+/// Supported units are `ns`, `us`, `ms` and `s`. Digits could be
+/// separated with `_`. Used inside `const` items in macro expansions,
+/// so a malformed literal results in a compilation error at the call
+/// site.
+pub const fn parse_duration(literal: &str) -> Duration {
+	let bytes     = literal.as_bytes();
+	let mut value = 0u64;
+	let mut i     = 0;
+	while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'_') {
+		if bytes[i] != b'_' {
+			value = value * 10 + (bytes[i] - b'0') as u64;
+		}
+		i += 1;
+	}
+	if i == 0 {
+		panic!("Duration should start with a number, e.g. `5us`.");
+	}
+
+	match bytes.split_at(i).1 {
+		b"ns" => Duration::from_nanos(value),
+		b"us" => Duration::from_micros(value),
+		b"ms" => Duration::from_millis(value),
+		b"s"  => Duration::from_secs(value),
+		_     => panic!("Duration unit should be one of `ns`, `us`, `ms` or `s`, e.g. `5us`."),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_limits() {
+		assert_eq!(parse_usize("0"),   0);
+		assert_eq!(parse_usize("128"), 128);
+	}
+
+	#[test]
+	#[should_panic]
+	fn rejects_garbage_limits() {
+		parse_usize("12a");
+	}
+
+	#[cfg(feature = "name-length-check")]
+	#[test]
+	#[should_panic]
+	fn rejects_long_names() {
+		check_name_length(&"x".repeat(MAX_NAME_LENGTH.unwrap() + 1));
+	}
+
+	#[test]
+	fn accepts_short_names() {
+		check_name_length("short");
+	}
+
+	#[test]
+	fn checks_callstack_depth() {
+		assert_eq!(check_callstack_depth(1),  1);
+		assert_eq!(check_callstack_depth(62), 62);
+	}
+
+	#[test]
+	#[should_panic]
+	fn rejects_too_deep_callstacks() {
+		check_callstack_depth(MAX_CALLSTACK_DEPTH + 1);
+	}
+
+	#[test]
+	fn checks_color_literals() {
+		assert_eq!(check_color_literal(0xFF8000).as_u32(), 0xFF8000);
+	}
+
+	#[test]
+	#[should_panic]
+	fn rejects_wide_color_literals() {
+		check_color_literal(0x1000000);
+	}
+
+	#[test]
+	fn parses_durations() {
+		assert_eq!(parse_duration("5us"),     Duration::from_micros(5));
+		assert_eq!(parse_duration("1_500ns"), Duration::from_nanos(1500));
+		assert_eq!(parse_duration("16ms"),    Duration::from_millis(16));
+		assert_eq!(parse_duration("2s"),      Duration::from_secs(2));
+	}
+
+	#[test]
+	#[should_panic]
+	fn rejects_durations_without_unit() {
+		parse_duration("5");
+	}
+}
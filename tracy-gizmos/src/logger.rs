@@ -0,0 +1,107 @@
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::MessageLevel;
+
+/// `log` backend, which forwards records to Tracy's log.
+///
+/// Every record is sent as a message, colored by its level, same as
+/// [`message!`] with a [`MessageLevel`] does, and prefixed with its
+/// target, e.g. `app::net: connected`. Levels compiled out via
+/// `max-level-*` features are filtered out.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// TracyLogger::init().unwrap();
+/// let _tracy = start_capture();
+/// log::warn!("Asset is missing, falling back.");
+/// ```
+///
+/// [`message!`]: crate::message!
+pub struct TracyLogger;
+
+impl TracyLogger {
+	/// Sets the logger as the `log` backend.
+	///
+	/// # Errors
+	///
+	/// Fails if the `log` backend is set already.
+	pub fn init() -> Result<(), SetLoggerError> {
+		static LOGGER: TracyLogger = TracyLogger;
+
+		log::set_logger(&LOGGER)?;
+		log::set_max_level(max_level());
+		Ok(())
+	}
+}
+
+impl Log for TracyLogger {
+	fn enabled(&self, metadata: &Metadata) -> bool {
+		message_level(metadata.level()).is_enabled()
+	}
+
+	fn log(&self, record: &Record) {
+		#[cfg(feature = "enabled")]
+		if self.enabled(record.metadata()) {
+			let mut text = format!("{}: {}", record.target(), record.args());
+			crate::details::truncate_message(&mut text);
+			crate::details::message_size_color(&text, message_level(record.level()).color());
+		}
+		#[cfg(not(feature = "enabled"))]
+		{
+			// Silences unused variable warning.
+			_ = record;
+		}
+	}
+
+	fn flush(&self) {}
+}
+
+fn message_level(level: Level) -> MessageLevel {
+	match level {
+		Level::Error => MessageLevel::Error,
+		Level::Warn  => MessageLevel::Warn,
+		Level::Info  => MessageLevel::Info,
+		Level::Debug => MessageLevel::Debug,
+		Level::Trace => MessageLevel::Trace,
+	}
+}
+
+/// Returns the most verbose level, which is compiled in.
+fn max_level() -> LevelFilter {
+	Level::iter()
+		.filter(|level| message_level(*level).is_enabled())
+		.last()
+		.map_or(LevelFilter::Off, |level| level.to_level_filter())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn log_records() {
+		let record = |level| {
+			TracyLogger.log(&Record::builder()
+				.level(level)
+				.target("app::net")
+				.args(format_args!("connected"))
+				.build());
+		};
+		record(Level::Info);
+		assert_eq!(max_level() == LevelFilter::Trace, MessageLevel::Trace.is_enabled());
+
+		#[cfg(feature = "enabled")]
+		{
+			use crate::record::Event::*;
+
+			let events = crate::record::record(|| {
+				record(Level::Error);
+				record(Level::Trace);
+			});
+			assert_eq!(events[0], Message("app::net: connected".into()));
+			assert_eq!(events.len(), if MessageLevel::Trace.is_enabled() { 2 } else { 1 });
+		}
+	}
+}
@@ -0,0 +1,210 @@
+use std::marker::PhantomData;
+use std::time::Instant;
+
+use crate::ZoneLocation;
+
+/// Begins a [`ManualZone`], which started at the given [`Instant`].
+///
+/// Optionally, a custom [`Color`] could be assigned to the zone.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::time::Instant;
+/// # struct Job { started: Instant, finished: Instant }
+/// # fn finished_jobs() -> Vec<Job> { vec![] }
+/// for job in finished_jobs() {
+///     manual_zone!("job", Color::ORANGE, job.started).end_at(job.finished);
+/// }
+/// ```
+///
+/// [`Color`]: crate::Color
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! manual_zone {
+	($name:literal, $time:expr) => {
+		$crate::manual_zone!($name, $crate::Color::UNSPECIFIED, $time)
+	};
+	($name:literal, $color:expr, $time:expr) => {
+		$crate::ManualZone::begin_at($crate::zone!(@loc $name, $color), $time)
+	};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! manual_zone {
+	($name:literal, $time:expr) => {{
+		let _: ::std::time::Instant = $time;
+		$crate::ManualZone::compiled_out()
+	}};
+	($name:literal, $color:expr, $time:expr) => {{
+		// Silences unused `Color` import warning.
+		_ = $color;
+		$crate::manual_zone!($name, $time)
+	}};
+}
+
+/// A zone with explicit begin & end timestamps.
+///
+/// Work, which is timed externally, e.g. post-processed on a
+/// different thread or reported by a device, could be placed on the
+/// current thread's timeline this way. Timestamps are converted via
+/// [`to_tracy_time`], refer to it for the calibration details.
+///
+/// Manual zones could be nested the same way as regular zones, but
+/// Tracy expects every thread's timeline to go forward. Hence, the
+/// begin timestamp must not precede anything already emitted by the
+/// current thread, and nested zones must fit their parents.
+///
+/// If the zone is dropped without [`ManualZone::end_at`], it ends at
+/// the moment of drop.
+///
+/// Refer to [`manual_zone!`] for the usage how-to.
+///
+/// [`to_tracy_time`]: crate::to_tracy_time
+pub struct ManualZone {
+	#[cfg(feature = "enabled")]
+	active:   bool,
+	/// The zone was recorded, so its end is recorded as well.
+	#[cfg(all(test, feature = "enabled"))]
+	recorded: bool,
+	_unsend:  PhantomData<*mut ()>,
+}
+
+// Tracy doesn't verify zones (`TRACY_NO_VERIFY`), so only the
+// activity flag matters.
+#[cfg(feature = "enabled")]
+const ACTIVE: sys::TracyCZoneCtx = sys::TracyCZoneCtx { id: 0, active: 1 };
+
+impl ManualZone {
+	/// Begins a zone at the given location, which started at the
+	/// given moment.
+	pub fn begin_at(location: &'static ZoneLocation, time: Instant) -> Self {
+		#[cfg(feature = "enabled")]
+		{
+			#[cfg(test)]
+			// SAFETY: Location name is static & null-terminated.
+			if unsafe { crate::record::location_begin(location) } {
+				return Self { active: false, recorded: true, _unsend: PhantomData };
+			}
+			let Some(emission) = crate::emission() else {
+				return Self::compiled_out();
+			};
+			let time = crate::to_tracy_time(time);
+			crate::lazy::flush();
+			// SAFETY: Location is static.
+			unsafe {
+				sys::___tracy_gizmos_emit_zone_begin_at(&location.0, time);
+			}
+			// User hooks must not hold the shutdown back.
+			drop(emission);
+			crate::zone_path::enter(location.0.name, ACTIVE);
+			#[cfg(debug_assertions)]
+			crate::lifo::enter(ACTIVE);
+			#[cfg(feature = "zone-hooks")]
+			crate::hooks::enter(Some(location), ACTIVE);
+			crate::self_profile::count_event();
+			Self { active: true, ..Self::compiled_out() }
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		Self::compiled_out()
+	}
+
+	#[doc(hidden)]
+	pub const fn compiled_out() -> Self {
+		Self {
+			#[cfg(feature = "enabled")]
+			active:   false,
+			#[cfg(all(test, feature = "enabled"))]
+			recorded: false,
+			_unsend:  PhantomData,
+		}
+	}
+
+	/// Ends the zone, which finished at the given moment.
+	pub fn end_at(self, time: Instant) {
+		#[cfg(feature = "enabled")]
+		self.end(crate::to_tracy_time(time));
+		std::mem::forget(self);
+	}
+
+	#[cfg(feature = "enabled")]
+	fn end(&self, time: i64) {
+		#[cfg(test)]
+		if self.recorded {
+			crate::record::push(|| crate::record::Event::ZoneEnd);
+			return;
+		}
+		if !self.active {
+			return;
+		}
+		crate::zone_path::leave(ACTIVE);
+		#[cfg(debug_assertions)]
+		crate::lifo::leave(ACTIVE);
+		#[cfg(feature = "zone-hooks")]
+		crate::hooks::leave(ACTIVE);
+		let Some(_emission) = crate::emission() else {
+			return;
+		};
+		// SAFETY: The zone begin was emitted by `begin_at`.
+		unsafe {
+			sys::___tracy_gizmos_emit_zone_end_at(time);
+		}
+		crate::self_profile::count_event();
+	}
+}
+
+impl Drop for ManualZone {
+	fn drop(&mut self) {
+		#[cfg(feature = "enabled")]
+		// SAFETY: The Tracy clock is usable regardless of the profiler
+		// state.
+		self.end(unsafe { sys::___tracy_gizmos_time() });
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use super::*;
+
+	#[test]
+	fn manual_zones() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let started = Instant::now();
+		let outer   = manual_zone!("outer", started);
+		manual_zone!("inner", crate::Color::RED, started + Duration::from_micros(10))
+			.end_at(started + Duration::from_micros(20));
+		outer.end_at(started + Duration::from_micros(30));
+		let _dropped = manual_zone!("dropped", Instant::now());
+	}
+
+	#[cfg(feature = "enabled")]
+	#[test]
+	fn recorded_manual_zones() {
+		use crate::record::Event::*;
+
+		let started = Instant::now();
+		let events  = crate::record::record(|| {
+			let outer = manual_zone!("outer", started);
+			manual_zone!("inner", crate::Color::RED, started + Duration::from_micros(10))
+				.end_at(started + Duration::from_micros(20));
+			outer.end_at(started + Duration::from_micros(30));
+			// Ends exactly once, when dropped.
+			let _dropped = manual_zone!("dropped", Instant::now());
+		});
+		assert_eq!(events, [
+			ZoneBegin("outer".into()),
+			ZoneBegin("inner".into()),
+			ZoneColor(crate::Color::RED.as_u32()),
+			ZoneEnd,
+			ZoneEnd,
+			ZoneBegin("dropped".into()),
+			ZoneEnd,
+		]);
+	}
+}
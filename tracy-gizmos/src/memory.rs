@@ -1,3 +1,7 @@
+use std::ffi::CStr;
+#[cfg(feature = "enabled")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
 /// Marks a memory allocation event.
 ///
 /// Tracy can monitor the memory usage of your application. Knowledge
@@ -124,6 +128,144 @@ macro_rules! emit_free {
 	};
 }
 
+/// A named memory pool, which allocations are tracked both by Tracy
+/// and client-side.
+///
+/// It is an alternative to [`emit_alloc!`] & [`emit_free!`] for the
+/// allocators, which know the freed size, e.g. the ones implementing
+/// [`GlobalAlloc`]. Besides emitting the events, the pool maintains
+/// its usage statistics, so tools can print end-of-run memory
+/// summaries alongside what Tracy recorded, refer to
+/// [`MemoryPool::snapshot`].
+///
+/// The same dangers as for [`emit_alloc!`] apply.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn allocate(size: usize) -> *mut u8 { todo!() }
+/// # fn deallocate(ptr: *mut u8, size: usize) { todo!() }
+/// static SCRATCH: MemoryPool = MemoryPool::new(c"scratch");
+///
+/// let buf = allocate(1024);
+/// SCRATCH.alloc(buf, 1024);
+/// // ... work with buf ...
+/// SCRATCH.free(buf, 1024);
+/// deallocate(buf, 1024);
+///
+/// let stats = SCRATCH.snapshot();
+/// println!("scratch peak: {} bytes", stats.peak_bytes);
+/// ```
+///
+/// [`GlobalAlloc`]: std::alloc::GlobalAlloc
+pub struct MemoryPool {
+	#[cfg(feature = "enabled")]
+	name:        &'static CStr,
+	#[cfg(feature = "enabled")]
+	live_allocs: AtomicU64,
+	#[cfg(feature = "enabled")]
+	live_bytes:  AtomicU64,
+	#[cfg(feature = "enabled")]
+	peak_bytes:  AtomicU64,
+}
+
+/// Usage statistics of the [`MemoryPool`].
+///
+/// They are always zero, unless `enabled` feature is on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PoolStats {
+	/// Amount of allocations, which are not freed yet.
+	pub live_allocs: u64,
+	/// Size of allocations, which are not freed yet.
+	pub live_bytes:  u64,
+	/// The highest `live_bytes` so far.
+	pub peak_bytes:  u64,
+}
+
+impl MemoryPool {
+	/// Creates the memory pool of the given name.
+	pub const fn new(name: &'static CStr) -> Self {
+		Self {
+			#[cfg(feature = "enabled")]
+			name,
+			#[cfg(feature = "enabled")]
+			live_allocs: AtomicU64::new(0),
+			#[cfg(feature = "enabled")]
+			live_bytes:  AtomicU64::new(0),
+			#[cfg(feature = "enabled")]
+			peak_bytes:  AtomicU64::new(0),
+		}
+	}
+
+	/// Marks the memory allocation of the given size.
+	#[inline(always)]
+	// Tracy only records the address, it never dereferences it.
+	#[allow(clippy::not_unsafe_ptr_arg_deref)]
+	pub fn alloc<T>(&self, ptr: *const T, size: usize) {
+		#[cfg(feature = "enabled")]
+		{
+			self.live_allocs.fetch_add(1, Ordering::Relaxed);
+			let live = self.live_bytes.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+			self.peak_bytes.fetch_max(live, Ordering::Relaxed);
+			// SAFETY: The pool name is static & null-terminated.
+			unsafe { crate::details::track_alloc(self.name.as_ptr().cast(), ptr, size) };
+		}
+	}
+
+	/// Marks the memory freeing, the size must match the allocated
+	/// one.
+	///
+	/// # Panics
+	///
+	/// In debug builds, panics if more memory is freed than it was
+	/// allocated. Otherwise, the statistics saturate at zero.
+	#[inline(always)]
+	// Tracy only records the address, it never dereferences it.
+	#[allow(clippy::not_unsafe_ptr_arg_deref)]
+	pub fn free<T>(&self, ptr: *const T, size: usize) {
+		#[cfg(feature = "enabled")]
+		{
+			let allocs = saturating_sub(&self.live_allocs, 1);
+			let bytes  = saturating_sub(&self.live_bytes, size as u64);
+			debug_assert!(
+				allocs >= 1 && bytes >= size as u64,
+				"More memory is freed than it was allocated in the pool.",
+			);
+			// SAFETY: The pool name is static & null-terminated.
+			unsafe { crate::details::track_free(self.name.as_ptr().cast(), ptr) };
+		}
+	}
+
+	/// Returns the current usage statistics of the pool.
+	///
+	/// Statistics are maintained even without a running capture, but
+	/// concurrent allocations could make them slightly inconsistent
+	/// with each other.
+	pub fn snapshot(&self) -> PoolStats {
+		#[cfg(feature = "enabled")]
+		{
+			PoolStats {
+				live_allocs: self.live_allocs.load(Ordering::Relaxed),
+				live_bytes:  self.live_bytes.load(Ordering::Relaxed),
+				peak_bytes:  self.peak_bytes.load(Ordering::Relaxed),
+			}
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		PoolStats::default()
+	}
+}
+
+/// Subtracts from the counter, saturating at zero, and returns the
+/// previous value.
+#[cfg(feature = "enabled")]
+fn saturating_sub(counter: &AtomicU64, value: u64) -> u64 {
+	counter
+		.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| Some(n.saturating_sub(value)))
+		.unwrap_or_else(|n| n)
+}
+
 /// Implementation details, do not relay on anything from this module!
 ///
 /// It is public only due to the usage in public macro bodies.
@@ -131,3 +273,38 @@ macro_rules! emit_free {
 #[cfg(feature = "enabled")]
 pub mod details {
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pool_stats() {
+		static POOL: MemoryPool = MemoryPool::new(c"Pool stats");
+
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let (a, b) = (Box::new([0u8; 16]), Box::new([0u8; 64]));
+		POOL.alloc(a.as_ptr(), a.len());
+		POOL.alloc(b.as_ptr(), b.len());
+		POOL.free(a.as_ptr(), a.len());
+		#[cfg(feature = "enabled")]
+		assert_eq!(POOL.snapshot(), PoolStats { live_allocs: 1, live_bytes: 64, peak_bytes: 80 });
+		POOL.free(b.as_ptr(), b.len());
+		#[cfg(feature = "enabled")]
+		assert_eq!(POOL.snapshot(), PoolStats { live_allocs: 0, live_bytes: 0, peak_bytes: 80 });
+		#[cfg(not(feature = "enabled"))]
+		assert_eq!(POOL.snapshot(), PoolStats::default());
+	}
+
+	#[cfg(all(feature = "enabled", debug_assertions))]
+	#[test]
+	#[should_panic(expected = "More memory is freed")]
+	fn mismatched_free() {
+		static POOL: MemoryPool = MemoryPool::new(c"Mismatched free");
+
+		let a = Box::new([0u8; 16]);
+		POOL.alloc(a.as_ptr(), a.len());
+		POOL.free(a.as_ptr(), 32);
+	}
+}
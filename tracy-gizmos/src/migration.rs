@@ -0,0 +1,148 @@
+//! Shims of the `puffin` macros, so the code could be switched to
+//! `tracy-gizmos` by a find & replace of the `puffin::` paths.
+//!
+//! The `tracing::instrument` shim lives in the attributes crate, refer
+//! to [`instrument`].
+//!
+//! [`instrument`]: crate::instrument
+
+/// Instruments the current function with a zone, same as
+/// `puffin::profile_function!`.
+///
+/// The zone is named after the function, while the optional data is
+/// attached as the zone text. Unlike [`zone!`], the name is resolved
+/// at run-time, but only once per call site, so the first call is a
+/// bit more expensive.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn decode(_: &str) {}
+/// fn load(path: &str) {
+///     profile_function!(path);
+///     decode(path);
+/// }
+/// ```
+///
+/// [`zone!`]: crate::zone!
+/// [`instrument`]: crate::instrument
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! profile_function {
+	() => {
+		$crate::profile_function!("");
+	};
+	($data:expr) => {
+		let __tracy_scope = {
+			// Only the path of a nested type is known on stable.
+			struct __TracyGizmosFunction;
+			static LOCATION: ::std::sync::OnceLock<&'static $crate::ZoneLocation> = ::std::sync::OnceLock::new();
+			$crate::Zone::begin(LOCATION.get_or_init(|| {
+				$crate::details::function_location(
+					::std::any::type_name::<__TracyGizmosFunction>(),
+					// SAFETY: We null-terminate the string.
+					unsafe { ::std::ffi::CStr::from_bytes_with_nul_unchecked(concat!(file!(), "\0").as_bytes()) },
+					line!(),
+				)
+			}))
+		};
+		$crate::profile_scope!(@data __tracy_scope, $data);
+	};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! profile_function {
+	() => {};
+	($data:expr) => {
+		// Silences unused expression warning.
+		_ = &$data;
+	};
+}
+
+/// Instruments the current scope with a zone, same as
+/// `puffin::profile_scope!`.
+///
+/// Literal names are as cheap as with [`zone!`], while other names
+/// are copied, same as with [`zone_dynamic!`]. The optional data is
+/// attached as the zone text.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn simulate(_: &str) {}
+/// # let entities = ["player"];
+/// for entity in entities {
+///     profile_scope!("simulate", entity);
+///     simulate(entity);
+/// }
+/// ```
+///
+/// [`zone!`]: crate::zone!
+/// [`zone_dynamic!`]: crate::zone_dynamic!
+#[macro_export]
+macro_rules! profile_scope {
+	($name:literal) => {
+		$crate::zone!($name);
+	};
+	($name:literal, $data:expr) => {
+		$crate::zone!(__tracy_scope, $name);
+		$crate::profile_scope!(@data __tracy_scope, $data);
+	};
+	(@data $zone:ident, $data:expr) => {
+		let data       = &$data;
+		let data: &str = ::core::convert::AsRef::<str>::as_ref(data);
+		if !data.is_empty() {
+			$zone.text(data);
+		}
+	};
+	($name:expr) => {
+		$crate::zone_dynamic!($name);
+	};
+	($name:expr, $data:expr) => {
+		$crate::zone_dynamic!(__tracy_scope, $name);
+		$crate::profile_scope!(@data __tracy_scope, $data);
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	fn profiled(data: &str) -> usize {
+		profile_function!(data);
+		profile_scope!("literal");
+		profile_scope!("literal with data", data);
+		let name = String::from("dynamic");
+		profile_scope!(name);
+		profile_scope!(&name, format!("{data}!"));
+		data.len()
+	}
+
+	#[test]
+	fn puffin_shims() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		profile_function!();
+		assert_eq!(profiled("data"), 4);
+
+		#[cfg(feature = "enabled")]
+		{
+			use crate::record::Event::*;
+
+			let events = crate::record::record(|| _ = profiled(""));
+			assert_eq!(events, [
+				ZoneBegin("profiled".into()),
+				ZoneBegin("literal".into()),
+				ZoneBegin("literal with data".into()),
+				ZoneBegin("dynamic".into()),
+				ZoneBegin("dynamic".into()),
+				ZoneEnd,
+				ZoneEnd,
+				ZoneEnd,
+				ZoneEnd,
+				ZoneEnd,
+			]);
+		}
+	}
+}
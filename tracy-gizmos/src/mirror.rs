@@ -0,0 +1,196 @@
+//! Mirroring of zones, messages and plot values into a user sink.
+//!
+//! When `mirror` feature is enabled, every emitted zone begin & end,
+//! message and plot value is also delivered to the callback
+//! registered via [`set_mirror`], if there is any. Only a single
+//! atomic load is paid per event otherwise.
+
+#[cfg(feature = "enabled")]
+use std::ffi::CStr;
+#[cfg(feature = "enabled")]
+use std::os::raw::c_char;
+#[cfg(feature = "enabled")]
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// An event mirrored into the user sink, refer to [`set_mirror`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum MirrorEvent<'a> {
+	/// A zone has begun on the current thread.
+	ZoneBegin {
+		/// Zone name.
+		name: &'a str,
+	},
+	/// The innermost zone of the current thread has ended.
+	ZoneEnd,
+	/// A message was logged on the current thread.
+	Message {
+		/// Message text.
+		text: &'a str,
+	},
+	/// A value was emitted into the plot.
+	Plot {
+		/// Plot name.
+		name:  &'a str,
+		/// Emitted value, integers are converted.
+		value: f64,
+	},
+}
+
+/// Mirror sink, which is a plain function, so it is cheap to check
+/// and call.
+pub type MirrorSink = fn(&MirrorEvent<'_>);
+
+/// Currently registered `MirrorSink`, null if there is none.
+#[cfg(feature = "enabled")]
+static SINK: AtomicPtr<()> = AtomicPtr::new(std::ptr::null_mut());
+
+/// Registers the sink, which receives every zone begin & end,
+/// message and plot value emitted to Tracy, or removes the current
+/// one with `None`.
+///
+/// It enables in-app overlays or custom logging without
+/// instrumenting the code twice. The sink is called synchronously on
+/// the emitting thread, so it must be fast and must not emit any
+/// instrumentation itself. The capture shutdown waits for the sink
+/// calls in progress. Events are mirrored only while a capture
+/// is active and are never delivered when the instrumentation is
+/// disabled.
+///
+/// Lazy zones are mirrored when they are actually emitted, i.e. when
+/// something happens inside of them or when they end.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// fn overlay(event: &MirrorEvent) {
+///     if let MirrorEvent::Message { text } = event {
+///         eprintln!("[overlay] {text}");
+///     }
+/// }
+///
+/// let _tracy = start_capture();
+/// set_mirror(Some(overlay));
+/// message!("Shown in the overlay, too");
+/// ```
+pub fn set_mirror(sink: Option<MirrorSink>) {
+	#[cfg(feature = "enabled")]
+	SINK.store(sink.map_or(std::ptr::null_mut(), |sink| sink as *mut ()), Ordering::Release);
+}
+
+#[cfg(feature = "enabled")]
+#[inline(always)]
+fn emit<'a>(event: impl FnOnce() -> MirrorEvent<'a>) {
+	let sink = SINK.load(Ordering::Acquire);
+	if sink.is_null() {
+		return;
+	}
+	// SAFETY: Only `MirrorSink`s are stored, see `set_mirror`.
+	let sink = unsafe { std::mem::transmute::<*mut (), MirrorSink>(sink) };
+	sink(&event());
+}
+
+/// Names & texts are created from `str`s, unless some weird
+/// `CStr` is passed explicitly.
+#[cfg(feature = "enabled")]
+fn to_str(s: &CStr) -> &str {
+	s.to_str().unwrap_or("<non-UTF-8>")
+}
+
+/// Mirrors a zone begin.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn zone_begin(name: &str) {
+	emit(|| MirrorEvent::ZoneBegin { name });
+}
+
+/// Mirrors a zone begin.
+///
+/// # Safety
+///
+/// `name` must be null-terminated.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) unsafe fn zone_begin_cstr(name: *const c_char) {
+	emit(|| MirrorEvent::ZoneBegin { name: to_str(CStr::from_ptr(name)) });
+}
+
+/// Mirrors a zone end.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn zone_end() {
+	emit(|| MirrorEvent::ZoneEnd);
+}
+
+/// Mirrors a message.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn message(text: &str) {
+	emit(|| MirrorEvent::Message { text });
+}
+
+/// Mirrors a message.
+///
+/// # Safety
+///
+/// `text` must be null-terminated.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) unsafe fn message_cstr(text: *const c_char) {
+	emit(|| MirrorEvent::Message { text: to_str(CStr::from_ptr(text)) });
+}
+
+/// Mirrors a plot value.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn plot(name: &CStr, value: f64) {
+	emit(|| MirrorEvent::Plot { name: to_str(name), value });
+}
+
+#[cfg(all(test, feature = "enabled"))]
+mod tests {
+	use std::sync::Mutex;
+
+	use super::*;
+	use crate::*;
+
+	static EVENTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+	fn record(event: &MirrorEvent) {
+		let event = match event {
+			MirrorEvent::ZoneBegin { name }   => format!("> {name}"),
+			MirrorEvent::ZoneEnd              => "<".to_string(),
+			MirrorEvent::Message { text }     => format!("! {text}"),
+			MirrorEvent::Plot { name, value } => format!("{name} = {value}"),
+		};
+		EVENTS.lock().unwrap().push(event);
+	}
+
+	#[test]
+	fn mirrors_events() {
+		let _tracy = start_capture();
+		set_mirror(Some(record));
+		{
+			zone!("outer");
+			zone!(lazy "lazy");
+			message!("literal");
+			zone!("skipped", enabled: false);
+			plot!("mirrored plot", 2);
+		}
+		set_mirror(None);
+		zone!("unmirrored");
+		let mut expected = vec![
+			"> outer",
+			"> lazy",
+			"! literal",
+			"mirrored plot = 2",
+			"<",
+		];
+		// Lazy zones don't record end callstacks.
+		#[cfg(feature = "zone-end-callstack")]
+		expected.push("! outer end");
+		expected.push("<");
+		assert_eq!(*EVENTS.lock().unwrap(), expected);
+	}
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+#[cfg(feature = "enabled")]
+use std::sync::OnceLock;
+
+/// Default Tracy port for both data & broadcast.
+#[cfg(feature = "enabled")]
+const DEFAULT_PORT: u16 = 8086;
+/// Amount of ports Tracy tries, when the default one is busy.
+const PORT_SEARCH_RANGE: u16 = 20;
+
+/// Describes how the Tracy client can be reached by the profiler.
+///
+/// Refer to [`capture_network_info`] for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NetworkInfo {
+	/// `true` if the client announces itself on the local network,
+	/// so it is listed in the profiler's discovery list.
+	pub broadcast:      bool,
+	/// UDP port used for the announcements.
+	pub broadcast_port: u16,
+	/// TCP port the client is listening on for the profiler
+	/// connection.
+	pub data_port:      u16,
+	/// `true` if the client will try up to 20 consecutive ports
+	/// starting from `data_port`, when it is already busy (e.g. by
+	/// another profiled application).
+	pub port_search:    bool,
+	/// `true` if the client is listening only on the localhost
+	/// interface.
+	pub only_localhost: bool,
+	/// `true` if the client is listening only on IPv4 interfaces.
+	pub only_ipv4:      bool,
+}
+
+/// Network setup of the client, which reads it only once it starts.
+#[cfg(feature = "enabled")]
+static INFO: OnceLock<NetworkInfo> = OnceLock::new();
+
+/// Returns the network setup of the Tracy client or `None` if the
+/// instrumentation is disabled.
+///
+/// It is determined only once, when the capture starts (or on the
+/// first call, if it is earlier), hence it is cheap enough to be
+/// polled every frame, so an in-app debug UI can tell exactly what to
+/// type into the profiler's connect box.
+///
+/// Note, that it is derived from the crate features and the
+/// environment variables the client respects (`TRACY_PORT`,
+/// `TRACY_ONLY_LOCALHOST`, `TRACY_ONLY_IPV4`), as Tracy doesn't
+/// report the port it has actually bound to. Changes of the
+/// variables after the capture start are not reflected, same as by
+/// the client.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// if let Some(info) = capture_network_info() {
+///     println!("Tracy: {info}");
+/// }
+/// ```
+pub fn capture_network_info() -> Option<NetworkInfo> {
+	#[cfg(feature = "enabled")]
+	return Some(*INFO.get_or_init(detect));
+
+	#[cfg(not(feature = "enabled"))]
+	None
+}
+
+/// Determines the network setup, as the client is about to start.
+#[cfg(feature = "enabled")]
+pub(crate) fn init() {
+	INFO.get_or_init(detect);
+}
+
+#[cfg(feature = "enabled")]
+fn detect() -> NetworkInfo {
+	let user_port = std::env::var("TRACY_PORT")
+		.ok()
+		.and_then(|p| p.parse::<u16>().ok())
+		.filter(|&p| p != 0);
+	let env_flag = |name| std::env::var_os(name).is_some_and(|v| v.as_encoded_bytes().first() == Some(&b'1'));
+
+	let only_localhost = cfg!(feature = "only-localhost") || env_flag("TRACY_ONLY_LOCALHOST");
+	// IPv6 is never tried, if localhost-only mode is compiled in.
+	let only_ipv4 = cfg!(feature = "only-ipv4")
+		|| cfg!(feature = "only-localhost")
+		|| env_flag("TRACY_ONLY_IPV4");
+
+	NetworkInfo {
+		broadcast:      cfg!(feature = "broadcast"),
+		broadcast_port: DEFAULT_PORT,
+		data_port:      user_port.unwrap_or(DEFAULT_PORT),
+		port_search:    user_port.is_none(),
+		only_localhost,
+		only_ipv4,
+	}
+}
+
+impl fmt::Display for NetworkInfo {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let host = match (self.only_localhost, self.only_ipv4) {
+			(true,  _)     => "127.0.0.1",
+			(false, true)  => "<this machine's IPv4 address>",
+			(false, false) => "<this machine's address>",
+		};
+		write!(f, "{host}:{}", self.data_port)?;
+		if self.port_search {
+			write!(f, " (or up to {} if busy)", self.data_port + PORT_SEARCH_RANGE - 1)?;
+		}
+		if self.broadcast {
+			write!(f, ", announced on UDP port {}", self.broadcast_port)?;
+		}
+		Ok(())
+	}
+}
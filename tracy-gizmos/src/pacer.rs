@@ -0,0 +1,161 @@
+use std::time::Duration;
+#[cfg(feature = "enabled")]
+use std::time::Instant;
+
+#[cfg(feature = "enabled")]
+use crate::{Color, Plot, PlotEmit};
+
+/// Weight of the latest frame in the smoothed frame time.
+#[cfg(feature = "enabled")]
+const SMOOTHING: f64 = 0.1;
+
+/// Frame pacing analysis of the main frame.
+///
+/// It marks the main frame end, same as [`frame!`], and records the
+/// time between consecutive frame ends, so all numbers share the
+/// frame boundaries shown by Tracy. Instantaneous & exponentially
+/// smoothed FPS are emitted into `FPS` & `FPS, smoothed` plots. A
+/// frame, which time deviates from the target frame time by more than
+/// the band (10% by default), is reported via a message, so stutters
+/// are easy to find on the timeline.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::time::Duration;
+/// # fn update() {}
+/// # fn render() {}
+/// let mut pacer = FramePacer::new(Duration::from_secs(1) / 60).with_band(0.05);
+/// loop {
+///     update();
+///     render();
+///     pacer.frame();
+/// }
+/// ```
+///
+/// [`frame!`]: crate::frame!
+pub struct FramePacer {
+	#[cfg(feature = "enabled")]
+	target:   Duration,
+	#[cfg(feature = "enabled")]
+	band:     f64,
+	#[cfg(feature = "enabled")]
+	last:     Option<Instant>,
+	/// Smoothed frame time, in seconds.
+	#[cfg(feature = "enabled")]
+	smoothed: Option<f64>,
+}
+
+impl FramePacer {
+	/// Default tolerated deviation from the target frame time.
+	pub const DEFAULT_BAND: f64 = 0.1;
+
+	/// Creates the pacer of frames, which should take `target` time.
+	pub const fn new(target: Duration) -> Self {
+		Self {
+			#[cfg(feature = "enabled")]
+			target,
+			#[cfg(feature = "enabled")]
+			band:     Self::DEFAULT_BAND,
+			#[cfg(feature = "enabled")]
+			last:     None,
+			#[cfg(feature = "enabled")]
+			smoothed: None,
+		}
+	}
+
+	/// Sets the tolerated deviation from the target frame time, as a
+	/// fraction of it, e.g. `0.1` is ±10%.
+	#[cfg_attr(not(feature = "enabled"), allow(unused_mut))]
+	pub const fn with_band(mut self, band: f64) -> Self {
+		#[cfg(feature = "enabled")]
+		{
+			self.band = band;
+		}
+		self
+	}
+
+	/// Marks the main frame end & records its time.
+	pub fn frame(&mut self) {
+		#[cfg(feature = "enabled")]
+		{
+			// SAFETY: Null pointer means main frame.
+			unsafe {
+				crate::details::mark_frame_end(std::ptr::null());
+			}
+			let now  = Instant::now();
+			let last = self.last.replace(now);
+			if let Some(last) = last {
+				self.record(now - last);
+			}
+		}
+	}
+
+	/// Returns the smoothed frame time, once there are at least two
+	/// frames.
+	pub fn smoothed_frame_time(&self) -> Option<Duration> {
+		#[cfg(feature = "enabled")]
+		return self.smoothed.map(Duration::from_secs_f64);
+
+		#[cfg(not(feature = "enabled"))]
+		None
+	}
+
+	#[cfg(feature = "enabled")]
+	fn record(&mut self, delta: Duration) {
+		const FPS:          Plot = Plot::new(c"FPS");
+		const SMOOTHED_FPS: Plot = Plot::new(c"FPS, smoothed");
+
+		let seconds  = delta.as_secs_f64();
+		let smoothed = match self.smoothed {
+			Some(smoothed) => smoothed + (seconds - smoothed) * SMOOTHING,
+			None           => seconds,
+		};
+		self.smoothed = Some(smoothed);
+		if seconds > 0.0 {
+			FPS.emit(1.0 / seconds);
+		}
+		if smoothed > 0.0 {
+			SMOOTHED_FPS.emit(1.0 / smoothed);
+		}
+
+		let target = self.target.as_secs_f64();
+		if (seconds - target).abs() > target * self.band {
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			crate::details::message_size_color(
+				&format!(
+					"Frame took {delta:.1?}, expected {:.1?} ±{}%",
+					self.target,
+					(self.band * 100.0).round(),
+				),
+				Color::YELLOW,
+			);
+			#[cfg(feature = "no-dynamic-strings")]
+			// SAFETY: The text is static & null-terminated.
+			unsafe {
+				crate::details::message_color(c"Frame pacing deviation".as_ptr().cast(), Color::YELLOW);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn paced_frames() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let mut pacer = FramePacer::new(Duration::from_millis(1)).with_band(0.5);
+		pacer.frame();
+		assert_eq!(pacer.smoothed_frame_time(), None);
+		for _ in 0..3 {
+			std::thread::sleep(Duration::from_millis(1));
+			pacer.frame();
+		}
+		#[cfg(feature = "enabled")]
+		assert!(pacer.smoothed_frame_time().unwrap() >= Duration::from_millis(1));
+	}
+}
@@ -29,6 +29,7 @@ macro_rules! plot {
 		match $value {
 			tmp => {
 				use $crate::PlotEmit;
+				const _: () = $crate::limits::check_name_length($name);
 				$crate::Plot::new(
 					// SAFETY: We null-terminate the string.
 					unsafe {
@@ -59,6 +60,9 @@ macro_rules! plot {
 ///
 /// Value could be emitted to the plot after that.
 ///
+/// Every plot must be configured the same way everywhere. Debug
+/// builds report conflicting configurations via a message.
+///
 /// # Examples
 /// ```no_run
 /// # use tracy_gizmos::*;
@@ -70,6 +74,7 @@ macro_rules! plot {
 #[cfg(any(doc, feature = "enabled"))]
 macro_rules! make_plot {
 	($var:ident, $name:literal, $config:expr) => {
+		const _: () = $crate::limits::check_name_length($name);
 		#[allow(unused_variables)]
 		let $var = $crate::Plot::with_config(
 			// SAFETY: We null-terminate the string.
@@ -119,6 +124,10 @@ impl Plot {
 
 	#[inline(always)]
 	pub fn with_config(name: &'static CStr, config: PlotConfig) -> Self {
+		#[cfg(feature = "enabled")]
+		let Some(_emission) = crate::emission() else {
+			return Self(name);
+		};
 		#[cfg(feature = "enabled")]
 		// SAFETY: `PlotConfig` ensures values are correct.
 		unsafe {
@@ -130,6 +139,10 @@ impl Plot {
 				config.color .as_u32(),
 			);
 		}
+		#[cfg(feature = "enabled")]
+		crate::self_profile::count_event();
+		#[cfg(all(debug_assertions, feature = "enabled", not(feature = "no-dynamic-strings")))]
+		crate::collisions::plot(name, config);
 
 		Self(#[cfg(feature = "enabled")] name)
 	}
@@ -145,24 +158,44 @@ pub trait PlotEmit<T> {
 }
 
 macro_rules! impl_emit {
-	($ty:ident, $with:ident) => {
+	($ty:ident, $variant:ident) => {
 		impl PlotEmit<$ty> for Plot {
 			#[inline(always)]
 			fn emit(&self, value: $ty) {
 				#[cfg(feature = "enabled")]
-				// SAFETY: `Plot` creation ensures the name correctness.
-				unsafe {
-					sys::$with(self.0.as_ptr(), value);
-				}
+				crate::plot_batch::emit(self.0, crate::plot_batch::PlotValue::$variant(value));
 			}
 		}
 	};
 }
 
 // Yay, overloading.
-impl_emit!(f64, ___tracy_emit_plot);
-impl_emit!(f32, ___tracy_emit_plot_float);
-impl_emit!(i64, ___tracy_emit_plot_int);
+impl_emit!(f64, F64);
+impl_emit!(f32, F32);
+impl_emit!(i64, I64);
+
+/// Forwards the plot values batched on the current thread to Tracy.
+///
+/// Does nothing, unless [`CaptureOptions::plot_batching`] is set.
+/// Useful before a thread goes idle for a long time, so its latest
+/// values do not linger in the batch.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn wait_for_work() {}
+/// plot!("Queue length", 0);
+/// flush_plots();
+/// wait_for_work();
+/// ```
+///
+/// [`CaptureOptions::plot_batching`]: crate::CaptureOptions::plot_batching
+#[inline(always)]
+pub fn flush_plots() {
+	#[cfg(feature = "enabled")]
+	crate::plot_batch::flush();
+}
 
 /// A plot configuration, which controls the way plot will be
 /// displayed.
@@ -0,0 +1,157 @@
+//! Per-thread coalescing of plot emissions.
+//!
+//! When [`CaptureOptions::plot_batching`] is set, plot values are
+//! kept in a thread-local buffer and only the latest value of every
+//! plot is forwarded to Tracy once per window. It reduces the queue
+//! contention, when dozens of threads plot every iteration.
+//!
+//! [`CaptureOptions::plot_batching`]: crate::CaptureOptions::plot_batching
+
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Batching window in nanoseconds, `0` means no batching.
+static WINDOW: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy)]
+pub(crate) enum PlotValue {
+	F64(f64),
+	F32(f32),
+	I64(i64),
+}
+
+struct Batch {
+	flushed: Instant,
+	values:  Vec<(&'static CStr, PlotValue)>,
+}
+
+impl Drop for Batch {
+	fn drop(&mut self) {
+		// Values are not lost, when the thread exits, unless the
+		// capture is already gone.
+		if WINDOW.load(Ordering::Relaxed) != 0 {
+			flush_values(&mut self.values);
+		}
+	}
+}
+
+thread_local! {
+	static BATCH: RefCell<Batch> = RefCell::new(Batch {
+		flushed: Instant::now(),
+		values:  Vec::new(),
+	});
+}
+
+pub(crate) fn set_window(window: Option<Duration>) {
+	let nanos = window.map_or(0, |w| w.as_nanos().clamp(1, u64::MAX as u128) as u64);
+	WINDOW.store(nanos, Ordering::Relaxed);
+}
+
+/// Emits the value right away, or keeps it in the current thread's
+/// batch, if batching is enabled.
+#[inline(always)]
+pub(crate) fn emit(name: &'static CStr, value: PlotValue) {
+	let window = WINDOW.load(Ordering::Relaxed);
+	if window == 0 {
+		emit_now(name, value);
+	} else {
+		push(name, value, Duration::from_nanos(window));
+	}
+}
+
+fn push(name: &'static CStr, value: PlotValue, window: Duration) {
+	// The thread-local could be already gone, if plotting happens
+	// during the thread teardown.
+	let pushed = BATCH.try_with(|batch| {
+		let mut batch = batch.borrow_mut();
+		match batch.values.iter_mut().find(|(n, _)| n.as_ptr() == name.as_ptr()) {
+			Some((_, v)) => *v = value,
+			None         => batch.values.push((name, value)),
+		}
+		if batch.flushed.elapsed() >= window {
+			batch.flushed = Instant::now();
+			flush_values(&mut batch.values);
+		}
+	});
+	if pushed.is_err() {
+		emit_now(name, value);
+	}
+}
+
+/// Forwards the current thread's batched plot values to Tracy.
+pub(crate) fn flush() {
+	_ = BATCH.try_with(|batch| {
+		let mut batch = batch.borrow_mut();
+		batch.flushed = Instant::now();
+		flush_values(&mut batch.values);
+	});
+}
+
+fn flush_values(values: &mut Vec<(&'static CStr, PlotValue)>) {
+	for (name, value) in values.drain(..) {
+		emit_now(name, value);
+	}
+}
+
+fn emit_now(name: &'static CStr, value: PlotValue) {
+	#[cfg(test)]
+	if crate::record::push(|| crate::record::Event::Plot(name.to_string_lossy().into_owned(), match value {
+		PlotValue::F64(v) => v,
+		PlotValue::F32(v) => v as f64,
+		PlotValue::I64(v) => v as f64,
+	})) {
+		return;
+	}
+	let Some(_emission) = crate::emission() else {
+		return;
+	};
+	// SAFETY: Plot names are always static & null-terminated.
+	unsafe {
+		match value {
+			PlotValue::F64(v) => sys::___tracy_emit_plot(name.as_ptr(), v),
+			PlotValue::F32(v) => sys::___tracy_emit_plot_float(name.as_ptr(), v),
+			PlotValue::I64(v) => sys::___tracy_emit_plot_int(name.as_ptr(), v),
+		}
+	}
+	#[cfg(feature = "mirror")]
+	crate::mirror::plot(name, match value {
+		PlotValue::F64(v) => v,
+		PlotValue::F32(v) => v as f64,
+		PlotValue::I64(v) => v as f64,
+	});
+	crate::self_profile::count_event();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+
+	fn pending() -> usize {
+		BATCH.with_borrow(|batch| batch.values.len())
+	}
+
+	#[test]
+	fn coalesces_within_window() {
+		let tracy = start_capture_with(CaptureOptions {
+			plot_batching: Some(Duration::from_secs(60)),
+			..Default::default()
+		});
+		flush_plots();
+		plot!("batched a", 1);
+		plot!("batched a", 2.0);
+		plot!("batched b", 3.0f32);
+		assert_eq!(pending(), 2);
+		flush_plots();
+		assert_eq!(pending(), 0);
+		plot!("batched a", 4);
+		drop(tracy);
+		assert_eq!(pending(), 0);
+
+		let _tracy = start_capture();
+		plot!("unbatched", 5);
+		assert_eq!(pending(), 0);
+	}
+}
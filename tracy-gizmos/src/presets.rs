@@ -0,0 +1,9 @@
+//! Ready-made instrumentation conventions for common kinds of
+//! workloads.
+//!
+//! Every preset is a usable API on its own, but it also serves as a
+//! living documentation of how the crate pieces compose, so feel free
+//! to copy & adapt one, if it doesn't fit exactly.
+
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub mod asset_pipeline;
@@ -0,0 +1,222 @@
+//! Asset pipeline tracer.
+//!
+//! It bundles the conventions for instrumenting asset loading:
+//! - every asset load is a discontinuous `Assets` frame and a zone
+//!   named after the asset, so the frame timeline shows how long
+//!   every asset took;
+//! - every [`Stage`] of the load is a zone of its own color;
+//! - bytes handled by every stage are emitted into `Assets: <stage>
+//!   bytes` plots;
+//! - staging memory is tracked in the `Asset staging` memory pool via
+//!   [`StagingBuffer`].
+//!
+//! Discontinuous frames can't overlap, so loads sharing the frame
+//! are expected to be sequential, e.g. done by a dedicated loader
+//! thread.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use tracy_gizmos::*;
+//! use tracy_gizmos::presets::asset_pipeline::{self as assets, Stage, StagingBuffer};
+//! # fn read_file(path: &str, into: &mut [u8]) -> usize { 0 }
+//! # fn upload(data: &[u8]) {}
+//!
+//! # let path = "textures/grass.png";
+//! let load = assets::load(path);
+//! let mut staging = StagingBuffer::new(64 * 1024);
+//! let read = {
+//!     let _read = load.stage(Stage::Read);
+//!     read_file(path, &mut staging)
+//! };
+//! load.bytes(Stage::Read, read as u64);
+//! {
+//!     let _upload = load.stage(Stage::Upload);
+//!     upload(&staging[..read]);
+//! }
+//! load.bytes(Stage::Upload, read as u64);
+//! ```
+
+use std::ops::{Deref, DerefMut};
+
+use crate::{Frame, MemoryPool, Plot, PlotEmit, Zone};
+#[cfg(feature = "enabled")]
+use crate::{Color, ZoneLocation};
+
+/// A stage of the asset load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+	/// Reading the raw data, e.g. from disk or network.
+	Read,
+	/// Decoding the raw data, e.g. decompressing or parsing.
+	Decode,
+	/// Processing the decoded data, e.g. generating mips.
+	Process,
+	/// Uploading the processed data, e.g. to the GPU.
+	Upload,
+}
+
+impl Stage {
+	/// Returns the stage name, as it is visible in the profiler.
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Read    => "Read",
+			Self::Decode  => "Decode",
+			Self::Process => "Process",
+			Self::Upload  => "Upload",
+		}
+	}
+
+	const fn plot(self) -> Plot {
+		Plot::new(match self {
+			Self::Read    => c"Assets: Read bytes",
+			Self::Decode  => c"Assets: Decode bytes",
+			Self::Process => c"Assets: Process bytes",
+			Self::Upload  => c"Assets: Upload bytes",
+		})
+	}
+
+	#[cfg(feature = "enabled")]
+	fn location(self) -> &'static ZoneLocation {
+		const FILE: &str = concat!(file!(), '\0');
+		const FUNC: &[u8] = b"asset_pipeline\0";
+		// SAFETY: All passed strings are static & null-terminated.
+		static READ: ZoneLocation = unsafe {
+			crate::details::zone_location("Read\0", FUNC, FILE, line!(), Color::SKY_BLUE.as_u32())
+		};
+		// SAFETY: All passed strings are static & null-terminated.
+		static DECODE: ZoneLocation = unsafe {
+			crate::details::zone_location("Decode\0", FUNC, FILE, line!(), Color::ORANGE.as_u32())
+		};
+		// SAFETY: All passed strings are static & null-terminated.
+		static PROCESS: ZoneLocation = unsafe {
+			crate::details::zone_location("Process\0", FUNC, FILE, line!(), Color::GOLD.as_u32())
+		};
+		// SAFETY: All passed strings are static & null-terminated.
+		static UPLOAD: ZoneLocation = unsafe {
+			crate::details::zone_location("Upload\0", FUNC, FILE, line!(), Color::LIME_GREEN.as_u32())
+		};
+		match self {
+			Self::Read    => &READ,
+			Self::Decode  => &DECODE,
+			Self::Process => &PROCESS,
+			Self::Upload  => &UPLOAD,
+		}
+	}
+}
+
+/// An asset load in progress, which ends when dropped.
+///
+/// Refer to [`asset_pipeline`](crate::presets::asset_pipeline) for the
+/// usage how-to.
+pub struct AssetLoad {
+	// Fields are dropped in order, so the zone ends within the frame.
+	zone:   Zone,
+	_frame: Frame,
+}
+
+/// Starts the asset load, which is both a discontinuous `Assets`
+/// frame and a zone named after the asset.
+#[track_caller]
+pub fn load(name: &str) -> AssetLoad {
+	#[cfg(feature = "enabled")]
+	// SAFETY: The frame name is static & null-terminated.
+	let frame = unsafe { crate::details::discontinuous_frame(c"Assets".as_ptr()) };
+	#[cfg(not(feature = "enabled"))]
+	let frame = Frame();
+
+	AssetLoad { zone: Zone::begin_dynamic(name), _frame: frame }
+}
+
+impl AssetLoad {
+	/// Returns the asset zone, e.g. to attach some asset data to it.
+	pub fn zone(&self) -> &Zone {
+		&self.zone
+	}
+
+	/// Begins the stage zone, which ends when dropped.
+	pub fn stage(&self, stage: Stage) -> Zone {
+		#[cfg(feature = "enabled")]
+		// SAFETY: Stage locations are static.
+		unsafe {
+			crate::details::zone(stage.location(), 1)
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		Zone::new()
+	}
+
+	/// Emits the amount of bytes handled by the stage.
+	pub fn bytes(&self, stage: Stage, bytes: u64) {
+		stage.plot().emit(bytes as i64);
+	}
+}
+
+/// The memory pool for the staging memory.
+static STAGING: MemoryPool = MemoryPool::new(c"Asset staging");
+
+/// A fixed-size staging buffer, which is tracked in the `Asset
+/// staging` memory pool.
+pub struct StagingBuffer {
+	data: Box<[u8]>,
+}
+
+impl StagingBuffer {
+	/// Allocates the zeroed buffer of the given size.
+	pub fn new(size: usize) -> Self {
+		let data = vec![0; size].into_boxed_slice();
+		// Empty buffers share the same dangling pointer, so they are
+		// not tracked.
+		if size != 0 {
+			STAGING.alloc(data.as_ptr(), size);
+		}
+		Self { data }
+	}
+}
+
+impl Deref for StagingBuffer {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		&self.data
+	}
+}
+
+impl DerefMut for StagingBuffer {
+	fn deref_mut(&mut self) -> &mut [u8] {
+		&mut self.data
+	}
+}
+
+impl Drop for StagingBuffer {
+	fn drop(&mut self) {
+		if !self.data.is_empty() {
+			STAGING.free(self.data.as_ptr(), self.data.len());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn asset_loads() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		for path in ["a.png", "b.png"] {
+			let asset = load(path);
+			asset.zone().text("texture");
+			let mut staging = StagingBuffer::new(16);
+			for stage in [Stage::Read, Stage::Decode, Stage::Process, Stage::Upload] {
+				let _stage = asset.stage(stage);
+				staging[0] += 1;
+				asset.bytes(stage, staging.len() as u64);
+			}
+			assert_eq!(staging[0], 4);
+			// Buffers could be freed on another thread.
+			std::thread::spawn(move || drop(staging)).join().unwrap();
+			drop(StagingBuffer::new(0));
+		}
+	}
+}
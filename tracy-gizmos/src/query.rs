@@ -0,0 +1,154 @@
+use std::ffi::CStr;
+
+#[cfg(feature = "enabled")]
+use crate::{Plot, PlotEmit};
+
+/// Instruments the current scope with a profiling zone of an ECS
+/// query and records its cost.
+///
+/// It standardizes how per-system query cost is recorded, regardless
+/// of the ECS being used. Amount of matched entities and, optionally,
+/// visited archetypes are attached to the zone as numbers (in this
+/// order) and emitted into the [`QueryStats`] plots of the same name.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # struct World;
+/// # impl World { fn query(&self) -> Vec<u32> { vec![] } fn archetypes(&self) -> usize { 0 } }
+/// # let world = World;
+/// let positions = world.query();
+/// query_zone!("physics: positions", positions.len(), world.archetypes());
+/// for position in positions {
+///     // now actually integrate :-)
+/// }
+/// ```
+///
+/// Same as with [`zone!`], the zone could be named to attach more
+/// dynamic data:
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # let enemies = vec![1, 2, 3];
+/// query_zone!(ai, "ai: enemies", enemies.len());
+/// ai.text("aggressive");
+/// ```
+///
+/// [`zone!`]: crate::zone!
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! query_zone {
+	($var:ident, $name:literal, $entities:expr $(, $archetypes:expr)?) => {
+		$crate::zone!($var, $name);
+		let stats = $crate::query_stats!($name);
+		let entities = $entities as u64;
+		$var.number(entities);
+		stats.entities_matched(entities);
+		$(
+			let archetypes = $archetypes as u64;
+			$var.number(archetypes);
+			stats.archetypes_visited(archetypes);
+		)?
+	};
+	($name:literal, $entities:expr $(, $archetypes:expr)?) => {
+		$crate::query_zone!(_z, $name, $entities $(, $archetypes)?);
+	};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! query_zone {
+	($($var:ident,)? $name:literal, $entities:expr $(, $archetypes:expr)?) => {
+		$crate::zone!($($var,)? $name);
+		// Silences unused expression warnings.
+		_ = $entities;
+		$(_ = $archetypes;)?
+	};
+}
+
+/// Creates the [`QueryStats`] plots for the query.
+///
+/// Plots are named `<name>: entities matched` and `<name>: archetypes
+/// visited`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # let (matched, visited) = (128, 4);
+/// let stats = query_stats!("render: meshes");
+/// stats.entities_matched(matched);
+/// stats.archetypes_visited(visited);
+/// ```
+#[macro_export]
+macro_rules! query_stats {
+	($name:literal) => {{
+		const _: () = $crate::limits::check_name_length(concat!($name, ": entities matched"));
+		const _: () = $crate::limits::check_name_length(concat!($name, ": archetypes visited"));
+		// SAFETY: We null-terminate the strings.
+		unsafe {
+			$crate::QueryStats::new(
+				::std::ffi::CStr::from_bytes_with_nul_unchecked(concat!($name, ": entities matched\0").as_bytes()),
+				::std::ffi::CStr::from_bytes_with_nul_unchecked(concat!($name, ": archetypes visited\0").as_bytes()),
+			)
+		}
+	}};
+}
+
+/// A group of plots recording the cost of an ECS query: amount of
+/// matched entities and visited archetypes.
+///
+/// Refer to [`query_stats!`] & [`query_zone!`].
+#[derive(Debug, Clone, Copy)]
+pub struct QueryStats {
+	#[cfg(feature = "enabled")]
+	entities:   Plot,
+	#[cfg(feature = "enabled")]
+	archetypes: Plot,
+}
+
+#[doc(hidden)]
+impl QueryStats {
+	#[inline(always)]
+	pub const fn new(entities: &'static CStr, archetypes: &'static CStr) -> Self {
+		Self {
+			#[cfg(feature = "enabled")]
+			entities:   Plot::new(entities),
+			#[cfg(feature = "enabled")]
+			archetypes: Plot::new(archetypes),
+		}
+	}
+}
+
+impl QueryStats {
+	/// Emits the amount of entities matched by the query.
+	#[inline(always)]
+	pub fn entities_matched(&self, count: u64) {
+		#[cfg(feature = "enabled")]
+		self.entities.emit(count as i64);
+	}
+
+	/// Emits the amount of archetypes visited by the query.
+	#[inline(always)]
+	pub fn archetypes_visited(&self, count: u64) {
+		#[cfg(feature = "enabled")]
+		self.archetypes.emit(count as i64);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn query_zones() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let entities = [1, 2, 3];
+		query_zone!("test query", entities.len());
+		query_zone!(zone, "test query", entities.len(), 2u32);
+		zone.text("named");
+		let stats = query_stats!("test query");
+		stats.entities_matched(1);
+		stats.archetypes_visited(1);
+	}
+}
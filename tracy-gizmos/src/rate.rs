@@ -0,0 +1,83 @@
+//! Rate limiting of zones, refer to `max_hz:` argument of `zone!`.
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use crate::Zone;
+
+/// Static data of the rate limited zone.
+#[doc(hidden)]
+pub struct RateLimit {
+	/// Minimal time between emitted zones, in nanoseconds.
+	period:     u64,
+	/// The earliest moment the next zone could be emitted at, in
+	/// nanoseconds since [`epoch`].
+	next:       AtomicU64,
+	suppressed: AtomicU64,
+}
+
+impl RateLimit {
+	#[doc(hidden)]
+	pub const fn new(max_hz: u32) -> Self {
+		if max_hz == 0 {
+			panic!("Zone rate should be at least 1 Hz.");
+		}
+		Self {
+			period:     1_000_000_000 / max_hz as u64,
+			next:       AtomicU64::new(0),
+			suppressed: AtomicU64::new(0),
+		}
+	}
+
+	/// Returns `true`, if the zone should be emitted, otherwise it is
+	/// counted as suppressed.
+	#[doc(hidden)]
+	#[inline(always)]
+	pub fn admit(&self) -> bool {
+		let now  = epoch().elapsed().as_nanos() as u64;
+		let next = self.next.load(Ordering::Relaxed);
+		// Only one of the racing threads wins the slot.
+		if now >= next && self.next.compare_exchange(next, now + self.period, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+			return true;
+		}
+		self.suppressed.fetch_add(1, Ordering::Relaxed);
+		false
+	}
+
+	/// Attaches the amount of zones suppressed since the previous
+	/// emitted one to the admitted zone.
+	#[doc(hidden)]
+	pub fn report(&self, zone: &Zone) {
+		let suppressed = self.suppressed.swap(0, Ordering::Relaxed);
+		if suppressed != 0 {
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			zone.text_fmt(format_args!("{suppressed} suppressed"));
+			#[cfg(feature = "no-dynamic-strings")]
+			zone.number(suppressed);
+		}
+	}
+}
+
+fn epoch() -> Instant {
+	static EPOCH: OnceLock<Instant> = OnceLock::new();
+	*EPOCH.get_or_init(Instant::now)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::zone;
+
+	#[test]
+	fn zones_are_rate_limited() {
+		static RATE: RateLimit = RateLimit::new(1);
+
+		let _tracy = crate::start_capture();
+		let admitted = (0..5).filter(|_| RATE.admit()).count();
+		assert_eq!((admitted, RATE.suppressed.load(Ordering::Relaxed)), (1, 4));
+		zone!(spammy, "spammy", enabled: false);
+		RATE.report(&spammy);
+		assert_eq!(RATE.suppressed.load(Ordering::Relaxed), 0);
+	}
+}
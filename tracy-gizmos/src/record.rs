@@ -0,0 +1,152 @@
+//! Test double, which records the emitted events instead of passing
+//! them to Tracy.
+//!
+//! While [`record`] runs, every zone, message, frame mark & plot value
+//! emitted on the current thread, as well as colors of the recorded
+//! zones, is pushed into a `Vec` and never reaches the client, so
+//! macros & helpers could be tested for the exact emission sequence
+//! without a running capture. Recording
+//! happens before the muting checks, so the filters applied by the
+//! macros themselves, e.g. `enabled:` or `level:`, are still in
+//! effect. Lazy zones are not recorded.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, c_char};
+use std::marker::PhantomData;
+
+use crate::{Zone, ZoneLocation};
+
+/// Id of the zones begun while recording, so their end is recorded
+/// as well.
+pub(crate) const ZONE_ID: u32 = u32::MAX;
+
+/// An emitted event.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Event {
+	ZoneBegin(String),
+	/// Color of the recorded zone, as `0xRRGGBB`. The static one is
+	/// recorded right after the zone begin.
+	ZoneColor(u32),
+	ZoneEnd,
+	Message(String),
+	/// Continuous frame mark, `None` is the main frame.
+	FrameMark(Option<String>),
+	FrameStart(String),
+	FrameEnd(String),
+	Plot(String, f64),
+}
+
+thread_local! {
+	static EVENTS: RefCell<Option<Vec<Event>>> = const { RefCell::new(None) };
+}
+
+/// Runs the closure, recording the events emitted by the current
+/// thread.
+pub(crate) fn record(f: impl FnOnce()) -> Vec<Event> {
+	EVENTS.with_borrow_mut(|events| *events = Some(Vec::new()));
+	f();
+	EVENTS.with_borrow_mut(Option::take).unwrap_or_default()
+}
+
+/// Records the event, if the current thread is recording.
+///
+/// Returns `true` if it was recorded, so it must not be emitted.
+pub(crate) fn push(event: impl FnOnce() -> Event) -> bool {
+	EVENTS
+		.try_with(|events| match &mut *events.borrow_mut() {
+			Some(events) => {
+				events.push(event());
+				true
+			},
+			None => false,
+		})
+		.unwrap_or(false)
+}
+
+/// Records the zone begin, returning the zone, which records its end.
+pub(crate) fn zone(name: impl FnOnce() -> String) -> Option<Zone> {
+	push(|| Event::ZoneBegin(name())).then(recorded_zone)
+}
+
+/// Records the begin of the zone at the static location, returning
+/// the zone, which records its end.
+///
+/// # Safety
+///
+/// The location name must be null-terminated.
+pub(crate) unsafe fn location_zone(location: &ZoneLocation) -> Option<Zone> {
+	location_begin(location).then(recorded_zone)
+}
+
+/// Records the begin of the zone at the static location, along with
+/// its color, if any.
+///
+/// Returns `true` if it was recorded, so it must not be emitted.
+///
+/// # Safety
+///
+/// The location name must be null-terminated.
+pub(crate) unsafe fn location_begin(location: &ZoneLocation) -> bool {
+	if !push(|| Event::ZoneBegin(string(location.0.name))) {
+		return false;
+	}
+	if location.0.color != 0 {
+		push(|| Event::ZoneColor(location.0.color));
+	}
+	true
+}
+
+fn recorded_zone() -> Zone {
+	Zone {
+		ctx:     sys::TracyCZoneCtx { id: ZONE_ID, active: 0 },
+		_unsend: PhantomData,
+	}
+}
+
+/// Returns the null-terminated string, e.g. a name of the source
+/// location.
+///
+/// # Safety
+///
+/// `s` must be null-terminated.
+pub(crate) unsafe fn string(s: *const c_char) -> String {
+	CStr::from_ptr(s).to_string_lossy().into_owned()
+}
+
+mod tests {
+	use super::*;
+	use super::Event::*;
+	use crate::{frame, message, plot, zone};
+
+	#[test]
+	fn recorded_emissions() {
+		let events = record(|| {
+			frame!(_io, "IO");
+			zone!(outer, "outer");
+			message!("hello");
+			message!("world");
+			{
+				zone!("inner", enabled: false);
+				Zone::begin_at("dynamic", "recorded_emissions", file!(), line!());
+			}
+			drop(outer);
+			plot!("plot", 1.5);
+			frame!();
+			frame!("secondary");
+		});
+		assert_eq!(events, [
+			FrameStart("IO".into()),
+			ZoneBegin("outer".into()),
+			Message("hello".into()),
+			Message("world".into()),
+			ZoneBegin("dynamic".into()),
+			ZoneEnd,
+			ZoneEnd,
+			Plot("plot".into(), 1.5),
+			FrameMark(None),
+			FrameMark(Some("secondary".into())),
+			FrameEnd("IO".into()),
+		]);
+		assert!(record(|| {}).is_empty());
+	}
+}
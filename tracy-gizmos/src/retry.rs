@@ -0,0 +1,193 @@
+#[cfg(feature = "enabled")]
+use std::time::{Duration, Instant};
+
+use crate::Zone;
+#[cfg(feature = "enabled")]
+use crate::{Color, Plot, PlotEmit, ZoneLocation};
+
+/// Creates a [`RetryZone`], which instruments an operation retried
+/// with a backoff.
+///
+/// Optionally, a custom [`Color`] could be assigned to the operation
+/// zone.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::time::Duration;
+/// # fn query() -> Result<u32, ()> { Ok(1) }
+/// let mut retry = retry_zone!("fetch user");
+/// let user = (1..=5).find_map(|i| {
+///     let _attempt = retry.attempt(i);
+///     query().inspect_err(|_| std::thread::sleep(Duration::from_millis(100) * i)).ok()
+/// });
+/// ```
+///
+/// [`Color`]: crate::Color
+#[macro_export]
+#[cfg(any(doc, feature = "enabled"))]
+macro_rules! retry_zone {
+	($name:literal) => {
+		$crate::retry_zone!($name, $crate::Color::UNSPECIFIED)
+	};
+	($name:literal, $color:expr) => {{
+		const _: () = $crate::limits::check_name_length(concat!($name, ": retries"));
+		$crate::RetryZone::new(
+			$crate::zone!(@loc $name, $color),
+			// SAFETY: We null-terminate the string.
+			unsafe {
+				::std::ffi::CStr::from_bytes_with_nul_unchecked(concat!($name, ": retries\0").as_bytes())
+			},
+		)
+	}};
+}
+
+#[macro_export]
+#[cfg(all(not(doc), not(feature = "enabled")))]
+macro_rules! retry_zone {
+	($name:literal) => {
+		$crate::RetryZone::compiled_out()
+	};
+	($name:literal, $color:expr) => {{
+		// Silences unused `Color` import warning.
+		_ = $color;
+		$crate::RetryZone::compiled_out()
+	}};
+}
+
+/// A zone of an operation, which is retried with a backoff, e.g. a
+/// network or database request.
+///
+/// Every attempt is a nested `attempt` zone, which gets the attempt
+/// number & the backoff accumulated so far attached as a text. The
+/// backoff is the time spent between the attempts. With
+/// `no-dynamic-strings` feature, both are attached as numbers, the
+/// backoff is in nanoseconds.
+///
+/// When the operation ends, the amount of retries is emitted into
+/// the `<name>: retries` plot, so flaky operations stand out.
+///
+/// Refer to [`retry_zone!`] for the usage how-to.
+pub struct RetryZone {
+	#[cfg(feature = "enabled")]
+	retries:  Plot,
+	#[cfg(feature = "enabled")]
+	attempts: u32,
+	#[cfg(feature = "enabled")]
+	backoff:  Duration,
+	#[cfg(feature = "enabled")]
+	last_end: Option<Instant>,
+	zone:     Zone,
+}
+
+impl RetryZone {
+	/// Begins the operation zone at the given location, the retries
+	/// are emitted into the given plot.
+	#[cfg(feature = "enabled")]
+	pub fn new(location: &'static ZoneLocation, retries: &'static std::ffi::CStr) -> Self {
+		Self {
+			retries:  Plot::new(retries),
+			attempts: 0,
+			backoff:  Duration::ZERO,
+			last_end: None,
+			zone:     Zone::begin(location),
+		}
+	}
+
+	#[doc(hidden)]
+	#[cfg(not(feature = "enabled"))]
+	pub fn compiled_out() -> Self {
+		Self { zone: Zone::new() }
+	}
+
+	/// Returns the operation zone, e.g. to attach the request data to
+	/// it.
+	pub fn zone(&self) -> &Zone {
+		&self.zone
+	}
+
+	/// Begins the attempt of the given number, which ends when the
+	/// returned zone is dropped.
+	pub fn attempt(&mut self, number: u32) -> Attempt<'_> {
+		#[cfg(feature = "enabled")]
+		{
+			const FILE: &str = concat!(file!(), '\0');
+			// SAFETY: All passed strings are static & null-terminated.
+			static ATTEMPT: ZoneLocation = unsafe {
+				crate::details::zone_location("attempt\0", b"RetryZone::attempt\0", FILE, line!(), Color::UNSPECIFIED.as_u32())
+			};
+
+			if let Some(last_end) = self.last_end {
+				self.backoff += last_end.elapsed();
+			}
+			self.attempts += 1;
+			let zone = Zone::begin(&ATTEMPT);
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			zone.text_fmt(format_args!("attempt {number}, {:?} backoff", self.backoff));
+			#[cfg(feature = "no-dynamic-strings")]
+			{
+				zone.number(number as u64);
+				zone.number(self.backoff.as_nanos() as u64);
+			}
+			Attempt { zone, retry: self }
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		Attempt { zone: Zone::new(), retry: self }
+	}
+}
+
+#[cfg(feature = "enabled")]
+impl Drop for RetryZone {
+	fn drop(&mut self) {
+		if self.attempts != 0 {
+			self.retries.emit((self.attempts - 1) as i64);
+		}
+	}
+}
+
+/// An attempt of the [`RetryZone`], which ends when dropped.
+pub struct Attempt<'a> {
+	zone:  Zone,
+	#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+	retry: &'a mut RetryZone,
+}
+
+impl Attempt<'_> {
+	/// Returns the attempt zone, e.g. to attach the error to it.
+	pub fn zone(&self) -> &Zone {
+		&self.zone
+	}
+}
+
+impl Drop for Attempt<'_> {
+	fn drop(&mut self) {
+		#[cfg(feature = "enabled")]
+		{
+			self.retry.last_end = Some(Instant::now());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn retried_operations() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		{
+			let mut retry = retry_zone!("flaky", crate::Color::RED);
+			retry.zone().text("request");
+			for i in 1..=3 {
+				let attempt = retry.attempt(i);
+				attempt.zone().text("timeout");
+			}
+			#[cfg(feature = "enabled")]
+			assert_eq!((retry.attempts, retry.last_end.is_some()), (3, true));
+		}
+
+		let mut once = retry_zone!("reliable");
+		drop(once.attempt(1));
+	}
+}
@@ -0,0 +1,94 @@
+#[cfg(feature = "enabled")]
+use std::sync::Mutex;
+#[cfg(feature = "enabled")]
+use std::time::Instant;
+
+#[cfg(feature = "enabled")]
+use crate::{details, Color};
+
+// Discontinuous frames are identified by the name pointer, so all
+// segments share the same one.
+#[cfg(feature = "enabled")]
+const FRAME: &std::ffi::CStr = c"Segment";
+
+#[cfg(feature = "enabled")]
+static CURRENT: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+
+/// Begins a named capture segment.
+///
+/// Long captures (e.g. soak tests) are hard to navigate. Segments
+/// split them into chapters: every segment is a discontinuous frame
+/// in the `Segment` frame set and is bracketed with
+/// `Segment begin: <name>` & `Segment end: <name> (<duration>)`
+/// messages, so it is easy to find in the timeline or via the
+/// message search.
+///
+/// Only one segment could be active at a time. Beginning a new
+/// segment ends the current one.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn play(level: u32) {}
+/// for level in 1..=3 {
+///     begin_named_segment(&format!("level {level}"));
+///     play(level);
+/// }
+/// end_segment();
+/// ```
+pub fn begin_named_segment(name: &str) {
+	#[cfg(feature = "enabled")]
+	{
+		let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+		if let Some(previous) = current.take() {
+			end(previous);
+		}
+		details::message_size_color(&format!("Segment begin: {name}"), Color::GOLD);
+		// SAFETY: `FRAME` is null-terminated and static.
+		let frame = unsafe { details::discontinuous_frame(FRAME.as_ptr()) };
+		// `Frame` can't be stored in a static, so it is recreated to
+		// end the segment instead.
+		std::mem::forget(frame);
+		*current = Some((name.to_string(), Instant::now()));
+	}
+}
+
+/// Ends the current capture segment, if there is one.
+///
+/// The current segment is also ended when the capture is dropped.
+///
+/// Refer to [`begin_named_segment`] for the details.
+pub fn end_segment() {
+	#[cfg(feature = "enabled")]
+	if let Some(current) = CURRENT.lock().unwrap_or_else(|e| e.into_inner()).take() {
+		end(current);
+	}
+}
+
+#[cfg(feature = "enabled")]
+fn end((name, started): (String, Instant)) {
+	drop(crate::Frame(FRAME.as_ptr()));
+	details::message_size_color(
+		&format!("Segment end: {name} ({:.3?})", started.elapsed()),
+		Color::GOLD,
+	);
+}
+
+#[cfg(all(test, feature = "enabled"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn segments_are_sequential() {
+		let tracy = crate::start_capture();
+		begin_named_segment("first");
+		begin_named_segment("second");
+		assert_eq!(CURRENT.lock().unwrap().as_ref().map(|s| s.0.as_str()), Some("second"));
+		end_segment();
+		end_segment();
+		begin_named_segment("dangling");
+		drop(tracy);
+		assert!(CURRENT.lock().unwrap().is_none());
+	}
+}
@@ -0,0 +1,145 @@
+//! Self-profiling of the crate's own glue.
+//!
+//! When `self-profile` feature is enabled, every emission is counted
+//! and the rates are periodically reported as plots together with
+//! the interner size, so it is possible to verify the instrumentation
+//! itself isn't a bottleneck.
+//! Otherwise, the counting is compiled out completely.
+
+#[cfg(feature = "self-profile")]
+use std::{
+	ffi::CStr,
+	sync::atomic::{AtomicU64, Ordering},
+	time::{Duration, Instant},
+};
+
+#[cfg(feature = "self-profile")]
+use crate::background::Background;
+
+#[cfg(feature = "self-profile")]
+static EVENTS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "self-profile")]
+static TEXT_BYTES: AtomicU64 = AtomicU64::new(0);
+/// The last reported events rate, as `f64` bits.
+#[cfg(all(feature = "self-profile", feature = "debug-http"))]
+static EVENTS_RATE: AtomicU64 = AtomicU64::new(0);
+
+/// Counts a single event emitted to Tracy.
+#[inline(always)]
+pub(crate) fn count_event() {
+	#[cfg(feature = "self-profile")]
+	EVENTS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts an event carrying the dynamic text, which Tracy has to
+/// copy.
+#[inline(always)]
+pub(crate) fn count_text(len: usize) {
+	#[cfg(feature = "self-profile")]
+	{
+		EVENTS.fetch_add(1, Ordering::Relaxed);
+		TEXT_BYTES.fetch_add(len as u64, Ordering::Relaxed);
+	}
+	#[cfg(not(feature = "self-profile"))]
+	{
+		// Silences unused variable warning.
+		_ = len;
+	}
+}
+
+/// Returns the last reported amount of events per second, if the
+/// `self-profile` feature is enabled.
+#[cfg(feature = "debug-http")]
+pub(crate) fn events_per_sec() -> Option<f64> {
+	#[cfg(feature = "self-profile")]
+	return Some(f64::from_bits(EVENTS_RATE.load(Ordering::Relaxed)));
+
+	#[cfg(not(feature = "self-profile"))]
+	None
+}
+
+#[cfg(feature = "self-profile")]
+const EVENTS_PLOT:   &CStr = c"tracy-gizmos: events/s";
+#[cfg(feature = "self-profile")]
+const TEXT_PLOT:     &CStr = c"tracy-gizmos: text bytes/s";
+#[cfg(feature = "self-profile")]
+const INTERNER_PLOT: &CStr = c"tracy-gizmos: interner size";
+
+/// Starts the periodic reporting of the counted data.
+#[cfg(feature = "self-profile")]
+pub(crate) fn spawn() -> Background {
+	const PERIOD: Duration = Duration::from_secs(1);
+
+	Background::spawn("tracy-gizmos self-profiler", |stop| {
+		// SAFETY: Capture is alive until we are stopped.
+		unsafe {
+			for (name, format) in [
+				(EVENTS_PLOT,   sys::TracyPlotFormatNumber),
+				(TEXT_PLOT,     sys::TracyPlotFormatNumber),
+				(INTERNER_PLOT, sys::TracyPlotFormatMemory),
+			] {
+				sys::___tracy_emit_plot_config(
+					name.as_ptr(),
+					format,
+					0, // Staircase.
+					1, // Filled.
+					0, // Any color.
+				);
+			}
+		}
+
+		let mut last = Instant::now();
+		while !stop.wait(PERIOD) {
+			let now = Instant::now();
+			report((now - last).as_secs_f64());
+			last = now;
+		}
+	})
+}
+
+/// Reports the data counted during the elapsed seconds.
+#[cfg(feature = "self-profile")]
+fn report(elapsed: f64) {
+	let events = EVENTS.swap(0, Ordering::Relaxed) as f64;
+	let bytes  = TEXT_BYTES.swap(0, Ordering::Relaxed) as f64;
+	#[cfg(feature = "debug-http")]
+	EVENTS_RATE.store((events / elapsed).to_bits(), Ordering::Relaxed);
+	plot(EVENTS_PLOT,   events / elapsed);
+	plot(TEXT_PLOT,     bytes  / elapsed);
+	plot(INTERNER_PLOT, crate::interner_stats().bytes as f64);
+}
+
+#[cfg(feature = "self-profile")]
+fn plot(name: &CStr, value: f64) {
+	#[cfg(test)]
+	if crate::record::push(|| crate::record::Event::Plot(name.to_string_lossy().into_owned(), value)) {
+		return;
+	}
+	// SAFETY: Capture is alive until the reporting is stopped.
+	unsafe {
+		sys::___tracy_emit_plot(name.as_ptr(), value);
+	}
+}
+
+#[cfg(all(test, feature = "self-profile"))]
+mod tests {
+	use super::*;
+	use crate::record::Event::*;
+
+	#[test]
+	fn self_profile_plots() {
+		let name = "self-profiled interned name";
+		crate::intern::intern(name);
+
+		let events = crate::record::record(|| report(1.0));
+		let [Plot(events_name, _), Plot(text_name, _), Plot(interner_name, interner)] = &events[..] else {
+			panic!("Only plots are expected, got: {events:?}");
+		};
+		// Rates are reset by the self-profilers of the concurrent
+		// tests' captures, so only the interner size is reliable.
+		assert_eq!(events_name, "tracy-gizmos: events/s");
+		assert_eq!(text_name, "tracy-gizmos: text bytes/s");
+		assert_eq!(interner_name, "tracy-gizmos: interner size");
+		assert!(*interner >= (name.len() + 1) as f64);
+	}
+}
@@ -0,0 +1,89 @@
+#[cfg(feature = "enabled")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Id of the active capture, `0` means there is none.
+#[cfg(feature = "enabled")]
+static ID: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the random id of the active capture, if there is one.
+///
+/// The id is generated anew for every capture and is also added to
+/// the [`app_info`] as `Capture id: <id>`, where `<id>` is 16
+/// lowercase hex digits (`{:016x}`). Write it to external logs, so
+/// they could be joined with the capture later.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// let _tracy = start_capture();
+/// if let Some(id) = capture_id() {
+///     eprintln!("Tracy capture id: {id:016x}");
+/// }
+/// ```
+///
+/// [`app_info`]: crate::app_info
+pub fn capture_id() -> Option<u64> {
+	#[cfg(feature = "enabled")]
+	match ID.load(Ordering::Relaxed) {
+		0  => None,
+		id => Some(id),
+	}
+
+	#[cfg(not(feature = "enabled"))]
+	None
+}
+
+/// Generates the id for a just started capture and reports it.
+#[cfg(feature = "enabled")]
+pub(crate) fn begin() {
+	let id = random_id();
+	ID.store(id, Ordering::Relaxed);
+
+	const PREFIX: &[u8] = b"Capture id: ";
+	let mut info = [0; PREFIX.len() + 16];
+	info[..PREFIX.len()].copy_from_slice(PREFIX);
+	for (i, digit) in info[PREFIX.len()..].iter_mut().enumerate() {
+		*digit = b"0123456789abcdef"[(id >> (60 - i * 4)) as usize & 0xf];
+	}
+	// SAFETY: Only ASCII is written.
+	crate::app_info(unsafe { std::str::from_utf8_unchecked(&info) });
+}
+
+#[cfg(feature = "enabled")]
+pub(crate) fn end() {
+	ID.store(0, Ordering::Relaxed);
+}
+
+#[cfg(feature = "enabled")]
+fn random_id() -> u64 {
+	use std::collections::hash_map::RandomState;
+	use std::hash::{BuildHasher, Hasher};
+	use std::time::SystemTime;
+
+	// `RandomState` is randomly seeded per process, the rest makes
+	// consecutive captures differ.
+	let mut hasher = RandomState::new().build_hasher();
+	hasher.write_u32(std::process::id());
+	if let Ok(now) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+		hasher.write_u128(now.as_nanos());
+	}
+	// Zero is reserved for no capture.
+	hasher.finish().max(1)
+}
+
+#[cfg(all(test, feature = "enabled"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ids_are_per_capture() {
+		assert_eq!(capture_id(), None);
+		let tracy = crate::start_capture();
+		let first = capture_id().unwrap();
+		drop(tracy);
+		assert_eq!(capture_id(), None);
+		let _tracy = crate::start_capture();
+		assert_ne!(capture_id().unwrap(), first);
+	}
+}
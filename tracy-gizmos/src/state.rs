@@ -0,0 +1,118 @@
+#[cfg(feature = "enabled")]
+use std::ffi::CStr;
+
+#[cfg(feature = "enabled")]
+use crate::{Plot, PlotConfig, PlotEmit, PlotStyle};
+
+/// Instrumented state machine.
+///
+/// Standardizes how discrete state timelines are represented in
+/// captures. Every state gets an index in order of its first
+/// appearance, the current state index is plotted as a staircase
+/// under the track name and every transition is reported as a
+/// `<name>: <from> -> <to>` message.
+///
+/// The track itself works the same way, even if the instrumentation
+/// is disabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// let mut state = StateTrack::new("GameState");
+/// state.transition("Menu");
+/// state.transition("Loading");
+/// state.transition("Playing");
+/// assert_eq!(state.current(), Some("Playing"));
+/// ```
+pub struct StateTrack {
+	states:  Vec<String>,
+	current: Option<usize>,
+	#[cfg(feature = "enabled")]
+	name:    &'static CStr,
+	#[cfg(feature = "enabled")]
+	plot:    Plot,
+}
+
+impl StateTrack {
+	/// Creates a track without a current state.
+	///
+	/// The plot name is interned and never freed, as Tracy requires
+	/// it to live until the end of the program, refer to
+	/// [`set_interner_cap`].
+	///
+	/// # Panics
+	///
+	/// Panics if the `name` contains a null byte.
+	///
+	/// [`set_interner_cap`]: crate::set_interner_cap
+	pub fn new(name: &'static str) -> Self {
+		assert!(!name.contains('\0'), "State track name should not contain null bytes.");
+		#[cfg(feature = "enabled")]
+		let name: &'static CStr = crate::intern::intern(name);
+		Self {
+			states:  Vec::new(),
+			current: None,
+			#[cfg(feature = "enabled")]
+			name,
+			#[cfg(feature = "enabled")]
+			plot:    Plot::with_config(name, PlotConfig {
+				style: PlotStyle::Staircase,
+				..Default::default()
+			}),
+		}
+	}
+
+	/// Transitions to the given state.
+	///
+	/// Transition to the current state does nothing.
+	pub fn transition(&mut self, to: &str) {
+		let index = match self.states.iter().position(|s| s == to) {
+			Some(index) => index,
+			None        => {
+				self.states.push(to.to_string());
+				self.states.len() - 1
+			},
+		};
+		if self.current == Some(index) {
+			return;
+		}
+
+		#[cfg(feature = "enabled")]
+		{
+			self.plot.emit(index as i64);
+			let name = self.name.to_string_lossy();
+			let text = match self.current {
+				Some(from) => format!("{name}: {} -> {to}", self.states[from]),
+				None       => format!("{name}: {to}"),
+			};
+			crate::details::message_size(&text);
+		}
+		self.current = Some(index);
+	}
+
+	/// Returns the current state, if there was any transition.
+	pub fn current(&self) -> Option<&str> {
+		self.current.map(|i| self.states[i].as_str())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn tracks_states() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let mut state = StateTrack::new("test state");
+		assert_eq!(state.current(), None);
+		state.transition("a");
+		state.transition("b");
+		state.transition("b");
+		state.transition("a");
+		assert_eq!(state.current(), Some("a"));
+		assert_eq!(state.current, Some(0));
+		assert_eq!(state.states, ["a", "b"]);
+	}
+}
@@ -0,0 +1,254 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+#[cfg(feature = "enabled")]
+use std::panic::Location;
+#[cfg(feature = "enabled")]
+use std::sync::Mutex;
+#[cfg(feature = "enabled")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "enabled")]
+use crate::{Color, Plot, PlotEmit, ZoneLocation};
+
+/// Instrumentation of asynchronous streams.
+///
+/// Streaming pipelines, e.g. network decoders or file scanners, do
+/// most of their work piece by piece, while being polled. The
+/// instrumented stream emits a zone for every `poll_next`, which
+/// yields an item, and plots the yielded items per second as
+/// `<name>: items/s`. Polls, which yield nothing, are not emitted,
+/// unless something happens inside of them.
+///
+/// Requires `futures` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use futures_core::Stream;
+/// use tracy_gizmos::TracyStreamExt;
+///
+/// fn decoded(packets: impl Stream<Item = Vec<u8>>) -> impl Stream<Item = Vec<u8>> {
+///     packets.instrument_items("decode packet")
+/// }
+/// ```
+pub trait TracyStreamExt: Stream + Sized {
+	/// Instruments the stream, refer to [`TracyStreamExt`] for the
+	/// details.
+	///
+	/// Zone locations are interned per name & call site, so prefer a
+	/// fixed set of names.
+	#[track_caller]
+	fn instrument_items(self, name: &'static str) -> InstrumentedStream<Self> {
+		InstrumentedStream {
+			stream: self,
+			#[cfg(feature = "enabled")]
+			probe:  intern(name, Location::caller()),
+			#[cfg(feature = "enabled")]
+			items:  0,
+			#[cfg(feature = "enabled")]
+			window: Instant::now(),
+		}
+	}
+}
+
+impl<S: Stream> TracyStreamExt for S {}
+
+/// A stream instrumented via [`TracyStreamExt::instrument_items`].
+pub struct InstrumentedStream<S> {
+	stream: S,
+	#[cfg(feature = "enabled")]
+	probe:  &'static Probe,
+	#[cfg(feature = "enabled")]
+	items:  u64,
+	#[cfg(feature = "enabled")]
+	window: Instant,
+}
+
+impl<S> InstrumentedStream<S> {
+	/// Returns the inner stream.
+	pub fn into_inner(self) -> S {
+		let this = std::mem::ManuallyDrop::new(self);
+		#[cfg(feature = "enabled")]
+		this.emit_rate();
+		// SAFETY: `this` is never dropped, so the stream is moved out
+		// only once.
+		unsafe { std::ptr::read(&this.stream) }
+	}
+
+	/// Emits the items rate since the window start.
+	#[cfg(feature = "enabled")]
+	fn emit_rate(&self) {
+		let elapsed = self.window.elapsed();
+		if self.items != 0 && !elapsed.is_zero() {
+			self.probe.plot.emit(self.items as f64 / elapsed.as_secs_f64());
+		}
+	}
+}
+
+impl<S: Stream> Stream for InstrumentedStream<S> {
+	type Item = S::Item;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+		// SAFETY: The stream is structurally pinned, it is never
+		// moved out of the pinned `self`.
+		let this   = unsafe { self.get_unchecked_mut() };
+		let stream = unsafe { Pin::new_unchecked(&mut this.stream) };
+
+		#[cfg(feature = "enabled")]
+		{
+			let mut zone = ItemZone::begin(&this.probe.location);
+			let polled   = stream.poll_next(cx);
+			if let Poll::Ready(Some(_)) = polled {
+				zone.yielded = true;
+				drop(zone);
+				this.item_yielded();
+			}
+			polled
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		stream.poll_next(cx)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.stream.size_hint()
+	}
+}
+
+#[cfg(feature = "enabled")]
+impl<S> InstrumentedStream<S> {
+	/// Plots the items rate once per window.
+	fn item_yielded(&mut self) {
+		const WINDOW: Duration = Duration::from_secs(1);
+
+		self.items += 1;
+		if self.window.elapsed() >= WINDOW {
+			self.emit_rate();
+			self.items  = 0;
+			self.window = Instant::now();
+		}
+	}
+}
+
+#[cfg(feature = "enabled")]
+impl<S> Drop for InstrumentedStream<S> {
+	fn drop(&mut self) {
+		// The last window is likely incomplete, but it still counts.
+		self.emit_rate();
+	}
+}
+
+/// Zone location & rate plot of the instrumented stream.
+#[cfg(feature = "enabled")]
+struct Probe {
+	name:     &'static str,
+	caller:   &'static Location<'static>,
+	location: ZoneLocation,
+	plot:     Plot,
+}
+
+/// Returns the probe of the given name & call site, which is
+/// allocated once and never freed, as Tracy requires. Its strings
+/// are interned.
+#[cfg(feature = "enabled")]
+fn intern(name: &'static str, caller: &'static Location<'static>) -> &'static Probe {
+	static PROBES: Mutex<Vec<&'static Probe>> = Mutex::new(Vec::new());
+
+	let mut probes = PROBES.lock().unwrap_or_else(|e| e.into_inner());
+	if let Some(probe) = probes.iter().find(|p| p.name == name && p.caller == caller) {
+		return probe;
+	}
+	let probe = Box::leak(Box::new(Probe {
+		name,
+		caller,
+		location: ZoneLocation::new(
+			crate::intern::intern(name),
+			c"TracyStreamExt::instrument_items",
+			crate::intern::intern(caller.file()),
+			caller.line(),
+			Color::UNSPECIFIED,
+		),
+		plot: Plot::new(crate::intern::intern(&format!("{name}: items/s"))),
+	}));
+	probes.push(probe);
+	probe
+}
+
+/// A lazy zone of a single poll, which is emitted only if an item was
+/// yielded or something happened inside of it.
+#[cfg(feature = "enabled")]
+struct ItemZone {
+	yielded: bool,
+}
+
+#[cfg(feature = "enabled")]
+impl ItemZone {
+	fn begin(location: &'static ZoneLocation) -> Self {
+		// SAFETY: Location is static.
+		unsafe { crate::lazy::begin(&location.0) };
+		Self { yielded: false }
+	}
+}
+
+#[cfg(feature = "enabled")]
+impl Drop for ItemZone {
+	fn drop(&mut self) {
+		crate::lazy::end(self.yielded.then_some(Duration::ZERO));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::task::Waker;
+
+	use super::*;
+
+	struct Countdown(u32);
+
+	impl Stream for Countdown {
+		type Item = u32;
+
+		fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<u32>> {
+			// Every other poll is pending, to exercise skipped zones.
+			if self.0 % 2 == 1 {
+				self.0 -= 1;
+				cx.waker().wake_by_ref();
+				return Poll::Pending;
+			}
+			if self.0 == 0 {
+				return Poll::Ready(None);
+			}
+			self.0 -= 1;
+			Poll::Ready(Some(self.0))
+		}
+	}
+
+	#[test]
+	fn instrumented_streams() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let mut cx    = Context::from_waker(Waker::noop());
+		let mut items = Vec::new();
+		let mut stream = Countdown(8).instrument_items("countdown");
+		loop {
+			match Pin::new(&mut stream).poll_next(&mut cx) {
+				Poll::Ready(Some(item)) => items.push(item),
+				Poll::Ready(None)       => break,
+				Poll::Pending           => {},
+			}
+		}
+		assert_eq!(items, [7, 5, 3, 1]);
+		assert_eq!(stream.into_inner().0, 0);
+
+		#[cfg(feature = "enabled")]
+		{
+			let here = Location::caller();
+			let a    = intern("interned", here);
+			let b    = intern("interned", here);
+			assert!(std::ptr::eq(a, b));
+		}
+	}
+}
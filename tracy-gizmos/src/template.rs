@@ -0,0 +1,262 @@
+#[cfg(feature = "enabled")]
+use std::cell::RefCell;
+#[cfg(feature = "enabled")]
+use std::collections::HashMap;
+#[cfg(feature = "enabled")]
+use std::hash::{DefaultHasher, Hash, Hasher};
+#[cfg(feature = "enabled")]
+use std::panic::Location;
+#[cfg(feature = "enabled")]
+use std::sync::Mutex;
+
+#[cfg(feature = "enabled")]
+use crate::{Color, ZoneLocation};
+use crate::Zone;
+
+/// A zone name template, which interns every distinct formatted name.
+///
+/// Hot sites often need semi-dynamic names, e.g. `load textures` and
+/// `load audio`, which come from a small set of values. Regular
+/// dynamic zones copy the name on every use, while the template
+/// interns a static zone location per distinct name on its first
+/// use, so the following zones cost almost as much as [`zone!`].
+///
+/// The template is parsed at compile-time and must contain a single
+/// `{}` placeholder. Locations are interned per call site, so zones
+/// of the same template used in several places keep their own source
+/// locations. Once interned, a location is found in a thread-local
+/// cache without any locking.
+///
+/// Tracy requires interned locations to live until the end of the
+/// program, so they are never freed or evicted. To keep the memory
+/// bounded, at most [`ZoneTemplate::DEFAULT_CAPACITY`] (or the given
+/// capacity) locations are interned per template, while the rest of
+/// the names are copied as for [`Zone::begin_dynamic`]. The same
+/// happens, once the global name interner is full, refer to
+/// [`set_interner_cap`].
+///
+/// Requires `no-dynamic-strings` feature to be disabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn load(kind: &str) {}
+/// static LOAD: ZoneTemplate = ZoneTemplate::new("load {}");
+///
+/// for kind in ["textures", "audio", "textures"] {
+///     let _loading = LOAD.zone(kind);
+///     load(kind);
+/// }
+/// ```
+///
+/// [`zone!`]: crate::zone!
+/// [`set_interner_cap`]: crate::set_interner_cap
+pub struct ZoneTemplate {
+	template: &'static str,
+	hole:     usize,
+	#[cfg(feature = "enabled")]
+	capacity: usize,
+	#[cfg(feature = "enabled")]
+	interned: Mutex<Option<HashMap<Site, &'static ZoneLocation>>>,
+}
+
+/// Formatted value & the call site, which the location is interned
+/// for.
+#[cfg(feature = "enabled")]
+type Site = (Box<str>, &'static str, u32);
+
+/// Template address, call site file & line and the value hash.
+#[cfg(feature = "enabled")]
+type CacheKey = (usize, usize, u32, u64);
+
+#[cfg(feature = "enabled")]
+thread_local! {
+	/// Locations already interned, which are looked up without
+	/// locking.
+	static CACHE: RefCell<HashMap<CacheKey, &'static ZoneLocation>> = RefCell::new(HashMap::new());
+}
+
+impl ZoneTemplate {
+	/// Default amount of names interned per template.
+	pub const DEFAULT_CAPACITY: usize = 64;
+
+	/// Creates the template, which must contain a single `{}`
+	/// placeholder.
+	pub const fn new(template: &'static str) -> Self {
+		Self::with_capacity(template, Self::DEFAULT_CAPACITY)
+	}
+
+	/// Creates the template, which interns at most `capacity` names.
+	pub const fn with_capacity(template: &'static str, capacity: usize) -> Self {
+		Self {
+			template,
+			hole:     find_hole(template.as_bytes()),
+			#[cfg(feature = "enabled")]
+			capacity,
+			#[cfg(feature = "enabled")]
+			interned: Mutex::new(None),
+		}
+	}
+
+	/// Returns the zone name formatted with the given value.
+	pub fn format(&self, value: &str) -> String {
+		let (prefix, suffix) = self.template.split_at(self.hole);
+		[prefix, value, &suffix[2..]].concat()
+	}
+
+	/// Begins a zone named after the template formatted with the
+	/// given value. The zone ends when dropped.
+	#[track_caller]
+	pub fn zone(&self, value: &str) -> Zone {
+		#[cfg(feature = "enabled")]
+		{
+			let caller = Location::caller();
+			match self.cached(value, caller).or_else(|| self.intern(value, caller)) {
+				// SAFETY: Interned locations are leaked, hence static.
+				Some(location) => unsafe { crate::details::zone(location, 1) },
+				None           => Zone::begin_copied(&self.format(value), b"ZoneTemplate::zone", caller),
+			}
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		Zone::new()
+	}
+
+	/// Returns the location of the formatted name, if it was
+	/// interned and seen by the current thread already.
+	#[cfg(feature = "enabled")]
+	#[inline(always)]
+	fn cached(&self, value: &str, caller: &'static Location) -> Option<&'static ZoneLocation> {
+		let key = self.cache_key(value, caller);
+		let location = CACHE.with_borrow(|cache| cache.get(&key).copied())?;
+		// Hashes could collide, so the name is compared as well.
+		let (prefix, suffix) = self.template.split_at(self.hole);
+		let suffix = &suffix[2..];
+		let name   = location.name();
+		let same   = name.len() == prefix.len() + value.len() + suffix.len()
+			&& name.starts_with(prefix)
+			&& name.ends_with(suffix)
+			&& &name[prefix.len()..name.len() - suffix.len()] == value;
+		same.then_some(location)
+	}
+
+	#[cfg(feature = "enabled")]
+	fn cache_key(&self, value: &str, caller: &'static Location) -> CacheKey {
+		let mut hasher = DefaultHasher::new();
+		value.hash(&mut hasher);
+		(self as *const Self as usize, caller.file().as_ptr() as usize, caller.line(), hasher.finish())
+	}
+
+	/// Returns the interned location of the formatted name at the
+	/// call site, unless the template is full.
+	#[cfg(feature = "enabled")]
+	#[cold]
+	fn intern(&self, value: &str, caller: &'static Location) -> Option<&'static ZoneLocation> {
+		let mut interned = self.interned.lock().unwrap_or_else(|e| e.into_inner());
+		let interned     = interned.get_or_insert_with(HashMap::new);
+		let site         = (value.into(), caller.file(), caller.line());
+		let location     = match interned.get(&site) {
+			Some(location) => *location,
+			None           => {
+				if interned.len() >= self.capacity {
+					return None;
+				}
+				// Names are copied instead of being reported as
+				// `overflow`, once the interner is full.
+				let name     = crate::intern::try_intern(&self.format(value))?;
+				let file     = crate::intern::try_intern(caller.file())?;
+				let location = &*Box::leak(Box::new(ZoneLocation::new(
+					name,
+					c"ZoneTemplate::zone",
+					file,
+					caller.line(),
+					Color::UNSPECIFIED,
+				)));
+				interned.insert(site, location);
+				location
+			},
+		};
+		let key = self.cache_key(value, caller);
+		CACHE.with_borrow_mut(|cache| cache.insert(key, location));
+		Some(location)
+	}
+}
+
+const fn find_hole(template: &[u8]) -> usize {
+	let mut hole = None;
+	let mut i    = 0;
+	while i + 1 < template.len() {
+		if template[i] == b'{' && template[i + 1] == b'}' {
+			if hole.is_some() {
+				panic!("Zone template should contain a single `{{}}` placeholder.");
+			}
+			hole = Some(i);
+			i += 1;
+		}
+		i += 1;
+	}
+	match hole {
+		Some(hole) => hole,
+		None       => panic!("Zone template should contain a single `{{}}` placeholder."),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn formats_names() {
+		assert_eq!(ZoneTemplate::new("load {}").format("audio"), "load audio");
+		assert_eq!(ZoneTemplate::new("{}").format("tick"), "tick");
+		assert_eq!(ZoneTemplate::new("[{}] step").format("a"), "[a] step");
+	}
+
+	#[test]
+	#[should_panic]
+	fn rejects_several_placeholders() {
+		find_hole(b"{} to {}");
+	}
+
+	#[test]
+	fn templated_zones() {
+		static LOAD: ZoneTemplate = ZoneTemplate::with_capacity("load {}", 2);
+
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		for kind in ["textures", "audio", "textures", "shaders"] {
+			let loading = LOAD.zone(kind);
+			loading.text(kind);
+		}
+		#[cfg(feature = "enabled")]
+		{
+			let interned = LOAD.interned.lock().unwrap();
+			let mut values = interned.as_ref().unwrap().keys().map(|(value, ..)| &**value).collect::<Vec<_>>();
+			values.sort();
+			assert_eq!(values, ["audio", "textures"]);
+		}
+
+		// Every call site gets its own location.
+		#[cfg(feature = "enabled")]
+		{
+			static SITES: ZoneTemplate = ZoneTemplate::new("site {}");
+
+			let first  = SITES.zone("a");
+			let second = SITES.zone("a");
+			drop((second, first));
+			let interned  = SITES.interned.lock().unwrap();
+			let mut lines = interned.as_ref().unwrap().values().map(|location| location.line()).collect::<Vec<_>>();
+			lines.sort();
+			assert_eq!(lines.len(), 2);
+			assert!(lines[0] < lines[1]);
+			drop(interned);
+
+			let caller = Location::caller();
+			assert!(SITES.cached("a", caller).is_none());
+			let location = SITES.intern("a", caller).unwrap();
+			assert!(std::ptr::eq(SITES.cached("a", caller).unwrap(), location));
+			assert!(SITES.cached("b", caller).is_none());
+		}
+	}
+}
@@ -0,0 +1,298 @@
+//! Thread helpers.
+
+use std::io;
+use std::thread::{Builder, JoinHandle};
+#[cfg(feature = "enabled")]
+use std::sync::atomic::AtomicBool;
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+use std::sync::atomic::Ordering;
+
+#[cfg(feature = "enabled")]
+use crate::{Color, ZoneLocation};
+use crate::Zone;
+
+#[cfg(feature = "enabled")]
+pub(crate) static LIFECYCLE_MESSAGES: AtomicBool = AtomicBool::new(false);
+
+/// Spawns a new thread, which is named both for the operating system
+/// and for Tracy.
+///
+/// If [`CaptureOptions::thread_lifecycle_messages`] is set, thread
+/// start & exit are also reported via messages, refer to
+/// [`emit_thread_started`].
+///
+/// # Errors
+///
+/// Same as [`std::thread::Builder::spawn`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn loop_and_do_io() {}
+/// let io = thread::spawn_named("I/O", || {
+///     zone!("I/O requests");
+///     loop_and_do_io();
+/// }).unwrap();
+/// io.join().unwrap();
+/// ```
+///
+/// [`CaptureOptions::thread_lifecycle_messages`]: crate::CaptureOptions::thread_lifecycle_messages
+pub fn spawn_named<F, T>(name: impl Into<String>, f: F) -> io::Result<JoinHandle<T>>
+where
+	F: FnOnce() -> T + Send + 'static,
+	T: Send + 'static,
+{
+	let name = name.into();
+	#[cfg(feature = "enabled")]
+	let tracy_name = std::ffi::CString::new(name.as_str()).ok();
+
+	Builder::new().name(name).spawn(move || {
+		#[cfg(feature = "enabled")]
+		if let Some(name) = tracy_name {
+			// SAFETY: CString is null-terminated.
+			unsafe {
+				crate::details::set_thread_name(name.as_ptr().cast());
+			}
+		}
+
+		emit_thread_started();
+		// Exit is reported even if `f` panics.
+		let _exit = ExitGuard;
+		f()
+	})
+}
+
+/// Emits a "thread started" message with the current thread name &
+/// id, if [`CaptureOptions::thread_lifecycle_messages`] is set.
+///
+/// It is useful to make short-lived thread churn visible even without
+/// system tracing privileges. Thread pools usually allow to hook
+/// thread start & exit, which are perfect places to call this and
+/// [`emit_thread_exiting`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn do_work() {}
+/// std::thread::spawn(|| {
+///     thread::emit_thread_started();
+///     do_work();
+///     thread::emit_thread_exiting();
+/// });
+/// ```
+///
+/// [`CaptureOptions::thread_lifecycle_messages`]: crate::CaptureOptions::thread_lifecycle_messages
+#[inline(always)]
+pub fn emit_thread_started() {
+	#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+	emit_lifecycle("started");
+}
+
+/// Emits a "thread exiting" message with the current thread name &
+/// id, if [`CaptureOptions::thread_lifecycle_messages`] is set.
+///
+/// Refer to [`emit_thread_started`] for details.
+///
+/// [`CaptureOptions::thread_lifecycle_messages`]: crate::CaptureOptions::thread_lifecycle_messages
+#[inline(always)]
+pub fn emit_thread_exiting() {
+	#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+	emit_lifecycle("exiting");
+}
+
+/// Makes the threads of the `rayon` pool report their start & exit,
+/// refer to [`emit_thread_started`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// let pool = thread::rayon_lifecycle(rayon_core::ThreadPoolBuilder::new())
+///     .build()
+///     .unwrap();
+/// ```
+#[cfg(feature = "rayon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub fn rayon_lifecycle<S>(builder: rayon_core::ThreadPoolBuilder<S>) -> rayon_core::ThreadPoolBuilder<S> {
+	builder
+		.start_handler(|_| emit_thread_started())
+		.exit_handler(|_| emit_thread_exiting())
+}
+
+/// Makes the threads of the `tokio` runtime report their start &
+/// exit, refer to [`emit_thread_started`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// let runtime = thread::tokio_lifecycle(&mut tokio::runtime::Builder::new_current_thread())
+///     .build()
+///     .unwrap();
+/// ```
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub fn tokio_lifecycle(builder: &mut tokio::runtime::Builder) -> &mut tokio::runtime::Builder {
+	builder
+		.on_thread_start(emit_thread_started)
+		.on_thread_stop(emit_thread_exiting)
+}
+
+#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+fn emit_lifecycle(what: &str) {
+	if !LIFECYCLE_MESSAGES.load(Ordering::Acquire) {
+		return;
+	}
+	let thread = std::thread::current();
+	let text   = format!(
+		"Thread {what}: {} ({:?})",
+		thread.name().unwrap_or("<unnamed>"),
+		thread.id(),
+	);
+	crate::details::message_size(&text);
+}
+
+/// Activity of a job system worker thread.
+///
+/// Without the system tracing privileges, Tracy can't show when a
+/// worker is actually running or waiting for jobs. This approximates
+/// it: the whole thread timeline is covered by alternating gray
+/// `Worker idle` & green `Worker active` zones, so idle gaps are easy
+/// to spot.
+///
+/// States should be switched at the top level of the worker loop,
+/// when no other zones are active on the thread. The current zone
+/// ends when the activity is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn wait_for_job() -> Option<u32> { None }
+/// # fn run(job: u32) {}
+/// let mut activity = thread::WorkerActivity::new();
+/// loop {
+///     activity.enter_idle();
+///     let Some(job) = wait_for_job() else { break };
+///     activity.enter_active();
+///     run(job);
+/// }
+/// ```
+#[derive(Default)]
+pub struct WorkerActivity {
+	zone:   Option<Zone>,
+	active: bool,
+}
+
+impl WorkerActivity {
+	/// Creates an activity, which is in no state yet.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Switches to the idle state, does nothing if already idle.
+	pub fn enter_idle(&mut self) {
+		self.enter(false);
+	}
+
+	/// Switches to the active state, does nothing if already active.
+	pub fn enter_active(&mut self) {
+		self.enter(true);
+	}
+
+	/// Returns `true`, if the worker is in the active state.
+	pub fn is_active(&self) -> bool {
+		self.zone.is_some() && self.active
+	}
+
+	fn enter(&mut self, active: bool) {
+		if self.zone.is_some() && self.active == active {
+			return;
+		}
+		// Previous zone must end first.
+		self.zone   = None;
+		self.zone   = Some(activity_zone(active));
+		self.active = active;
+	}
+}
+
+fn activity_zone(active: bool) -> Zone {
+	#[cfg(feature = "enabled")]
+	{
+		const FILE: &str = concat!(file!(), '\0');
+		// SAFETY: All passed strings are static & null-terminated.
+		static IDLE: ZoneLocation = unsafe {
+			crate::details::zone_location("Worker idle\0", b"WorkerActivity\0", FILE, line!(), Color::GRAY.as_u32())
+		};
+		// SAFETY: All passed strings are static & null-terminated.
+		static ACTIVE: ZoneLocation = unsafe {
+			crate::details::zone_location("Worker active\0", b"WorkerActivity\0", FILE, line!(), Color::LIME_GREEN.as_u32())
+		};
+		let location = if active { &ACTIVE } else { &IDLE };
+		// SAFETY: Locations are static.
+		unsafe { crate::details::zone(location, 1) }
+	}
+
+	#[cfg(not(feature = "enabled"))]
+	Zone::new()
+}
+
+struct ExitGuard;
+
+impl Drop for ExitGuard {
+	fn drop(&mut self) {
+		emit_thread_exiting();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn worker_activity_alternates() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let mut activity = WorkerActivity::new();
+		assert!(!activity.is_active());
+		activity.enter_idle();
+		activity.enter_idle();
+		assert!(!activity.is_active());
+		activity.enter_active();
+		activity.enter_active();
+		assert!(activity.is_active());
+		activity.enter_idle();
+		assert!(!activity.is_active());
+	}
+
+	#[cfg(any(feature = "rayon", feature = "tokio"))]
+	#[test]
+	fn pool_lifecycle() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture_with(crate::CaptureOptions {
+			thread_lifecycle_messages: true,
+			..Default::default()
+		});
+
+		#[cfg(feature = "rayon")]
+		{
+			let pool = rayon_lifecycle(rayon_core::ThreadPoolBuilder::new().num_threads(2))
+				.build()
+				.unwrap();
+			assert_eq!(pool.install(|| 42), 42);
+		}
+
+		#[cfg(feature = "tokio")]
+		{
+			let runtime = tokio_lifecycle(&mut tokio::runtime::Builder::new_current_thread())
+				.build()
+				.unwrap();
+			let result = runtime.block_on(async {
+				tokio::task::spawn_blocking(|| 42).await.unwrap()
+			});
+			assert_eq!(result, 42);
+		}
+	}
+}
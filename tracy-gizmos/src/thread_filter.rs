@@ -0,0 +1,167 @@
+//! Emission filtering by thread names.
+
+#[cfg(feature = "enabled")]
+use std::cell::{Cell, RefCell};
+#[cfg(feature = "enabled")]
+use std::ffi::CStr;
+#[cfg(feature = "enabled")]
+use std::sync::RwLock;
+#[cfg(feature = "enabled")]
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// Allows to skip the thread-local lookup on every emission, when
+/// there is no filter.
+#[cfg(feature = "enabled")]
+static FILTERING: AtomicBool = AtomicBool::new(false);
+/// Bumped on every filter change, so threads re-match their names.
+#[cfg(feature = "enabled")]
+static GENERATION: AtomicU32 = AtomicU32::new(0);
+#[cfg(feature = "enabled")]
+static PATTERNS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+#[cfg(feature = "enabled")]
+thread_local! {
+	/// Filter generation the thread name was matched against & the
+	/// result.
+	static MATCHED: Cell<(u32, bool)> = const { Cell::new((0, true)) };
+	/// Name given via `set_thread_name!`, which takes precedence over
+	/// the `std` one.
+	static NAME: RefCell<Option<Box<str>>> = const { RefCell::new(None) };
+}
+
+/// Sets the thread name patterns, so only matching threads emit
+/// anything into the capture.
+///
+/// It is useful to focus on a single subsystem of a process with
+/// plenty of threads. Patterns could contain `*` wildcards, which
+/// match any sequence of characters, e.g. `render*` or `*io*`. A
+/// thread name is either the one given via [`set_thread_name!`]
+/// (including [`thread::spawn_named`]), or the `std` one, while
+/// unnamed threads are matched by `*` only. An empty set of patterns
+/// removes the filter, which is the default.
+///
+/// Every thread matches its name only once per filter change, so the
+/// filter costs a thread-local lookup per emission. It is better to
+/// set the filter up front, as zones, which are active during the
+/// change, might end up not being closed in the capture.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// set_thread_filter(["main", "audio*"]);
+///
+/// let mixer = thread::spawn_named("audio mixer", || {
+///     zone!("mix"); // Emitted.
+/// }).unwrap();
+/// let io = thread::spawn_named("io", || {
+///     zone!("read"); // Not emitted.
+/// }).unwrap();
+/// ```
+///
+/// [`set_thread_name!`]: crate::set_thread_name!
+/// [`thread::spawn_named`]: crate::thread::spawn_named
+pub fn set_thread_filter<I>(patterns: I)
+where
+	I: IntoIterator,
+	I::Item: Into<String>,
+{
+	#[cfg(feature = "enabled")]
+	{
+		let mut current = PATTERNS.write().unwrap_or_else(|e| e.into_inner());
+		*current = patterns.into_iter().map(Into::into).collect();
+		GENERATION.fetch_add(1, Ordering::Release);
+		FILTERING.store(!current.is_empty(), Ordering::Release);
+	}
+}
+
+/// Returns the patterns set via [`set_thread_filter`].
+#[cfg(feature = "debug-http")]
+pub(crate) fn patterns() -> Vec<String> {
+	#[cfg(feature = "enabled")]
+	return PATTERNS.read().unwrap_or_else(|e| e.into_inner()).clone();
+
+	#[cfg(not(feature = "enabled"))]
+	Vec::new()
+}
+
+/// Returns `true`, if the current thread doesn't match the filter.
+#[cfg(feature = "enabled")]
+#[inline(always)]
+pub(crate) fn filtered_out() -> bool {
+	FILTERING.load(Ordering::Relaxed) && filtered_out_slow()
+}
+
+#[cfg(feature = "enabled")]
+#[inline(never)]
+fn filtered_out_slow() -> bool {
+	let generation = GENERATION.load(Ordering::Acquire);
+	let (matched_generation, matched) = MATCHED.get();
+	if matched_generation == generation {
+		return !matched;
+	}
+
+	let patterns = PATTERNS.read().unwrap_or_else(|e| e.into_inner());
+	let matched  = NAME
+		.try_with(|name| match &*name.borrow() {
+			Some(name) => matches_any(&patterns, name),
+			None       => matches_any(&patterns, std::thread::current().name().unwrap_or_default()),
+		})
+		.unwrap_or(true);
+	MATCHED.set((generation, matched));
+	!matched
+}
+
+/// Remembers the name given to the current thread, so it is matched
+/// against the filter.
+#[cfg(feature = "enabled")]
+pub(crate) fn set_name(name: &CStr) {
+	_ = NAME.try_with(|current| *current.borrow_mut() = Some(name.to_string_lossy().into()));
+	// Generations start from 1, so the name is re-matched.
+	MATCHED.set((0, true));
+}
+
+#[cfg(feature = "enabled")]
+fn matches_any(patterns: &[String], name: &str) -> bool {
+	patterns.iter().any(|pattern| matches(pattern, name))
+}
+
+/// Returns `true`, if the name matches the pattern, where `*` matches
+/// any sequence of characters.
+#[cfg(feature = "enabled")]
+fn matches(pattern: &str, name: &str) -> bool {
+	let mut parts = pattern.split('*');
+	let first     = parts.next().unwrap_or_default();
+	let Some(mut rest) = name.strip_prefix(first) else {
+		return false;
+	};
+	let Some(last) = parts.next_back() else {
+		return rest.is_empty();
+	};
+	for part in parts {
+		match rest.find(part) {
+			Some(i) => rest = &rest[i + part.len()..],
+			None    => return false,
+		}
+	}
+	rest.ends_with(last)
+}
+
+#[cfg(all(test, feature = "enabled"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn name_patterns() {
+		assert!(matches("main", "main"));
+		assert!(!matches("main", "main 2"));
+		assert!(matches("*", ""));
+		assert!(!matches("io", ""));
+		assert!(matches("render*", "render worker 3"));
+		assert!(matches("*worker", "render worker"));
+		assert!(!matches("*worker", "worker 1"));
+		assert!(matches("*io*", "async io pool"));
+		assert!(matches("a*b*c", "aXbYbc"));
+		assert!(!matches("ab*ba", "aba"));
+	}
+}
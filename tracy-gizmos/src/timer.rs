@@ -0,0 +1,148 @@
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "enabled")]
+use std::{ffi::CStr, panic::Location};
+
+use crate::Zone;
+#[cfg(feature = "enabled")]
+use crate::{Plot, PlotEmit};
+
+/// Instrumented periodic timer.
+///
+/// Helps to investigate why a periodic job is jittery. Every tick
+/// gets its own zone, the drift from the ideal schedule is plotted as
+/// `<name>: drift, ms` and skipped ticks are reported as messages.
+///
+/// The timer itself works the same way, even if the instrumentation
+/// is disabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use std::time::Duration;
+/// # fn save() {}
+/// let mut timer = RepeatingTimer::new("autosave", Duration::from_secs(30));
+/// loop {
+///     let _tick = timer.wait();
+///     save();
+/// }
+/// ```
+pub struct RepeatingTimer {
+	period:   Duration,
+	next:     Instant,
+	#[cfg(feature = "enabled")]
+	name:     &'static CStr,
+	#[cfg(feature = "enabled")]
+	drift:    Plot,
+	#[cfg(feature = "enabled")]
+	location: &'static Location<'static>,
+}
+
+impl RepeatingTimer {
+	/// Creates a timer, which first ticks after one `period` from now.
+	///
+	/// Zone & plot names are interned and never freed, as Tracy
+	/// requires them to live until the end of the program, refer to
+	/// [`set_interner_cap`].
+	///
+	/// # Panics
+	///
+	/// Panics if the `period` is zero or the `name` contains a null
+	/// byte.
+	///
+	/// [`set_interner_cap`]: crate::set_interner_cap
+	#[track_caller]
+	pub fn new(name: &'static str, period: Duration) -> Self {
+		assert!(!period.is_zero(), "Timer period should be non-zero.");
+		assert!(!name.contains('\0'), "Timer name should not contain null bytes.");
+		Self {
+			period,
+			next:     Instant::now() + period,
+			#[cfg(feature = "enabled")]
+			name:     crate::intern::intern(name),
+			#[cfg(feature = "enabled")]
+			drift:    Plot::new(crate::intern::intern(&format!("{name}: drift, ms"))),
+			#[cfg(feature = "enabled")]
+			location: Location::caller(),
+		}
+	}
+
+	/// Returns the timer period.
+	pub fn period(&self) -> Duration {
+		self.period
+	}
+
+	/// Blocks until the next tick and returns its zone.
+	///
+	/// If the timer is late by one or more periods, the missed ticks
+	/// are skipped, so the timer never tries to catch up.
+	pub fn wait(&mut self) -> Zone {
+		let now = Instant::now();
+		if now < self.next {
+			std::thread::sleep(self.next - now);
+		}
+
+		let tick = schedule(self.next, Instant::now(), self.period);
+		self.next = tick.next;
+
+		#[cfg(feature = "enabled")]
+		{
+			self.drift.emit(tick.late.as_secs_f64() * 1000.0);
+			if tick.skipped > 0 {
+				let name = self.name.to_string_lossy();
+				crate::details::message_size(&format!("{name}: skipped {} tick(s)", tick.skipped));
+			}
+			Zone::begin_cstr(self.name, c"RepeatingTimer::wait", self.location)
+		}
+		#[cfg(not(feature = "enabled"))]
+		Zone::new()
+	}
+}
+
+#[derive(Debug, PartialEq)]
+struct Tick {
+	late:    Duration,
+	skipped: u64,
+	next:    Instant,
+}
+
+fn schedule(scheduled: Instant, now: Instant, period: Duration) -> Tick {
+	let late    = now.saturating_duration_since(scheduled);
+	let skipped = (late.as_nanos() / period.as_nanos()) as u64;
+	let next    = scheduled + Duration::from_nanos((period.as_nanos() * (skipped as u128 + 1)) as u64);
+	Tick { late, skipped, next }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn skips_missed_ticks() {
+		let start  = Instant::now();
+		let period = Duration::from_millis(10);
+		let ms     = Duration::from_millis;
+
+		assert_eq!(
+			schedule(start, start + ms(3), period),
+			Tick { late: ms(3), skipped: 0, next: start + ms(10) },
+		);
+		assert_eq!(
+			schedule(start, start + ms(25), period),
+			Tick { late: ms(25), skipped: 2, next: start + ms(30) },
+		);
+	}
+
+	#[test]
+	fn ticks_periodically() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let start     = Instant::now();
+		let mut timer = RepeatingTimer::new("test timer", Duration::from_millis(1));
+		for _ in 0..3 {
+			let _tick = timer.wait();
+		}
+		assert!(start.elapsed() >= Duration::from_millis(3));
+	}
+}
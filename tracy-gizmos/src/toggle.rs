@@ -0,0 +1,168 @@
+#[cfg(feature = "enabled")]
+use std::ffi::CStr;
+#[cfg(feature = "enabled")]
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[cfg(feature = "enabled")]
+use crate::{Plot, PlotConfig, PlotEmit, PlotStyle};
+
+/// Emits the boolean state into the staircase plot, only when it
+/// changes, and returns the value back.
+///
+/// It is a shortcut for a static [`Toggle`] per call site, so the
+/// state could be reported every frame at the cost of a single atomic
+/// load.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn is_paused() -> bool { false }
+/// # fn update() {}
+/// loop {
+///     if !toggle_plot!("paused", is_paused()) {
+///         update();
+///     }
+///     frame!();
+/// }
+/// ```
+#[macro_export]
+macro_rules! toggle_plot {
+	($name:literal, $value:expr) => {
+		// match works as `let .. in` and is required to properly
+		// manage lifetimes.
+		match $value {
+			tmp => {
+				const _: () = $crate::limits::check_name_length($name);
+				static TOGGLE: $crate::Toggle = $crate::Toggle::from_static(
+					// SAFETY: We null-terminate the string.
+					unsafe {
+						std::ffi::CStr::from_bytes_with_nul_unchecked(concat!($name, '\0').as_bytes())
+					},
+				);
+				TOGGLE.set(tmp);
+				tmp
+			}
+		}
+	};
+}
+
+/// The state is not emitted yet.
+#[cfg(feature = "enabled")]
+const UNKNOWN: u8 = 2;
+
+/// Boolean state, e.g. vsync or pause, which is shown as a staircase
+/// plot of 0 & 1 next to the frame data.
+///
+/// The value is emitted only when it changes, so it is cheap to
+/// report it every frame. Refer to [`toggle_plot!`] for the literal
+/// names.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn render(_vsync: bool) {}
+/// let _tracy = start_capture();
+/// let vsync = Toggle::new("vsync on");
+/// for frame in 0.. {
+///     let on = frame % 1000 < 500;
+///     vsync.set(on);
+///     render(on);
+///     frame!();
+/// }
+/// ```
+///
+/// [`toggle_plot!`]: crate::toggle_plot!
+#[derive(Debug)]
+pub struct Toggle {
+	#[cfg(feature = "enabled")]
+	name:  &'static CStr,
+	#[cfg(feature = "enabled")]
+	state: AtomicU8,
+}
+
+impl Toggle {
+	/// Creates a toggle, which plot has the given name.
+	///
+	/// The name is interned and never freed, as Tracy requires it to
+	/// live until the end of the program, refer to
+	/// [`set_interner_cap`].
+	///
+	/// # Panics
+	///
+	/// Panics if the `name` contains a null byte.
+	///
+	/// [`set_interner_cap`]: crate::set_interner_cap
+	pub fn new(name: &str) -> Self {
+		assert!(!name.contains('\0'), "Toggle name should not contain null bytes.");
+		#[cfg(feature = "enabled")]
+		return Self::from_static(crate::intern::intern(name));
+
+		#[cfg(not(feature = "enabled"))]
+		Self {}
+	}
+
+	#[doc(hidden)]
+	#[cfg_attr(not(feature = "enabled"), allow(unused_variables))]
+	pub const fn from_static(name: &'static std::ffi::CStr) -> Self {
+		Self {
+			#[cfg(feature = "enabled")]
+			name,
+			#[cfg(feature = "enabled")]
+			state: AtomicU8::new(UNKNOWN),
+		}
+	}
+
+	/// Sets the state, which is emitted only if it has changed.
+	#[inline(always)]
+	pub fn set(&self, on: bool) {
+		#[cfg(feature = "enabled")]
+		if self.state.load(Ordering::Relaxed) != on as u8 {
+			self.changed(on);
+		}
+		#[cfg(not(feature = "enabled"))]
+		{
+			// Silences unused variable warning.
+			_ = on;
+		}
+	}
+
+	#[cfg(feature = "enabled")]
+	#[cold]
+	fn changed(&self, on: bool) {
+		if crate::muted() {
+			return;
+		}
+		let plot = match self.state.swap(on as u8, Ordering::Relaxed) {
+			state if state == on as u8 => return,
+			UNKNOWN => Plot::with_config(self.name, PlotConfig { style: PlotStyle::Staircase, ..Default::default() }),
+			_       => Plot::new(self.name),
+		};
+		plot.emit(on as i64);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn toggled_plots() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let vsync = Toggle::new("vsync on");
+		#[cfg(feature = "enabled")]
+		assert_eq!(vsync.state.load(Ordering::Relaxed), UNKNOWN);
+		vsync.set(true);
+		vsync.set(true);
+		#[cfg(feature = "enabled")]
+		assert_eq!(vsync.state.load(Ordering::Relaxed), 1);
+		vsync.set(false);
+		#[cfg(feature = "enabled")]
+		assert_eq!(vsync.state.load(Ordering::Relaxed), 0);
+		for paused in [false, true, true] {
+			assert_eq!(toggle_plot!("paused", paused), paused);
+		}
+	}
+}
@@ -0,0 +1,118 @@
+#[cfg(feature = "enabled")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::Zone;
+
+/// Begins a zone, which is linked to the parent zone of another
+/// thread via its [`ZoneToken`].
+///
+/// The arguments are the same as for [`zone!`], except for the token
+/// coming after the zone variable, if any.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn decode() -> Vec<u8> { vec![] }
+/// # fn upload(_: Vec<u8>) {}
+/// let (tx, rx) = std::sync::mpsc::channel();
+///
+/// std::thread::spawn(move || {
+///     zone!(producing, "decode");
+///     tx.send((producing.token(), decode())).unwrap();
+/// });
+///
+/// for (token, data) in rx {
+///     zone_with_parent!(token, "upload");
+///     upload(data);
+/// }
+/// ```
+///
+/// [`zone!`]: crate::zone!
+#[macro_export]
+macro_rules! zone_with_parent {
+	($var:ident, $token:expr, $name:literal $(, $color:expr)?) => {
+		$crate::zone!($var, $name $(, $color)?);
+		$var.set_parent(&$token);
+	};
+	($token:expr, $name:literal $(, $color:expr)?) => {
+		$crate::zone_with_parent!(_z, $token, $name $(, $color)?);
+	};
+}
+
+/// A token of the zone, which could be passed to another thread to
+/// link its zones to this one, refer to [`Zone::token`].
+///
+/// Tracy has no cross-thread parent & child relations, so they are
+/// approximated by zone texts in a standardized way: the parent zone
+/// gets `token #<id>` and the child zone gets `parent #<id> on
+/// <thread>`, where the thread is the name of the parent's thread.
+/// With `no-dynamic-strings` feature, only the id is attached to both
+/// as a number.
+#[derive(Debug, Clone)]
+pub struct ZoneToken {
+	#[cfg(feature = "enabled")]
+	id:     u64,
+	#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+	thread: Option<Box<str>>,
+}
+
+impl Zone {
+	/// Returns the token of the zone, which links zones of other
+	/// threads to it, refer to [`ZoneToken`].
+	///
+	/// Every call produces a new token.
+	pub fn token(&self) -> ZoneToken {
+		#[cfg(feature = "enabled")]
+		{
+			static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+			let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			{
+				self.text_fmt(format_args!("token #{id}"));
+				ZoneToken { id, thread: std::thread::current().name().map(Into::into) }
+			}
+			#[cfg(feature = "no-dynamic-strings")]
+			{
+				self.number(id);
+				ZoneToken { id }
+			}
+		}
+
+		#[cfg(not(feature = "enabled"))]
+		ZoneToken {}
+	}
+
+	/// Links the zone to the parent zone of another thread, refer to
+	/// [`ZoneToken`].
+	pub fn set_parent(&self, token: &ZoneToken) {
+		#[cfg(all(feature = "enabled", not(feature = "no-dynamic-strings")))]
+		match &token.thread {
+			Some(thread) => self.text_fmt(format_args!("parent #{} on {thread}", token.id)),
+			None         => self.text_fmt(format_args!("parent #{} on unnamed thread", token.id)),
+		}
+		#[cfg(all(feature = "enabled", feature = "no-dynamic-strings"))]
+		self.number(token.id);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::zone;
+
+	#[test]
+	fn cross_thread_parents() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		zone!(producing, "produce");
+		let tokens = [producing.token(), producing.token()];
+		#[cfg(feature = "enabled")]
+		assert_eq!(tokens[1].id, tokens[0].id + 1);
+		std::thread::spawn(move || {
+			zone_with_parent!(tokens[0], "consume");
+			zone_with_parent!(consuming, tokens[1].clone(), "consume", crate::Color::RED);
+			consuming.text("consumed");
+		}).join().unwrap();
+	}
+}
@@ -0,0 +1,110 @@
+//! Mirroring of frames and zones into the kernel trace buffer.
+//!
+//! When `trace-marker` feature is enabled on Linux, frame marks and
+//! zones are also written to the tracefs `trace_marker` file in the
+//! atrace format (`B|pid|name`, `E|pid` & `I|pid|name`), so `perf
+//! record -e ftrace:print`, `trace-cmd` or Perfetto sessions can be
+//! correlated with Tracy captures. Every marker is a syscall, so it
+//! is noticeably slower than the regular instrumentation.
+
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::raw::c_char;
+use std::sync::OnceLock;
+
+const PATHS: [&str; 2] = [
+	"/sys/kernel/tracing/trace_marker",
+	"/sys/kernel/debug/tracing/trace_marker",
+];
+
+static MARKER: OnceLock<Option<File>> = OnceLock::new();
+
+thread_local! {
+	// Every marker must be written via a single `write`, so it is
+	// formatted here first.
+	static BUFFER: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Opens the `trace_marker` file, if it wasn't opened yet.
+pub(crate) fn open() {
+	MARKER.get_or_init(|| {
+		let mut error = None;
+		for path in PATHS {
+			match OpenOptions::new().write(true).open(path) {
+				Ok(file) => return Some(file),
+				Err(e)   => error = Some(e),
+			}
+		}
+		if let Some(e) = error {
+			#[cfg(not(feature = "no-dynamic-strings"))]
+			crate::diagnostic(&format!("trace_marker is not available, markers are disabled: {e}"));
+			#[cfg(feature = "no-dynamic-strings")]
+			{
+				// Silences unused variable warning.
+				_ = e;
+				crate::diagnostic("trace_marker is not available, markers are disabled.");
+			}
+		}
+		None
+	});
+}
+
+fn write(f: impl FnOnce(&mut Vec<u8>)) {
+	let Some(Some(marker)) = MARKER.get() else {
+		return;
+	};
+	BUFFER.with_borrow_mut(|buffer| {
+		buffer.clear();
+		f(buffer);
+		// Markers are best-effort, there is nothing to do if the
+		// tracing is turned off in the meantime.
+		_ = (&*marker).write(buffer);
+	});
+}
+
+/// Mirrors a zone begin.
+///
+/// # Safety
+///
+/// `name` must be null-terminated.
+#[inline(always)]
+pub(crate) unsafe fn zone_begin(name: *const c_char) {
+	zone_begin_bytes(CStr::from_ptr(name).to_bytes());
+}
+
+/// Mirrors a zone begin, which name is known only at run-time.
+#[inline(always)]
+pub(crate) fn zone_begin_bytes(name: &[u8]) {
+	write(|buffer| {
+		_ = write!(buffer, "B|{}|", std::process::id());
+		buffer.extend_from_slice(name);
+	});
+}
+
+/// Mirrors a zone end.
+#[inline(always)]
+pub(crate) fn zone_end() {
+	write(|buffer| {
+		_ = write!(buffer, "E|{}", std::process::id());
+	});
+}
+
+/// Mirrors a frame mark of the given `kind` (`frame`, `frame start`
+/// or `frame end`). Null `name` stands for the main frame.
+///
+/// # Safety
+///
+/// Non-null `name` must be null-terminated.
+#[inline(always)]
+pub(crate) unsafe fn frame(kind: &str, name: *const c_char) {
+	write(|buffer| {
+		_ = write!(buffer, "I|{}|{kind}: ", std::process::id());
+		if name.is_null() {
+			buffer.extend_from_slice(b"main");
+		} else {
+			buffer.extend_from_slice(CStr::from_ptr(name).to_bytes());
+		}
+	});
+}
@@ -0,0 +1,228 @@
+#[cfg(feature = "enabled")]
+use std::cell::RefCell;
+#[cfg(feature = "enabled")]
+use std::fmt::{self, Write};
+
+#[cfg(feature = "enabled")]
+use tracing::field::{Field, Visit};
+#[cfg(feature = "enabled")]
+use tracing::span::{Attributes, Id, Record};
+#[cfg(feature = "enabled")]
+use tracing::{Event, Level};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Layer;
+#[cfg(feature = "enabled")]
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+#[cfg(feature = "enabled")]
+use crate::{MessageLevel, Zone};
+
+#[cfg(feature = "enabled")]
+thread_local! {
+	/// Zones of the spans entered on the current thread.
+	static ZONES: RefCell<Vec<(Id, Zone)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// `tracing` layer, which forwards spans & events to Tracy.
+///
+/// Every span is a zone, while it is entered, named after the span
+/// and pointing at its source location. Span fields are attached as
+/// `key=value` zone texts, the same way as [`Zone::field`] does.
+/// Every event is sent as a message, colored by its level, same as
+/// [`message!`] with a [`MessageLevel`] does, and formatted as
+/// `target: message key=value ...`. Levels compiled out via
+/// `max-level-*` features are filtered out.
+///
+/// Spans should be exited in the reverse order of entering on every
+/// thread, as zones can't overlap. It is always the case for the
+/// entered span guards and instrumented futures.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let subscriber = tracing_subscriber::registry().with(TracyLayer);
+/// tracing::subscriber::set_global_default(subscriber).unwrap();
+/// let _tracy = start_capture();
+///
+/// let _load = tracing::info_span!("load", path = "level.bin").entered();
+/// tracing::warn!(size = 0, "Level is empty.");
+/// ```
+///
+/// [`Zone::field`]: crate::Zone::field
+/// [`message!`]: crate::message!
+/// [`MessageLevel`]: crate::MessageLevel
+pub struct TracyLayer;
+
+impl<S> Layer<S> for TracyLayer
+where
+	S: Subscriber + for<'a> LookupSpan<'a>,
+{
+	#[cfg(feature = "enabled")]
+	fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+		if let Some(span) = ctx.span(id) {
+			let mut fields = SpanFields(Vec::new());
+			attrs.record(&mut fields);
+			span.extensions_mut().insert(fields);
+		}
+	}
+
+	#[cfg(feature = "enabled")]
+	fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+		if let Some(span) = ctx.span(id) {
+			if let Some(fields) = span.extensions_mut().get_mut::<SpanFields>() {
+				values.record(fields);
+			}
+		}
+	}
+
+	#[cfg(feature = "enabled")]
+	fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+		let Some(span) = ctx.span(id) else {
+			return;
+		};
+		let metadata = span.metadata();
+		let zone     = Zone::begin_at(
+			metadata.name(),
+			metadata.module_path().unwrap_or_default(),
+			metadata.file().unwrap_or_default(),
+			metadata.line().unwrap_or_default(),
+		);
+		if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+			for (key, value) in fields {
+				zone.field(key, value);
+			}
+		}
+		ZONES.with_borrow_mut(|zones| zones.push((id.clone(), zone)));
+	}
+
+	#[cfg(feature = "enabled")]
+	fn on_exit(&self, id: &Id, _ctx: Context<'_, S>) {
+		let zone = ZONES.with_borrow_mut(|zones| {
+			let entered = zones.iter().rposition(|(entered, _)| entered == id)?;
+			Some(zones.remove(entered))
+		});
+		// Ends the zone outside of the borrow.
+		drop(zone);
+	}
+
+	#[cfg(feature = "enabled")]
+	fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+		let metadata = event.metadata();
+		let level    = message_level(metadata.level());
+		if !level.is_enabled() {
+			return;
+		}
+		let mut text = EventText { message: String::new(), fields: String::new() };
+		event.record(&mut text);
+		let mut text = format!("{}: {}{}", metadata.target(), text.message, text.fields);
+		crate::details::truncate_message(&mut text);
+		crate::details::message_size_color(&text, level.color());
+	}
+}
+
+/// Fields of a span, which are attached to its zones.
+#[cfg(feature = "enabled")]
+struct SpanFields(Vec<(&'static str, String)>);
+
+#[cfg(feature = "enabled")]
+impl SpanFields {
+	fn set(&mut self, field: &Field, value: String) {
+		match self.0.iter_mut().find(|(key, _)| *key == field.name()) {
+			Some((_, v)) => *v = value,
+			None         => self.0.push((field.name(), value)),
+		}
+	}
+}
+
+#[cfg(feature = "enabled")]
+impl Visit for SpanFields {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.set(field, value.to_owned());
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		self.set(field, format!("{value:?}"));
+	}
+}
+
+/// Message & the other fields of an event.
+#[cfg(feature = "enabled")]
+struct EventText {
+	message: String,
+	fields:  String,
+}
+
+#[cfg(feature = "enabled")]
+impl Visit for EventText {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.record_debug(field, &format_args!("{value}"));
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		// Writing into a `String` never fails.
+		_ = if field.name() == "message" {
+			write!(self.message, "{value:?}")
+		} else {
+			write!(self.fields, " {}={value:?}", field.name())
+		};
+	}
+}
+
+#[cfg(feature = "enabled")]
+fn message_level(level: &Level) -> MessageLevel {
+	if *level == Level::ERROR {
+		MessageLevel::Error
+	} else if *level == Level::WARN {
+		MessageLevel::Warn
+	} else if *level == Level::INFO {
+		MessageLevel::Info
+	} else if *level == Level::DEBUG {
+		MessageLevel::Debug
+	} else {
+		MessageLevel::Trace
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use tracing_subscriber::layer::SubscriberExt;
+
+	use super::*;
+
+	fn traced() {
+		let span = tracing::info_span!("load", path = "level.bin", size = tracing::field::Empty);
+		let _load = span.enter();
+		span.record("size", 16);
+		tracing::warn!(target: "app::assets", size = 0, "{} is empty", "level.bin");
+		tracing::info_span!("decode").in_scope(|| tracing::error!(target: "app::assets", "failed"));
+	}
+
+	#[test]
+	fn tracing_layer() {
+		#[cfg(feature = "enabled")]
+		let _tracy     = crate::start_capture();
+		let subscriber = tracing_subscriber::registry().with(TracyLayer);
+		tracing::subscriber::with_default(subscriber, || {
+			traced();
+
+			#[cfg(feature = "enabled")]
+			{
+				use crate::record::Event::*;
+
+				assert_eq!(crate::record::record(traced), [
+					ZoneBegin("load".into()),
+					Message("app::assets: level.bin is empty size=0".into()),
+					ZoneBegin("decode".into()),
+					Message("app::assets: failed".into()),
+					ZoneEnd,
+					ZoneEnd,
+				]);
+				ZONES.with_borrow(|zones| assert!(zones.is_empty()));
+			}
+		});
+	}
+}
@@ -0,0 +1,187 @@
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+#[cfg(feature = "enabled")]
+use crate::{Color, Plot, PlotEmit};
+
+/// [`Arc`], which tracks its clones, so clone storms are seen in
+/// Tracy.
+///
+/// Huge shared values cloned every frame are hard to spot, as every
+/// clone is cheap on its own. Every named instance counts its clones &
+/// drops and plots the strong count as `<name>: strong count`. Once
+/// the strong count exceeds the threshold, a yellow message is
+/// emitted, which is repeated only after the count goes back to the
+/// threshold.
+///
+/// Clones & drops are counted the same way, even if the
+/// instrumentation is disabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn load_level() -> Vec<u8> { Vec::new() }
+/// let level = TrackedArc::with_threshold("level", load_level(), 64);
+/// let for_render = TrackedArc::clone(&level);
+/// assert_eq!(TrackedArc::strong_count(&level), 2);
+/// ```
+pub struct TrackedArc<T> {
+	shared: Arc<Shared<T>>,
+}
+
+struct Shared<T> {
+	name:      &'static str,
+	threshold: usize,
+	clones:    AtomicUsize,
+	drops:     AtomicUsize,
+	warned:    AtomicBool,
+	#[cfg(feature = "enabled")]
+	plot:      Plot,
+	value:     T,
+}
+
+impl<T> TrackedArc<T> {
+	/// Wraps the value, which is never reported as cloned too much.
+	///
+	/// The plot name is interned and never freed, as Tracy requires it
+	/// to live until the end of the program, refer to
+	/// [`set_interner_cap`].
+	///
+	/// [`set_interner_cap`]: crate::set_interner_cap
+	pub fn new(name: &'static str, value: T) -> Self {
+		Self::with_threshold(name, value, usize::MAX)
+	}
+
+	/// Wraps the value, which is reported, once its strong count
+	/// exceeds the `threshold`.
+	pub fn with_threshold(name: &'static str, value: T, threshold: usize) -> Self {
+		let this = Self {
+			shared: Arc::new(Shared {
+				name,
+				threshold,
+				clones: AtomicUsize::new(0),
+				drops:  AtomicUsize::new(0),
+				warned: AtomicBool::new(false),
+				#[cfg(feature = "enabled")]
+				plot:   Plot::new(crate::intern::intern(&format!("{name}: strong count"))),
+				value,
+			}),
+		};
+		this.track(1);
+		this
+	}
+
+	/// Returns the name of the instance.
+	pub fn name(this: &Self) -> &'static str {
+		this.shared.name
+	}
+
+	/// Returns the amount of the strong references.
+	pub fn strong_count(this: &Self) -> usize {
+		Arc::strong_count(&this.shared)
+	}
+
+	/// Returns the amount of clones made so far.
+	pub fn clones(this: &Self) -> usize {
+		this.shared.clones.load(Ordering::Relaxed)
+	}
+
+	/// Returns the amount of clones dropped so far.
+	pub fn drops(this: &Self) -> usize {
+		this.shared.drops.load(Ordering::Relaxed)
+	}
+
+	fn track(&self, count: usize) {
+		let shared = &self.shared;
+		#[cfg(feature = "enabled")]
+		shared.plot.emit(count as i64);
+
+		if count <= shared.threshold {
+			shared.warned.store(false, Ordering::Relaxed);
+		} else if !shared.warned.swap(true, Ordering::Relaxed) {
+			#[cfg(feature = "enabled")]
+			crate::details::message_size_color(
+				&format!("{}: {count} strong references, threshold is {}", shared.name, shared.threshold),
+				Color::YELLOW,
+			);
+		}
+	}
+}
+
+impl<T> Clone for TrackedArc<T> {
+	fn clone(&self) -> Self {
+		let clone = Self { shared: Arc::clone(&self.shared) };
+		self.shared.clones.fetch_add(1, Ordering::Relaxed);
+		self.track(Arc::strong_count(&self.shared));
+		clone
+	}
+}
+
+impl<T> Drop for TrackedArc<T> {
+	fn drop(&mut self) {
+		self.shared.drops.fetch_add(1, Ordering::Relaxed);
+		self.track(Arc::strong_count(&self.shared) - 1);
+	}
+}
+
+impl<T> Deref for TrackedArc<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		&self.shared.value
+	}
+}
+
+impl<T> AsRef<T> for TrackedArc<T> {
+	fn as_ref(&self) -> &T {
+		&self.shared.value
+	}
+}
+
+impl<T: fmt::Debug> fmt::Debug for TrackedArc<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(&self.shared.value, f)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn clone_storms() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let level = TrackedArc::with_threshold("test level", vec![0u8; 16], 2);
+		let storm = || {
+			let clones: Vec<_> = (0..3).map(|_| TrackedArc::clone(&level)).collect();
+			assert_eq!(TrackedArc::strong_count(&level), 4);
+			assert_eq!(clones[2].len(), 16);
+		};
+		storm();
+		assert_eq!(TrackedArc::name(&level), "test level");
+		assert_eq!(TrackedArc::clones(&level), 3);
+		assert_eq!(TrackedArc::drops(&level), 3);
+		assert_eq!(TrackedArc::strong_count(&level), 1);
+
+		#[cfg(feature = "enabled")]
+		{
+			use crate::record::Event::*;
+
+			let events = crate::record::record(storm);
+			let count  = |n| Plot("test level: strong count".into(), n as f64);
+			assert_eq!(events, [
+				count(2),
+				count(3),
+				Message("test level: 3 strong references, threshold is 2".into()),
+				count(4),
+				count(3),
+				count(2),
+				count(1),
+			]);
+		}
+	}
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+
+/// Tracy version.
+///
+/// Tracy viewer is able to connect only to the client of the same
+/// network protocol version, which usually changes with every
+/// release. Refer to [`CaptureOptions::expected_viewer_protocol`].
+///
+/// [`CaptureOptions::expected_viewer_protocol`]: crate::CaptureOptions::expected_viewer_protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TracyVersion {
+	/// Major version.
+	pub major: u32,
+	/// Minor version.
+	pub minor: u32,
+	/// Patch version.
+	pub patch: u32,
+}
+
+impl TracyVersion {
+	/// Version of the bundled Tracy client.
+	#[cfg(feature = "enabled")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "enabled")))]
+	pub const CLIENT: Self = Self::new(sys::TRACY_VERSION.0, sys::TRACY_VERSION.1, sys::TRACY_VERSION.2);
+
+	/// Network protocol version of the bundled Tracy client.
+	#[cfg(feature = "enabled")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "enabled")))]
+	pub const PROTOCOL: u32 = sys::TRACY_PROTOCOL_VERSION;
+
+	/// Creates a version from its parts.
+	pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+		Self { major, minor, patch }
+	}
+}
+
+impl fmt::Display for TracyVersion {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+	}
+}
+
+/// Complains, if the viewer of the expected protocol version can't
+/// connect to the bundled client. Returns `true`, if it can.
+#[cfg(feature = "enabled")]
+pub(crate) fn check_viewer(expected_protocol: u32) -> bool {
+	if expected_protocol == TracyVersion::PROTOCOL {
+		return true;
+	}
+
+	#[cfg(not(feature = "no-dynamic-strings"))]
+	crate::diagnostic(&format!(
+		"Tracy viewer protocol {expected_protocol} is expected, but the client {} uses protocol {}. \
+		 The viewer of the same protocol version is required to connect.",
+		TracyVersion::CLIENT,
+		TracyVersion::PROTOCOL,
+	));
+	#[cfg(feature = "no-dynamic-strings")]
+	crate::diagnostic(
+		"Tracy viewer of another protocol version is expected. \
+		 The viewer of the same protocol version as the client is required to connect."
+	);
+	false
+}
@@ -0,0 +1,132 @@
+//! Status of the hardware Vsync events capture.
+//!
+//! Tracy sets up the capture on its own and silently skips it, if
+//! something is missing, so the status is derived from the same
+//! conditions Tracy checks.
+
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+/// Status of the hardware Vsync events capture, refer to
+/// [`vsync_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum VsyncStatus {
+	/// Vsync events are captured.
+	Active,
+	/// `vsync` or `system-tracing` features are not enabled.
+	NotCompiled,
+	/// There is no running capture.
+	NotStarted,
+	/// The capture is disabled via `TRACY_NO_VSYNC_CAPTURE`
+	/// environment variable.
+	DisabledByEnv,
+	/// The capture is not supported on this platform.
+	Unsupported,
+	/// The process has no privileges to access the Vsync events,
+	/// e.g. it is not run as root.
+	NoPrivileges,
+	/// The status can't be determined, e.g. on Windows the capture
+	/// requires running as Administrator, which is not checked.
+	Unknown,
+}
+
+/// Returns whether the hardware Vsync events are captured.
+///
+/// The `vsync` feature silently does nothing if privileges are
+/// missing, so apps could warn the user or fall back to their own
+/// frame marks based on it. Tracy doesn't report the status, so it is
+/// derived from the conditions Tracy checks, when the capture starts.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// let _tracy = start_capture();
+/// if vsync_status() != VsyncStatus::Active {
+///     eprintln!("No vsync capture, monitors: {:?}", vsync_monitors());
+/// }
+/// ```
+pub fn vsync_status() -> VsyncStatus {
+	if !cfg!(all(feature = "enabled", feature = "vsync", feature = "system-tracing")) {
+		return VsyncStatus::NotCompiled;
+	}
+	#[cfg(feature = "enabled")]
+	if !crate::STARTED.load(std::sync::atomic::Ordering::Acquire) {
+		return VsyncStatus::NotStarted;
+	}
+	if std::env::var_os("TRACY_NO_VSYNC_CAPTURE").is_some_and(|v| v.as_encoded_bytes().starts_with(b"1")) {
+		return VsyncStatus::DisabledByEnv;
+	}
+
+	#[cfg(target_os = "linux")]
+	{
+		// Tracy reads the event id to subscribe to it, which is
+		// possible only with the access to debugfs.
+		const VBLANK_EVENT: &str = "/sys/kernel/debug/tracing/events/drm/drm_vblank_event/id";
+
+		match std::fs::read_to_string(VBLANK_EVENT) {
+			Ok(_)                                                      => VsyncStatus::Active,
+			Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => VsyncStatus::NoPrivileges,
+			// debugfs is readable only by root, so the event could be
+			// missing only for a privileged process.
+			Err(_) if Path::new("/sys/kernel/debug/tracing").exists()  => VsyncStatus::Unsupported,
+			Err(_)                                                     => VsyncStatus::NoPrivileges,
+		}
+	}
+	#[cfg(windows)]
+	{
+		VsyncStatus::Unknown
+	}
+	#[cfg(not(any(target_os = "linux", windows)))]
+	{
+		VsyncStatus::Unsupported
+	}
+}
+
+/// Returns the names of the connected monitors, which Vsync events
+/// could be captured for, e.g. `card0-HDMI-A-1`.
+///
+/// Tracy reports Vsync events per display controller, which usually
+/// matches a connected monitor. Monitors are enumerated only on
+/// Linux, on other platforms it is always empty.
+pub fn vsync_monitors() -> Vec<String> {
+	#[cfg(target_os = "linux")]
+	{
+		let Ok(connectors) = std::fs::read_dir("/sys/class/drm") else {
+			return Vec::new();
+		};
+		let mut monitors: Vec<_> = connectors
+			.flatten()
+			.filter(|connector| {
+				std::fs::read_to_string(connector.path().join("status"))
+					.is_ok_and(|status| status.trim() == "connected")
+			})
+			.filter_map(|connector| connector.file_name().into_string().ok())
+			.collect();
+		monitors.sort();
+		monitors
+	}
+	#[cfg(not(target_os = "linux"))]
+	{
+		Vec::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn vsync_queries() {
+		#[cfg(feature = "enabled")]
+		let _tracy = crate::start_capture();
+		let status = vsync_status();
+		if !cfg!(all(feature = "vsync", feature = "system-tracing")) {
+			assert_eq!(status, VsyncStatus::NotCompiled);
+		}
+		for monitor in vsync_monitors() {
+			assert!(!monitor.is_empty());
+		}
+	}
+}
@@ -0,0 +1,452 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Downscales the source with a single full-screen triangle.
+const SHADER: &str = r#"
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var linear: sampler;
+
+struct Vertex {
+	@builtin(position) position: vec4<f32>,
+	@location(0)       uv:       vec2<f32>,
+}
+
+@vertex
+fn vs(@builtin(vertex_index) index: u32) -> Vertex {
+	let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+	var vertex: Vertex;
+	vertex.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+	vertex.uv       = uv;
+	return vertex;
+}
+
+@fragment
+fn fs(vertex: Vertex) -> @location(0) vec4<f32> {
+	return vec4<f32>(textureSample(source, linear, vertex.uv).rgb, 1.0);
+}
+"#;
+
+/// Amount of readbacks in flight, as the GPU is usually up to 3
+/// frames behind.
+const READBACKS: usize = 3;
+
+/// States of a readback buffer.
+const FREE:    u8 = 0;
+const MAPPING: u8 = 1;
+const MAPPED:  u8 = 2;
+const FAILED:  u8 = 3;
+
+struct Readback {
+	buffer: wgpu::Buffer,
+	state:  Arc<AtomicU8>,
+	/// The frame the image was captured at.
+	frame:  u64,
+}
+
+/// The downscaled image & the pipeline rendering into it, which
+/// depend on the source format.
+struct Target {
+	source:   wgpu::TextureFormat,
+	texture:  wgpu::Texture,
+	pipeline: wgpu::RenderPipeline,
+}
+
+/// Captures the frame images from `wgpu` textures, e.g. the surface
+/// ones, refer to [`emit_frame_image`].
+///
+/// Every captured texture is downscaled on the GPU and read back
+/// asynchronously, so the rendering is never stalled. Images are
+/// emitted with the amount of frames they lag behind, once the GPU is
+/// done with them. Nothing is captured, unless the Tracy server is
+/// connected, and at most 3 images are in flight, so the capture is
+/// throttled by the GPU itself. Use [`WgpuFrameImages::with_interval`]
+/// to throttle it even more.
+///
+/// The captured texture must be either sampled or copied, so the
+/// surface must be configured with [`TextureUsages::TEXTURE_BINDING`]
+/// or [`TextureUsages::COPY_SRC`] in addition to the usual
+/// [`TextureUsages::RENDER_ATTACHMENT`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # fn render(_: &wgpu::Texture) {}
+/// # fn run(device: &wgpu::Device, queue: &wgpu::Queue, surface: &wgpu::Surface, mut config: wgpu::SurfaceConfiguration) {
+/// config.usage |= wgpu::TextureUsages::COPY_SRC;
+/// surface.configure(device, &config);
+///
+/// let mut images = WgpuFrameImages::new(device, queue, 320, 180);
+/// loop {
+///     let wgpu::CurrentSurfaceTexture::Success(frame) = surface.get_current_texture() else {
+///         continue;
+///     };
+///     render(&frame.texture);
+///     images.capture(&frame.texture);
+///     frame.present();
+///     frame!();
+/// }
+/// # }
+/// ```
+///
+/// [`emit_frame_image`]: crate::emit_frame_image
+/// [`TextureUsages::TEXTURE_BINDING`]: wgpu::TextureUsages::TEXTURE_BINDING
+/// [`TextureUsages::COPY_SRC`]: wgpu::TextureUsages::COPY_SRC
+/// [`TextureUsages::RENDER_ATTACHMENT`]: wgpu::TextureUsages::RENDER_ATTACHMENT
+pub struct WgpuFrameImages {
+	device:    wgpu::Device,
+	queue:     wgpu::Queue,
+	width:     u16,
+	height:    u16,
+	/// Bytes per row of the readback, which are aligned as `wgpu`
+	/// requires.
+	row_bytes: u32,
+	layout:    wgpu::BindGroupLayout,
+	pipeline:  wgpu::PipelineLayout,
+	shader:    wgpu::ShaderModule,
+	sampler:   wgpu::Sampler,
+	target:    Option<Target>,
+	/// Sampled copy of the sources, which can't be sampled directly.
+	copy:      Option<wgpu::Texture>,
+	readbacks: Vec<Readback>,
+	interval:  u64,
+	frame:     u64,
+	pixels:    Vec<u8>,
+}
+
+impl WgpuFrameImages {
+	/// Creates the frame images of the given size, e.g. 320x180.
+	///
+	/// # Panics
+	///
+	/// Panics if the dimensions are zero or not divisible by 4.
+	pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, width: u16, height: u16) -> Self {
+		assert!(
+			width > 0 && height > 0 && width.is_multiple_of(4) && height.is_multiple_of(4),
+			"Frame image dimensions should be non-zero & divisible by 4.",
+		);
+
+		let row_bytes = (width as u32 * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+		let layout    = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label:   Some("tracy-gizmos frame image"),
+			entries: &[
+				wgpu::BindGroupLayoutEntry {
+					binding:    0,
+					visibility: wgpu::ShaderStages::FRAGMENT,
+					ty:         wgpu::BindingType::Texture {
+						sample_type:    wgpu::TextureSampleType::Float { filterable: true },
+						view_dimension: wgpu::TextureViewDimension::D2,
+						multisampled:   false,
+					},
+					count:      None,
+				},
+				wgpu::BindGroupLayoutEntry {
+					binding:    1,
+					visibility: wgpu::ShaderStages::FRAGMENT,
+					ty:         wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+					count:      None,
+				},
+			],
+		});
+		let pipeline = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+			label:              Some("tracy-gizmos frame image"),
+			bind_group_layouts: &[Some(&layout)],
+			immediate_size:     0,
+		});
+		let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+			label:  Some("tracy-gizmos frame image"),
+			source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+		});
+		let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+			label:      Some("tracy-gizmos frame image"),
+			mag_filter: wgpu::FilterMode::Linear,
+			min_filter: wgpu::FilterMode::Linear,
+			..Default::default()
+		});
+		let readbacks = (0..READBACKS)
+			.map(|_| Readback {
+				buffer: device.create_buffer(&wgpu::BufferDescriptor {
+					label:              Some("tracy-gizmos frame image"),
+					size:               row_bytes as u64 * height as u64,
+					usage:              wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+					mapped_at_creation: false,
+				}),
+				state:  Arc::new(AtomicU8::new(FREE)),
+				frame:  0,
+			})
+			.collect();
+
+		Self {
+			device: device.clone(),
+			queue:  queue.clone(),
+			width,
+			height,
+			row_bytes,
+			layout,
+			pipeline,
+			shader,
+			sampler,
+			target: None,
+			copy:   None,
+			readbacks,
+			interval: 1,
+			frame:    0,
+			pixels:   Vec::new(),
+		}
+	}
+
+	/// Captures only every `interval`-th frame, to lower the
+	/// overhead even more.
+	///
+	/// # Panics
+	///
+	/// Panics if the `interval` is zero.
+	pub fn with_interval(mut self, interval: u32) -> Self {
+		assert!(interval > 0, "Frame image interval should be non-zero.");
+		self.interval = interval as u64;
+		self
+	}
+
+	/// Captures the texture, which contains the current frame, and
+	/// emits the images, which are read back by now.
+	///
+	/// It is meant to be called once per frame, after the frame is
+	/// rendered & submitted, but before it is presented.
+	///
+	/// # Panics
+	///
+	/// Panics if the texture could be neither sampled nor copied.
+	pub fn capture(&mut self, source: &wgpu::Texture) {
+		self.frame += 1;
+		// Nobody would see the images otherwise.
+		#[cfg(feature = "enabled")]
+		// SAFETY: The capture is running, unless muted.
+		let wanted = crate::emission().is_some_and(|_emission| unsafe { sys::___tracy_connected() } != 0);
+		#[cfg(not(feature = "enabled"))]
+		let wanted = false;
+
+		if wanted && self.frame.is_multiple_of(self.interval) {
+			self.read_back(source);
+		}
+		self.emit_ready();
+	}
+
+	/// Downscales the texture & starts reading it back, unless there
+	/// are too many readbacks in flight.
+	fn read_back(&mut self, source: &wgpu::Texture) {
+		let Some(index) = self.readbacks.iter().position(|r| r.state.load(Ordering::Acquire) == FREE) else {
+			return;
+		};
+
+		let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+			label: Some("tracy-gizmos frame image"),
+		});
+		let view = if source.usage().contains(wgpu::TextureUsages::TEXTURE_BINDING) {
+			source.create_view(&Default::default())
+		} else {
+			assert!(
+				source.usage().contains(wgpu::TextureUsages::COPY_SRC),
+				"Frame image source should have either TEXTURE_BINDING or COPY_SRC usage.",
+			);
+			let copy = self.copy(source);
+			encoder.copy_texture_to_texture(source.as_image_copy(), copy.as_image_copy(), source.size());
+			copy.create_view(&Default::default())
+		};
+
+		self.prepare_target(source.format());
+		let target     = self.target.as_ref().unwrap();
+		let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label:   Some("tracy-gizmos frame image"),
+			layout:  &self.layout,
+			entries: &[
+				wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+				wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+			],
+		});
+		{
+			let target_view = target.texture.create_view(&Default::default());
+			let mut pass    = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+				label:                    Some("tracy-gizmos frame image"),
+				color_attachments:        &[Some(wgpu::RenderPassColorAttachment {
+					view:           &target_view,
+					depth_slice:    None,
+					resolve_target: None,
+					ops:            wgpu::Operations {
+						load:  wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+						store: wgpu::StoreOp::Store,
+					},
+				})],
+				depth_stencil_attachment: None,
+				timestamp_writes:         None,
+				occlusion_query_set:      None,
+				multiview_mask:           None,
+			});
+			pass.set_pipeline(&target.pipeline);
+			pass.set_bind_group(0, &bind_group, &[]);
+			pass.draw(0..3, 0..1);
+		}
+
+		let readback = &mut self.readbacks[index];
+		encoder.copy_texture_to_buffer(
+			target.texture.as_image_copy(),
+			wgpu::TexelCopyBufferInfo {
+				buffer: &readback.buffer,
+				layout: wgpu::TexelCopyBufferLayout {
+					offset:         0,
+					bytes_per_row:  Some(self.row_bytes),
+					rows_per_image: None,
+				},
+			},
+			target.texture.size(),
+		);
+		self.queue.submit([encoder.finish()]);
+
+		readback.frame = self.frame;
+		readback.state.store(MAPPING, Ordering::Release);
+		let state = Arc::clone(&readback.state);
+		readback.buffer.map_async(wgpu::MapMode::Read, .., move |result| {
+			state.store(if result.is_ok() { MAPPED } else { FAILED }, Ordering::Release);
+		});
+	}
+
+	/// Emits the images, which are read back, from the oldest one.
+	fn emit_ready(&mut self) {
+		// Mapping callbacks are invoked only while polling.
+		_ = self.device.poll(wgpu::PollType::Poll);
+
+		self.readbacks.sort_by_key(|r| r.frame);
+		for readback in &self.readbacks {
+			match readback.state.load(Ordering::Acquire) {
+				MAPPED => {
+					let row = self.width as usize * 4;
+					self.pixels.clear();
+					{
+						let mapped = readback.buffer.get_mapped_range(..);
+						for line in mapped.chunks(self.row_bytes as usize) {
+							self.pixels.extend_from_slice(&line[..row]);
+						}
+					}
+					readback.buffer.unmap();
+					readback.state.store(FREE, Ordering::Release);
+
+					let offset = (self.frame - readback.frame).min(u8::MAX as u64) as u8;
+					crate::emit_frame_image(&self.pixels, self.width, self.height, offset, false);
+				},
+				FAILED => readback.state.store(FREE, Ordering::Release),
+				_      => {},
+			}
+		}
+	}
+
+	/// Returns the sampled texture for the copies of the source.
+	fn copy(&mut self, source: &wgpu::Texture) -> &wgpu::Texture {
+		let stale = self.copy.as_ref().is_none_or(|copy| copy.size() != source.size() || copy.format() != source.format());
+		if stale {
+			self.copy = Some(self.device.create_texture(&wgpu::TextureDescriptor {
+				label:           Some("tracy-gizmos frame image copy"),
+				size:            source.size(),
+				mip_level_count: 1,
+				sample_count:    1,
+				dimension:       wgpu::TextureDimension::D2,
+				format:          source.format(),
+				usage:           wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+				view_formats:    &[],
+			}));
+		}
+		self.copy.as_ref().unwrap()
+	}
+
+	/// Creates the downscaled image & the pipeline for the given
+	/// source format, unless they are created already.
+	fn prepare_target(&mut self, source: wgpu::TextureFormat) {
+		if self.target.as_ref().is_none_or(|target| target.source != source) {
+			// sRGB sources are decoded when sampled, so they are
+			// encoded back the same way.
+			let format = if source.is_srgb() {
+				wgpu::TextureFormat::Rgba8UnormSrgb
+			} else {
+				wgpu::TextureFormat::Rgba8Unorm
+			};
+			let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+				label:           Some("tracy-gizmos frame image"),
+				size:            wgpu::Extent3d {
+					width:                 self.width  as u32,
+					height:                self.height as u32,
+					depth_or_array_layers: 1,
+				},
+				mip_level_count: 1,
+				sample_count:    1,
+				dimension:       wgpu::TextureDimension::D2,
+				format,
+				usage:           wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+				view_formats:    &[],
+			});
+			let pipeline = self.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+				label:          Some("tracy-gizmos frame image"),
+				layout:         Some(&self.pipeline),
+				vertex:         wgpu::VertexState {
+					module:              &self.shader,
+					entry_point:         Some("vs"),
+					compilation_options: Default::default(),
+					buffers:             &[],
+				},
+				primitive:      Default::default(),
+				depth_stencil:  None,
+				multisample:    Default::default(),
+				fragment:       Some(wgpu::FragmentState {
+					module:              &self.shader,
+					entry_point:         Some("fs"),
+					compilation_options: Default::default(),
+					targets:             &[Some(format.into())],
+				}),
+				multiview_mask: None,
+				cache:          None,
+			});
+			self.target = Some(Target { source, texture, pipeline });
+		}
+	}
+}
+
+// Stub devices need `wgpu/noop` feature, so the tests are run via
+// `cargo test -F wgpu,wgpu/noop`.
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wgpu_frame_images() {
+		let (device, queue) = wgpu::Device::noop(&Default::default());
+		let mut images      = WgpuFrameImages::new(&device, &queue, 32, 16).with_interval(2);
+
+		for (format, usage) in [
+			(wgpu::TextureFormat::Bgra8UnormSrgb, wgpu::TextureUsages::COPY_SRC),
+			(wgpu::TextureFormat::Rgba8Unorm,     wgpu::TextureUsages::TEXTURE_BINDING),
+		] {
+			let source = device.create_texture(&wgpu::TextureDescriptor {
+				label:           None,
+				size:            wgpu::Extent3d { width: 100, height: 60, depth_or_array_layers: 1 },
+				mip_level_count: 1,
+				sample_count:    1,
+				dimension:       wgpu::TextureDimension::D2,
+				format,
+				usage:           usage | wgpu::TextureUsages::RENDER_ATTACHMENT,
+				view_formats:    &[],
+			});
+			for _ in 0..READBACKS + 1 {
+				images.read_back(&source);
+			}
+			images.capture(&source);
+			images.emit_ready();
+			assert!(images.readbacks.iter().all(|r| r.state.load(Ordering::Acquire) == FREE));
+			assert_eq!(images.pixels.len(), 32 * 16 * 4);
+		}
+		assert!(images.target.is_some_and(|target| target.source == wgpu::TextureFormat::Rgba8Unorm));
+	}
+
+	#[test]
+	#[should_panic(expected = "divisible by 4")]
+	fn unaligned_wgpu_frame_images() {
+		let (device, queue) = wgpu::Device::noop(&Default::default());
+		WgpuFrameImages::new(&device, &queue, 30, 16);
+	}
+}
@@ -0,0 +1,152 @@
+use winit::application::ApplicationHandler;
+use winit::event::{DeviceEvent, DeviceId, StartCause, WindowEvent};
+use winit::event_loop::ActiveEventLoop;
+use winit::window::WindowId;
+
+/// `winit` application adapter, which marks the main frames.
+///
+/// Every [`WindowEvent::RedrawRequested`] is handled inside of the
+/// `present` zone and the main frame end is marked once the event
+/// loop is about to wait, if any window was redrawn. So windowed apps
+/// get frame boundaries matching the redraws without placing
+/// [`frame!`] by hand. Every other event is just forwarded to the
+/// wrapped application.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tracy_gizmos::*;
+/// # use winit::application::ApplicationHandler;
+/// # use winit::event::WindowEvent;
+/// # use winit::event_loop::{ActiveEventLoop, EventLoop};
+/// # use winit::window::WindowId;
+/// # struct App;
+/// # impl ApplicationHandler for App {
+/// #     fn resumed(&mut self, _: &ActiveEventLoop) {}
+/// #     fn window_event(&mut self, _: &ActiveEventLoop, _: WindowId, _: WindowEvent) {}
+/// # }
+/// let _tracy = start_capture();
+/// let event_loop = EventLoop::new().unwrap();
+/// event_loop.run_app(&mut TracyApp::new(App)).unwrap();
+/// ```
+///
+/// [`frame!`]: crate::frame!
+pub struct TracyApp<A> {
+	app:     A,
+	redrawn: bool,
+}
+
+impl<A> TracyApp<A> {
+	/// Wraps the application.
+	pub const fn new(app: A) -> Self {
+		Self { app, redrawn: false }
+	}
+
+	/// Returns the wrapped application.
+	pub fn get_ref(&self) -> &A {
+		&self.app
+	}
+
+	/// Returns the wrapped application.
+	pub fn get_mut(&mut self) -> &mut A {
+		&mut self.app
+	}
+
+	/// Unwraps the application.
+	pub fn into_inner(self) -> A {
+		self.app
+	}
+
+	// Documented macro variants are picked up by rustdoc, even if the
+	// instrumentation is disabled, but they need `enabled` feature.
+	fn present(&mut self, redraw: impl FnOnce(&mut A)) {
+		#[cfg(any(feature = "enabled", not(doc)))]
+		crate::zone!("present");
+		redraw(&mut self.app);
+		self.redrawn = true;
+	}
+
+	fn frame(&mut self) {
+		if std::mem::take(&mut self.redrawn) {
+			#[cfg(any(feature = "enabled", not(doc)))]
+			crate::frame!();
+		}
+	}
+}
+
+impl<T: 'static, A: ApplicationHandler<T>> ApplicationHandler<T> for TracyApp<A> {
+	fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+		self.app.new_events(event_loop, cause);
+	}
+
+	fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+		self.app.resumed(event_loop);
+	}
+
+	fn user_event(&mut self, event_loop: &ActiveEventLoop, event: T) {
+		self.app.user_event(event_loop, event);
+	}
+
+	fn window_event(&mut self, event_loop: &ActiveEventLoop, window_id: WindowId, event: WindowEvent) {
+		if matches!(event, WindowEvent::RedrawRequested) {
+			self.present(|app| app.window_event(event_loop, window_id, event));
+		} else {
+			self.app.window_event(event_loop, window_id, event);
+		}
+	}
+
+	fn device_event(&mut self, event_loop: &ActiveEventLoop, device_id: DeviceId, event: DeviceEvent) {
+		self.app.device_event(event_loop, device_id, event);
+	}
+
+	fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+		self.app.about_to_wait(event_loop);
+		self.frame();
+	}
+
+	fn suspended(&mut self, event_loop: &ActiveEventLoop) {
+		self.app.suspended(event_loop);
+	}
+
+	fn exiting(&mut self, event_loop: &ActiveEventLoop) {
+		self.app.exiting(event_loop);
+	}
+
+	fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
+		self.app.memory_warning(event_loop);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn redraws_are_framed() {
+		let mut app = TracyApp::new(0);
+		app.frame();
+		app.present(|redraws| *redraws += 1);
+		app.present(|redraws| *redraws += 1);
+		assert_eq!(*app.get_ref(), 2);
+		*app.get_mut() = 0;
+
+		#[cfg(feature = "enabled")]
+		{
+			use crate::record::Event::*;
+
+			let events = crate::record::record(|| {
+				app.frame();
+				app.frame();
+				app.present(|redraws| *redraws += 1);
+				app.frame();
+			});
+			assert_eq!(events, [
+				FrameMark(None),
+				ZoneBegin("present".into()),
+				ZoneEnd,
+				FrameMark(None),
+			]);
+			assert_eq!(app.into_inner(), 1);
+		}
+	}
+}
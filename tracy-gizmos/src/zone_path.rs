@@ -0,0 +1,124 @@
+//! Tracking of the currently active zones per thread.
+//!
+//! When `zone-path` feature is enabled, every active zone is recorded
+//! in a thread-local stack, so it is possible to describe where
+//! something has happened, e.g. `main > load > open`. Otherwise, the
+//! tracking is compiled out completely.
+
+use std::os::raw::c_char;
+#[cfg(feature = "zone-path")]
+use std::cell::RefCell;
+#[cfg(all(feature = "zone-path", any(feature = "zone-end-callstack", not(feature = "no-dynamic-strings"))))]
+use std::ffi::CStr;
+
+#[cfg(feature = "zone-path")]
+thread_local! {
+	static PATH: RefCell<Vec<Name>> = const { RefCell::new(Vec::new()) };
+}
+
+#[cfg(feature = "zone-path")]
+// Names are read only when the path is formatted.
+#[cfg_attr(feature = "no-dynamic-strings", allow(dead_code))]
+enum Name {
+	/// Null-terminated name, which outlives the zone.
+	Static(*const c_char),
+	/// Copy of the name, which is known only at run-time.
+	Dynamic(Box<str>),
+}
+
+/// Records that the zone is entered.
+///
+/// `name` must be null-terminated and outlive the zone.
+#[inline(always)]
+pub(crate) fn enter(name: *const c_char, ctx: sys::TracyCZoneCtx) {
+	#[cfg(feature = "zone-path")]
+	if ctx.active != 0 {
+		PATH.with_borrow_mut(|path| path.push(Name::Static(name)));
+	}
+	#[cfg(not(feature = "zone-path"))]
+	{
+		// Silences unused variable warnings.
+		_ = (name, ctx);
+	}
+}
+
+/// Records that the zone with a run-time name is entered.
+///
+/// The name is copied only if it is tracked.
+#[inline(always)]
+pub(crate) fn enter_dynamic(name: &str, ctx: sys::TracyCZoneCtx) {
+	#[cfg(feature = "zone-path")]
+	if ctx.active != 0 {
+		PATH.with_borrow_mut(|path| path.push(Name::Dynamic(name.into())));
+	}
+	#[cfg(not(feature = "zone-path"))]
+	{
+		// Silences unused variable warnings.
+		_ = (name, ctx);
+	}
+}
+
+/// Records that the zone is left.
+#[inline(always)]
+pub(crate) fn leave(ctx: sys::TracyCZoneCtx) {
+	#[cfg(feature = "zone-path")]
+	if ctx.active != 0 {
+		PATH.with_borrow_mut(|path| path.pop());
+	}
+	#[cfg(not(feature = "zone-path"))]
+	{
+		// Silences unused variable warning.
+		_ = ctx;
+	}
+}
+
+/// Writes `<name> end` of the current thread's innermost active zone
+/// into the buffer, cutting too long names, and returns it, or
+/// `None` if there are no active zones.
+#[cfg(feature = "zone-end-callstack")]
+pub(crate) fn innermost_end<const N: usize>(buf: &mut [u8; N]) -> Option<&str> {
+	const END: &str = " end";
+	let len = PATH.with_borrow(|path| {
+		let name = match path.last()? {
+			// SAFETY: Static zone names are always null-terminated and
+			// outlive the zone.
+			Name::Static(name)  => unsafe { CStr::from_ptr(*name) }.to_string_lossy(),
+			Name::Dynamic(name) => name.as_ref().into(),
+		};
+		let mut len = name.len().min(N - END.len());
+		while !name.is_char_boundary(len) {
+			len -= 1;
+		}
+		buf[..len].copy_from_slice(&name.as_bytes()[..len]);
+		buf[len..len + END.len()].copy_from_slice(END.as_bytes());
+		Some(len + END.len())
+	})?;
+	// SAFETY: The name is cut at the char boundary.
+	Some(unsafe { std::str::from_utf8_unchecked(&buf[..len]) })
+}
+
+/// Returns the current thread's active zones as `outer > inner`, or
+/// `None` if there are no active zones or they are not tracked.
+#[cfg(not(feature = "no-dynamic-strings"))]
+pub(crate) fn current() -> Option<String> {
+	#[cfg(feature = "zone-path")]
+	{
+		PATH.with_borrow(|path| {
+			if path.is_empty() {
+				return None;
+			}
+			let names: Vec<_> = path
+				.iter()
+				.map(|name| match name {
+					// SAFETY: Static zone names are always
+					// null-terminated and outlive the zone.
+					Name::Static(name)  => unsafe { CStr::from_ptr(*name) }.to_string_lossy(),
+					Name::Dynamic(name) => name.as_ref().into(),
+				})
+				.collect();
+			Some(names.join(" > "))
+		})
+	}
+	#[cfg(not(feature = "zone-path"))]
+	None
+}